@@ -1,9 +1,30 @@
 //! Icinga API
 
 pub mod action;
+#[cfg(feature = "client")]
+pub mod cassette;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "client")]
+pub mod core;
+pub mod generic;
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+mod limit;
+#[cfg(feature = "client")]
+pub mod middleware;
 pub mod query;
+#[cfg(feature = "client")]
+pub mod spool;
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+mod stream;
+#[cfg(feature = "async-client")]
+pub mod submitter;
+#[cfg(feature = "client")]
+pub mod transport;
 
-#[cfg(feature = "async")]
+#[cfg(feature = "async-client")]
 pub mod async_client;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-client")]
 pub mod blocking;
+#[cfg(feature = "tower")]
+pub mod service;
@@ -9,7 +9,7 @@ use crate::types::action::StatusResponse;
 use crate::types::enums::object_type::IcingaObjectType;
 use crate::types::filter::IcingaFilter;
 use crate::types::query::ResultsWrapper;
-use crate::types::rest::{RestApiEndpoint, RestApiResponse};
+use crate::types::rest::{HttpMethod, RestApiEndpoint, RestApiResponse};
 
 /// REST API Endpoint for the acknowledge-problem call
 #[derive(Debug, Clone, derive_builder::Builder, Serialize, Deserialize)]
@@ -77,8 +77,8 @@ impl AcknowledgeProblemBuilder {
 impl RestApiEndpoint for AcknowledgeProblem {
     type RequestBody = Self;
 
-    fn method(&self) -> Result<reqwest::Method, crate::error::Error> {
-        Ok(reqwest::Method::POST)
+    fn method(&self) -> Result<HttpMethod, crate::error::Error> {
+        Ok(HttpMethod::Post)
     }
 
     fn url(&self, base_url: &url::Url) -> Result<url::Url, crate::error::Error> {
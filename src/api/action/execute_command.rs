@@ -11,7 +11,7 @@ use crate::types::action::ExecuteCommandResponse;
 use crate::types::enums::command_type::IcingaCommandType;
 use crate::types::names::IcingaUserName;
 use crate::types::query::ResultsWrapper;
-use crate::types::rest::{RestApiEndpoint, RestApiResponse};
+use crate::types::rest::{HttpMethod, RestApiEndpoint, RestApiResponse};
 
 /// REST API Endpoint for the execute-command call
 #[derive(Debug, Clone, derive_builder::Builder, Serialize, Deserialize)]
@@ -50,8 +50,8 @@ impl ExecuteCommand {
 impl RestApiEndpoint for ExecuteCommand {
     type RequestBody = Self;
 
-    fn method(&self) -> Result<reqwest::Method, crate::error::Error> {
-        Ok(reqwest::Method::POST)
+    fn method(&self) -> Result<HttpMethod, crate::error::Error> {
+        Ok(HttpMethod::Post)
     }
 
     fn url(&self, base_url: &url::Url) -> Result<url::Url, crate::error::Error> {
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::action::GenerateTicketResponse;
 use crate::types::query::ResultsWrapper;
-use crate::types::rest::{RestApiEndpoint, RestApiResponse};
+use crate::types::rest::{HttpMethod, RestApiEndpoint, RestApiResponse};
 
 /// REST API Endpoint for the generate-ticket call
 #[derive(Debug, Clone, derive_builder::Builder, Serialize, Deserialize)]
@@ -29,8 +29,8 @@ impl GenerateTicket {
 impl RestApiEndpoint for GenerateTicket {
     type RequestBody = Self;
 
-    fn method(&self) -> Result<reqwest::Method, crate::error::Error> {
-        Ok(reqwest::Method::POST)
+    fn method(&self) -> Result<HttpMethod, crate::error::Error> {
+        Ok(HttpMethod::Post)
     }
 
     fn url(&self, base_url: &url::Url) -> Result<url::Url, crate::error::Error> {
@@ -10,25 +10,39 @@ use crate::serde::{
 };
 use crate::types::action::StatusResponse;
 use crate::types::common::{command::IcingaCommandLine, performance_data::IcingaPerformanceData};
-use crate::types::enums::object_type::IcingaObjectType;
+use crate::types::enums::{
+    host_or_service_state::IcingaHostOrServiceState, host_state::IcingaHostState,
+    object_type::IcingaObjectType, service_state::IcingaServiceState,
+};
 use crate::types::filter::IcingaFilter;
 use crate::types::query::ResultsWrapper;
-use crate::types::rest::{RestApiEndpoint, RestApiResponse};
+use crate::types::rest::{HttpMethod, RestApiEndpoint, RestApiResponse};
 
 /// REST API Endpoint for the process-check-result call
+///
+/// when deserialized (e.g. from a spool) the numeric exit status is turned
+/// back into a typed one using the object type of the filter
 #[derive(Debug, Clone, derive_builder::Builder, Serialize, Deserialize)]
 #[builder(
     build_fn(error = "crate::error::Error", validate = "Self::validate"),
     derive(Debug)
 )]
+#[serde(from = "ProcessCheckResultFields")]
 pub struct ProcessCheckResult {
-    ///  For services: 0=OK, 1=WARNING, 2=CRITICAL, 3=UNKNOWN
-    ///  For hosts: 0=UP, 1=DOWN
-    pub exit_status: u64,
+    /// the state of the host or service
+    ///
+    /// use `try_exit_status` to set it from an [IcingaHostState] or
+    /// [IcingaServiceState]
+    #[builder(try_setter, setter(into))]
+    pub exit_status: ProcessCheckResultExitStatus,
     /// the plugin output without the performance data
     pub plugin_output: String,
     /// the performance data
+    ///
+    /// structured values are sent in their plain-text form since Icinga
+    /// expects an array of strings
     #[builder(default)]
+    #[serde(serialize_with = "serialize_performance_data_as_strings")]
     pub performance_data: Option<Vec<IcingaPerformanceData>>,
     /// the check command
     #[builder(default)]
@@ -60,6 +74,76 @@ pub struct ProcessCheckResult {
     filter: Option<IcingaFilter>,
 }
 
+/// the fields of a [ProcessCheckResult] as deserialized, before the exit
+/// status is typed
+#[derive(Debug, Deserialize)]
+struct ProcessCheckResultFields {
+    /// the numeric exit status
+    exit_status: u8,
+    /// the plugin output without the performance data
+    plugin_output: String,
+    /// the performance data
+    #[serde(default)]
+    performance_data: Option<Vec<IcingaPerformanceData>>,
+    /// the check command
+    #[serde(default)]
+    check_command: Option<IcingaCommandLine>,
+    /// usually the name of the command endpoint
+    #[serde(default)]
+    check_source: Option<String>,
+    /// the start time of the check command execution
+    #[serde(default, deserialize_with = "deserialize_optional_icinga_timestamp")]
+    execution_start: Option<time::OffsetDateTime>,
+    /// the end time of the check command execution
+    #[serde(default, deserialize_with = "deserialize_optional_icinga_timestamp")]
+    execution_end: Option<time::OffsetDateTime>,
+    /// Time-to-live duration in seconds for this check result
+    #[serde(default, deserialize_with = "deserialize_optional_seconds_as_duration")]
+    ttl: Option<time::Duration>,
+    /// filter to target which host and/or service this check result applies to
+    #[serde(flatten)]
+    filter: Option<IcingaFilter>,
+}
+
+impl From<ProcessCheckResultFields> for ProcessCheckResult {
+    fn from(value: ProcessCheckResultFields) -> Self {
+        let exit_status = value.filter.as_ref().map_or(
+            ProcessCheckResultExitStatus::Raw(value.exit_status),
+            |filter| ProcessCheckResultExitStatus::from_code(value.exit_status, filter.object_type),
+        );
+        Self {
+            exit_status,
+            plugin_output: value.plugin_output,
+            performance_data: value.performance_data,
+            check_command: value.check_command,
+            check_source: value.check_source,
+            execution_start: value.execution_start,
+            execution_end: value.execution_end,
+            ttl: value.ttl,
+            filter: value.filter,
+        }
+    }
+}
+
+/// serialize performance data as the array of plain-text strings the
+/// process-check-result call expects
+#[expect(
+    clippy::ref_option,
+    reason = "serialize_with passes a reference to the field"
+)]
+fn serialize_performance_data_as_strings<S>(
+    performance_data: &Option<Vec<IcingaPerformanceData>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    performance_data
+        .as_ref()
+        .map(|values| values.iter().map(ToString::to_string).collect::<Vec<_>>())
+        .serialize(serializer)
+}
+
 impl ProcessCheckResult {
     /// create a new builder for this endpoint
     ///
@@ -68,37 +152,261 @@ impl ProcessCheckResult {
     pub fn builder() -> ProcessCheckResultBuilder {
         ProcessCheckResultBuilder::default()
     }
+
+    /// the filter selecting the host or service this check result applies to
+    #[must_use]
+    pub const fn filter(&self) -> Option<&IcingaFilter> {
+        self.filter.as_ref()
+    }
 }
 
 impl ProcessCheckResultBuilder {
-    /// makes sure the filter object type is valid for this call (either Host or Service)
+    /// a builder with the exit status, plugin output, performance data and
+    /// execution times filled in from a plugin run
+    ///
+    /// the output is split like Icinga does it (see
+    /// [crate::plugin::parse_plugin_output]), `exit_code` is the code returned
+    /// by [std::process::ExitStatus::code] and is interpreted as a service
+    /// state, None (the plugin was terminated by a signal) and codes outside
+    /// of 0 to 3 are UNKNOWN
+    ///
+    /// use [Self::host_check] when submitting the result for a host
+    #[must_use]
+    pub fn from_plugin_output(
+        stdout: &str,
+        exit_code: Option<i32>,
+        execution_start: time::OffsetDateTime,
+        execution_end: time::OffsetDateTime,
+    ) -> Self {
+        let (plugin_output, performance_data) = crate::plugin::parse_plugin_output(stdout);
+        let exit_status = match exit_code {
+            Some(0) => ProcessCheckResultExitStatus::ServiceOk,
+            Some(1) => ProcessCheckResultExitStatus::ServiceWarning,
+            Some(2) => ProcessCheckResultExitStatus::ServiceCritical,
+            _ => ProcessCheckResultExitStatus::ServiceUnknown,
+        };
+        let mut builder = Self::default();
+        builder
+            .exit_status(exit_status)
+            .plugin_output(plugin_output)
+            .performance_data((!performance_data.is_empty()).then_some(performance_data))
+            .execution_start(Some(execution_start))
+            .execution_end(Some(execution_end));
+        builder
+    }
+
+    /// run a plugin and create a builder from its output with
+    /// [Self::from_plugin_output], the execution times are measured around
+    /// the run
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the plugin could not be started
+    pub fn run_plugin(command: &mut std::process::Command) -> Result<Self, crate::error::Error> {
+        let execution_start = time::OffsetDateTime::now_utc();
+        let output = command
+            .output()
+            .map_err(crate::error::Error::CouldNotRunPlugin)?;
+        let execution_end = time::OffsetDateTime::now_utc();
+        Ok(Self::from_plugin_output(
+            &String::from_utf8_lossy(&output.stdout),
+            output.status.code(),
+            execution_start,
+            execution_end,
+        ))
+    }
+
+    /// convert a service state exit status to the host state Icinga uses
+    /// for the same plugin exit code (see
+    /// [ProcessCheckResultExitStatus::for_host])
+    pub fn host_check(&mut self) -> &mut Self {
+        self.exit_status = self.exit_status.map(ProcessCheckResultExitStatus::for_host);
+        self
+    }
+
+    /// makes sure the filter object type is valid for this call (either Host
+    /// or Service) and the exit status can be submitted for it
     ///
     /// # Errors
     ///
-    /// this returns an error if the filter field object type is not Host or Service
+    /// this returns an error if the filter field object type is not Host or
+    /// Service or the exit status is a host state for a service or vice versa
     pub fn validate(&self) -> Result<(), crate::error::Error> {
         if let Some(Some(filter)) = &self.filter {
             if filter.object_type != IcingaObjectType::Host
                 && filter.object_type != IcingaObjectType::Service
             {
-                Err(crate::error::Error::FilterObjectTypeMismatch(
+                return Err(crate::error::Error::FilterObjectTypeMismatch(
                     vec![IcingaObjectType::Host, IcingaObjectType::Service],
                     filter.object_type.to_owned(),
+                ));
+            }
+            if let Some(exit_status) = self.exit_status
+                && let Some(object_type) = exit_status.object_type()
+                && object_type != filter.object_type
+            {
+                return Err(crate::error::Error::ExitStatusObjectTypeMismatch(
+                    exit_status,
+                    filter.object_type.to_owned(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// the exit status of a passive check result
+///
+/// Icinga accepts 0 (UP) and 1 (DOWN) for hosts and 0 (OK) to 3 (UNKNOWN)
+/// for services, the other host and service states can not be submitted and
+/// are not represented here
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum ProcessCheckResultExitStatus {
+    /// the host is UP
+    HostUp,
+    /// the host is DOWN
+    HostDown,
+    /// the service is OK
+    ServiceOk,
+    /// the service is WARNING
+    ServiceWarning,
+    /// the service is CRITICAL
+    ServiceCritical,
+    /// the service is UNKNOWN
+    ServiceUnknown,
+    /// the numeric exit status as sent to Icinga without any validation
+    Raw(u8),
+}
+
+impl ProcessCheckResultExitStatus {
+    /// the typed exit status for a numeric one and the object type it is
+    /// for, codes Icinga does not accept for the object type are returned
+    /// as [Self::Raw]
+    #[must_use]
+    pub const fn from_code(code: u8, object_type: IcingaObjectType) -> Self {
+        match (object_type, code) {
+            (IcingaObjectType::Host, 0) => Self::HostUp,
+            (IcingaObjectType::Host, 1) => Self::HostDown,
+            (IcingaObjectType::Service, 0) => Self::ServiceOk,
+            (IcingaObjectType::Service, 1) => Self::ServiceWarning,
+            (IcingaObjectType::Service, 2) => Self::ServiceCritical,
+            (IcingaObjectType::Service, 3) => Self::ServiceUnknown,
+            _ => Self::Raw(code),
+        }
+    }
+
+    /// the numeric exit status as sent to Icinga
+    #[must_use]
+    pub const fn code(self) -> u8 {
+        match self {
+            Self::HostUp | Self::ServiceOk => 0,
+            Self::HostDown | Self::ServiceWarning => 1,
+            Self::ServiceCritical => 2,
+            Self::ServiceUnknown => 3,
+            Self::Raw(exit_status) => exit_status,
+        }
+    }
+
+    /// the object type the exit status is for, None for raw exit statuses
+    #[must_use]
+    pub const fn object_type(self) -> Option<IcingaObjectType> {
+        match self {
+            Self::HostUp | Self::HostDown => Some(IcingaObjectType::Host),
+            Self::ServiceOk
+            | Self::ServiceWarning
+            | Self::ServiceCritical
+            | Self::ServiceUnknown => Some(IcingaObjectType::Service),
+            Self::Raw(_) => None,
+        }
+    }
+
+    /// convert a service state to the host state Icinga uses for the same
+    /// plugin exit code (OK and WARNING are UP, everything else is DOWN),
+    /// host states and raw exit statuses are returned unchanged
+    #[must_use]
+    pub const fn for_host(self) -> Self {
+        match self {
+            Self::ServiceOk | Self::ServiceWarning => Self::HostUp,
+            Self::ServiceCritical | Self::ServiceUnknown => Self::HostDown,
+            Self::HostUp | Self::HostDown | Self::Raw(_) => self,
+        }
+    }
+}
+
+impl TryFrom<IcingaHostState> for ProcessCheckResultExitStatus {
+    type Error = crate::error::Error;
+
+    fn try_from(value: IcingaHostState) -> Result<Self, Self::Error> {
+        match value {
+            IcingaHostState::Up => Ok(Self::HostUp),
+            IcingaHostState::Down => Ok(Self::HostDown),
+            IcingaHostState::Unreachable => Err(crate::error::Error::ExitStatusNotSubmittable(
+                IcingaHostOrServiceState::Host(value),
+            )),
+        }
+    }
+}
+
+impl TryFrom<IcingaServiceState> for ProcessCheckResultExitStatus {
+    type Error = crate::error::Error;
+
+    fn try_from(value: IcingaServiceState) -> Result<Self, Self::Error> {
+        match value {
+            IcingaServiceState::Ok => Ok(Self::ServiceOk),
+            IcingaServiceState::Warning => Ok(Self::ServiceWarning),
+            IcingaServiceState::Critical => Ok(Self::ServiceCritical),
+            IcingaServiceState::Unknown => Ok(Self::ServiceUnknown),
+            IcingaServiceState::Unreachable | IcingaServiceState::Pending => {
+                Err(crate::error::Error::ExitStatusNotSubmittable(
+                    IcingaHostOrServiceState::Service(value),
                 ))
-            } else {
-                Ok(())
             }
-        } else {
-            Ok(())
         }
     }
 }
 
+impl TryFrom<IcingaHostOrServiceState> for ProcessCheckResultExitStatus {
+    type Error = crate::error::Error;
+
+    fn try_from(value: IcingaHostOrServiceState) -> Result<Self, Self::Error> {
+        match value {
+            IcingaHostOrServiceState::Host(state) => Self::try_from(state),
+            IcingaHostOrServiceState::Service(state) => Self::try_from(state),
+        }
+    }
+}
+
+impl From<u8> for ProcessCheckResultExitStatus {
+    fn from(value: u8) -> Self {
+        Self::Raw(value)
+    }
+}
+
+impl Serialize for ProcessCheckResultExitStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u8(self.code())
+    }
+}
+
+/// without an object type a deserialized exit status is always [Self::Raw],
+/// [ProcessCheckResult] types it using the object type of its filter
+impl<'de> Deserialize<'de> for ProcessCheckResultExitStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        u8::deserialize(deserializer).map(Self::Raw)
+    }
+}
+
 impl RestApiEndpoint for ProcessCheckResult {
     type RequestBody = Self;
 
-    fn method(&self) -> Result<reqwest::Method, crate::error::Error> {
-        Ok(reqwest::Method::POST)
+    fn method(&self) -> Result<HttpMethod, crate::error::Error> {
+        Ok(HttpMethod::Post)
     }
 
     fn url(&self, base_url: &url::Url) -> Result<url::Url, crate::error::Error> {
@@ -118,3 +426,120 @@ impl RestApiEndpoint for ProcessCheckResult {
 }
 
 impl RestApiResponse<ProcessCheckResult> for ResultsWrapper<StatusResponse> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+
+    #[test]
+    fn test_from_plugin_output() -> Result<(), Box<dyn Error>> {
+        let execution_start = time::OffsetDateTime::from_unix_timestamp(1_700_000_000)?;
+        let execution_end = time::OffsetDateTime::from_unix_timestamp(1_700_000_002)?;
+        let process_check_result = ProcessCheckResultBuilder::from_plugin_output(
+            "PING WARNING - rta 120ms | rta=120ms;100;200\nlong output\n",
+            Some(1),
+            execution_start,
+            execution_end,
+        )
+        .ttl(None)
+        .filter(IcingaFilter {
+            object_type: IcingaObjectType::Service,
+            filter: r#"host.name=="example" && service.name=="ping""#.to_owned(),
+            filter_vars: std::collections::BTreeMap::new(),
+        })
+        .build()?;
+        assert_eq!(
+            process_check_result.exit_status,
+            ProcessCheckResultExitStatus::ServiceWarning
+        );
+        let request_body = serde_json::to_value(&process_check_result)?;
+        assert_eq!(request_body.get("exit_status"), Some(&serde_json::json!(1)));
+        assert_eq!(
+            request_body.get("performance_data"),
+            Some(&serde_json::json!(["rta=120ms;100;200"]))
+        );
+        assert_eq!(
+            process_check_result.plugin_output,
+            "PING WARNING - rta 120ms\nlong output"
+        );
+        assert_eq!(
+            process_check_result.performance_data,
+            Some(vec!["rta=120ms;100;200".parse()?])
+        );
+        assert_eq!(process_check_result.execution_start, Some(execution_start));
+        assert_eq!(process_check_result.execution_end, Some(execution_end));
+        let process_check_result = ProcessCheckResultBuilder::from_plugin_output(
+            "killed",
+            None,
+            execution_start,
+            execution_end,
+        )
+        .ttl(None)
+        .build()?;
+        assert_eq!(
+            process_check_result.exit_status,
+            ProcessCheckResultExitStatus::ServiceUnknown
+        );
+        assert_eq!(process_check_result.performance_data, None);
+        Ok(())
+    }
+
+    /// a filter for a single host or service
+    fn filter(object_type: IcingaObjectType) -> IcingaFilter {
+        IcingaFilter {
+            object_type,
+            filter: r#"host.name=="example""#.to_owned(),
+            filter_vars: std::collections::BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_exit_status_validation() -> Result<(), Box<dyn Error>> {
+        let now = time::OffsetDateTime::now_utc();
+        let host_check = ProcessCheckResultBuilder::from_plugin_output("DOWN", Some(2), now, now)
+            .ttl(None)
+            .filter(filter(IcingaObjectType::Host))
+            .host_check()
+            .build()?;
+        assert_eq!(
+            host_check.exit_status,
+            ProcessCheckResultExitStatus::HostDown
+        );
+        let reloaded: ProcessCheckResult =
+            serde_json::from_value(serde_json::to_value(&host_check)?)?;
+        assert_eq!(reloaded.exit_status, ProcessCheckResultExitStatus::HostDown);
+        assert!(matches!(
+            ProcessCheckResult::builder()
+                .try_exit_status(IcingaServiceState::Critical)?
+                .plugin_output("CRITICAL".to_owned())
+                .execution_start(None)
+                .execution_end(None)
+                .ttl(None)
+                .filter(filter(IcingaObjectType::Host))
+                .build(),
+            Err(crate::error::Error::ExitStatusObjectTypeMismatch(_, _))
+        ));
+        assert!(matches!(
+            ProcessCheckResult::builder().try_exit_status(IcingaHostState::Unreachable),
+            Err(crate::error::Error::ExitStatusNotSubmittable(_))
+        ));
+        assert!(matches!(
+            ProcessCheckResultExitStatus::try_from(IcingaServiceState::Pending),
+            Err(crate::error::Error::ExitStatusNotSubmittable(_))
+        ));
+        let raw = ProcessCheckResult::builder()
+            .exit_status(2)
+            .plugin_output("raw".to_owned())
+            .execution_start(None)
+            .execution_end(None)
+            .ttl(None)
+            .filter(filter(IcingaObjectType::Host))
+            .build()?;
+        assert_eq!(raw.exit_status.code(), 2);
+        let reloaded: ProcessCheckResult = serde_json::from_value(serde_json::to_value(&raw)?)?;
+        assert_eq!(reloaded.exit_status, ProcessCheckResultExitStatus::Raw(2));
+        Ok(())
+    }
+}
@@ -8,7 +8,7 @@ use crate::types::action::StatusResponse;
 use crate::types::enums::object_type::IcingaObjectType;
 use crate::types::filter::IcingaFilter;
 use crate::types::query::ResultsWrapper;
-use crate::types::rest::{RestApiEndpoint, RestApiResponse};
+use crate::types::rest::{HttpMethod, RestApiEndpoint, RestApiResponse};
 
 /// REST API Endpoint for the remove-acknowledgement call
 #[derive(Debug, Clone, derive_builder::Builder, Serialize, Deserialize)]
@@ -62,8 +62,8 @@ impl RemoveAcknowledgementBuilder {
 impl RestApiEndpoint for RemoveAcknowledgement {
     type RequestBody = Self;
 
-    fn method(&self) -> Result<reqwest::Method, crate::error::Error> {
-        Ok(reqwest::Method::POST)
+    fn method(&self) -> Result<HttpMethod, crate::error::Error> {
+        Ok(HttpMethod::Post)
     }
 
     fn url(&self, base_url: &url::Url) -> Result<url::Url, crate::error::Error> {
@@ -1,81 +1,114 @@
 //! Main API object (async version)
 
-use std::{path::Path, str::from_utf8};
+use std::{num::NonZeroUsize, sync::Arc, time::Instant};
 
 use futures::Stream;
 use futures::StreamExt as _;
 use futures::stream::TryStreamExt as _;
 use serde::{Serialize, de::DeserializeOwned};
 use tokio::io::AsyncBufReadExt as _;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio_stream::wrappers::LinesStream;
 use tokio_util::io::StreamReader;
 
+use crate::api::cassette::{Cassette, CassetteMode};
+use crate::api::client::IcingaClientAsync;
+use crate::api::core::{
+    IcingaHttpRequest, IcingaHttpResponse, MAX_ERROR_BODY_LENGTH, after_response, before_request,
+    send_error,
+};
+use crate::api::limit::RateLimiter;
+use crate::api::middleware::Middleware;
+use crate::api::stream::ResultsParser;
+use crate::api::submitter::PassiveResultSubmitter;
+use crate::api::transport::{AsyncBody, AsyncTransport, TransportRequest, TransportResponse};
 use crate::config::Icinga2Instance;
 use crate::types::{
     enums::event_stream_type::IcingaEventStreamType,
     event_stream::IcingaEvent,
     filter::IcingaFilter,
-    rest::{RestApiEndpoint, RestApiResponse},
+    lenient::{LenientDeserialize, SchemaDriftReport},
+    query::ResultsWrapper,
+    rest::{HttpMethod, RawResponse, RestApiEndpoint, RestApiResponse},
 };
 
 /// the runtime object for an Icinga2 instance (blocking variant)
 #[derive(Debug, Clone)]
 pub struct Icinga2Async {
-    /// the HTTP client to use
-    client: reqwest::Client,
+    /// the HTTP backend to send requests with
+    transport: Arc<dyn AsyncTransport>,
     /// the base URL for the Icinga API
     pub url: url::Url,
     /// username
     pub username: String,
     /// password
     password: String,
+    /// the cassette interactions are recorded to or replayed from
+    cassette: Option<Cassette>,
+    /// the middleware called around every REST API call
+    middleware: Vec<Arc<dyn Middleware>>,
+    /// the client side rate limit shared between clones
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// the limit on requests in flight shared between clones
+    in_flight_limit: Option<Arc<Semaphore>>,
 }
 
 impl Icinga2Async {
     /// create a new Icinga2 instance from a config that was
     /// either manually created or previously loaded via [Icinga2Instance::from_config_file]
     ///
+    /// this uses the reqwest backend if the `async` feature is enabled and
+    /// the hyper backend otherwise, see [Self::from_transport] to use another one
+    ///
     /// # Errors
     /// this fails if the CA certificate file mentioned in the configuration
     /// can not be found or parsed
+    #[cfg(any(feature = "async", feature = "hyper"))]
     pub fn from_instance_config(config: &Icinga2Instance) -> Result<Self, crate::error::Error> {
-        let client_builder = reqwest::ClientBuilder::new();
-        let client_builder = client_builder.user_agent(concat!(
-            env!("CARGO_PKG_NAME"),
-            "/",
-            env!("CARGO_PKG_VERSION")
-        ));
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert(
-            "Content-Type",
-            reqwest::header::HeaderValue::from_static("application/json"),
-        );
-        headers.insert(
-            "Accept",
-            reqwest::header::HeaderValue::from_static("application/json"),
-        );
-        let client_builder = client_builder.default_headers(headers);
-        let client_builder = if let Some(ca_certificate) = &config.ca_certificate {
-            let ca_cert_content = fs_err::read(ca_certificate)
-                .map_err(crate::error::Error::CouldNotReadCACertFile)?;
-            let ca_cert = reqwest::Certificate::from_pem(&ca_cert_content)
-                .map_err(crate::error::Error::CouldNotParsePEMCACertificate)?;
-            client_builder.tls_certs_only([ca_cert])
-        } else {
-            client_builder
-        };
-        let client = client_builder
-            .build()
-            .map_err(crate::error::Error::CouldNotBuildReqwestClientFromSuppliedInformation)?;
+        #[cfg(feature = "async")]
+        let transport =
+            crate::api::transport::reqwest_backend::ReqwestAsyncTransport::from_instance_config(
+                config,
+            )?;
+        #[cfg(not(feature = "async"))]
+        let transport =
+            crate::api::transport::hyper_backend::HyperTransport::from_instance_config(config)?;
+        Self::from_transport(config, transport)
+    }
+
+    /// create a new Icinga2 instance from a config sending requests with the
+    /// given HTTP backend
+    ///
+    /// the CA certificate in the config is not used, the backend has to be
+    /// set up to validate the server certificate
+    ///
+    /// # Errors
+    /// this fails if the URL in the configuration can not be parsed
+    pub fn from_transport<T>(
+        config: &Icinga2Instance,
+        transport: T,
+    ) -> Result<Self, crate::error::Error>
+    where
+        T: AsyncTransport + 'static,
+    {
         let url =
             url::Url::parse(&config.url).map_err(crate::error::Error::CouldNotParseUrlInConfig)?;
         let username = config.username.clone();
         let password = config.password.clone();
         Ok(Self {
-            client,
+            transport: Arc::new(transport),
             url,
             username,
             password,
+            cassette: None,
+            middleware: Vec::new(),
+            rate_limiter: config
+                .rate_limit
+                .as_ref()
+                .map(|rate_limit| Arc::new(RateLimiter::new(rate_limit))),
+            in_flight_limit: config
+                .max_in_flight
+                .map(|max_in_flight| Arc::new(Semaphore::new(max_in_flight.get()))),
         })
     }
 
@@ -85,126 +118,265 @@ impl Icinga2Async {
     /// this fails if the configuration file can not be found or parsed
     /// or the CA certificate file mentioned in the configuration file
     /// can not be found or parsed
-    pub fn from_config_file(path: &Path) -> Result<Self, crate::error::Error> {
+    #[cfg(any(feature = "async", feature = "hyper"))]
+    pub fn from_config_file(path: &std::path::Path) -> Result<Self, crate::error::Error> {
         let icinga_instance = Icinga2Instance::from_config_file(path)?;
         Self::from_instance_config(&icinga_instance)
     }
 
+    /// record all REST API calls to a cassette file or replay them from one
+    ///
+    /// see [crate::api::cassette] for details
+    ///
+    /// # Errors
+    /// in record mode this fails if the cassette file can not be created, in
+    /// replay mode if it can not be read or parsed
+    pub fn with_cassette(mut self, mode: CassetteMode) -> Result<Self, crate::error::Error> {
+        self.cassette = Some(Cassette::new(mode, &self.password)?);
+        Ok(self)
+    }
+
+    /// install middleware called around every REST API call
+    ///
+    /// see [crate::api::middleware] for details
+    #[must_use]
+    pub fn with_middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// start a [PassiveResultSubmitter] submitting passive check results
+    /// with `concurrency` requests in flight
+    ///
+    /// the client side limits of this client (see
+    /// [crate::config::Icinga2Instance::max_in_flight]) still apply
+    ///
+    /// # Panics
+    ///
+    /// this panics if called outside of a tokio runtime
+    #[must_use]
+    pub fn passive_result_submitter(&self, concurrency: NonZeroUsize) -> PassiveResultSubmitter {
+        PassiveResultSubmitter::start(self.clone(), concurrency)
+    }
+
+    /// wait until the client side limits allow sending another request
+    ///
+    /// the request counts as in flight until the returned permit is dropped
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the semaphore limiting requests in flight was closed
+    async fn wait_for_limits(&self) -> Result<Option<OwnedSemaphorePermit>, crate::error::Error> {
+        let in_flight = match &self.in_flight_limit {
+            Some(limit) => Some(
+                Arc::clone(limit)
+                    .acquire_owned()
+                    .await
+                    .map_err(crate::error::Error::InFlightLimitClosed)?,
+            ),
+            None => None,
+        };
+        if let Some(rate_limiter) = &self.rate_limiter {
+            tokio::time::sleep(rate_limiter.reserve()).await;
+        }
+        Ok(in_flight)
+    }
+
     /// common code for the REST API calls
     ///
     /// # Errors
     ///
     /// this returns an error if encoding, the actual request, or decoding of the response fail
-    #[expect(
-        clippy::future_not_send,
-        reason = "neither ApiEndpoint nor its RequestBody is required to be Send; callers that need a Send future can wrap with their own bounds"
-    )]
-    pub async fn rest<ApiEndpoint, Res>(
+    pub fn rest<ApiEndpoint, Res>(
         &self,
         api_endpoint: ApiEndpoint,
-    ) -> Result<Res, crate::error::Error>
+    ) -> impl Future<Output = Result<Res, crate::error::Error>> + Send
     where
         ApiEndpoint: RestApiEndpoint,
         <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
-        Res: DeserializeOwned + std::fmt::Debug + RestApiResponse<ApiEndpoint>,
+        Res: DeserializeOwned + std::fmt::Debug + RestApiResponse<ApiEndpoint> + Send,
     {
-        let method = api_endpoint.method()?;
-        let url = api_endpoint.url(&self.url)?;
-        let request_body: Option<std::borrow::Cow<<ApiEndpoint as RestApiEndpoint>::RequestBody>> =
-            api_endpoint.request_body()?;
-        let actual_method = if method == reqwest::Method::GET && request_body.is_some() {
-            reqwest::Method::POST
+        IcingaClientAsync::rest(self, api_endpoint)
+    }
+
+    /// variant of [Self::rest] using lenient deserialization
+    ///
+    /// missing non-essential attributes are filled in with defaults and unknown
+    /// attributes are captured instead of dropped, see [crate::types::lenient]
+    /// for details. The returned report lists both.
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if encoding, the actual request, or decoding of the response fail
+    pub fn rest_lenient<ApiEndpoint, Res>(
+        &self,
+        api_endpoint: ApiEndpoint,
+    ) -> impl Future<Output = Result<(Res, SchemaDriftReport), crate::error::Error>> + Send
+    where
+        ApiEndpoint: RestApiEndpoint,
+        <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
+        Res: LenientDeserialize + RestApiResponse<ApiEndpoint> + Send,
+    {
+        IcingaClientAsync::rest_lenient(self, api_endpoint)
+    }
+
+    /// call any REST API endpoint without parsing the response into a specific type
+    ///
+    /// this is an escape hatch for endpoints the crate does not model yet (see
+    /// [crate::api::generic::GenericEndpoint]) and for responses the crate's
+    /// types do not match. It does not require a [RestApiResponse]
+    /// implementation and does not fail on error statuses. An empty response
+    /// body is returned as null.
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if encoding, the actual request, or decoding of the response as JSON fail
+    pub fn rest_raw<ApiEndpoint>(
+        &self,
+        api_endpoint: ApiEndpoint,
+    ) -> impl Future<Output = Result<RawResponse, crate::error::Error>> + Send
+    where
+        ApiEndpoint: RestApiEndpoint,
+        <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
+    {
+        IcingaClientAsync::rest_raw(self, api_endpoint)
+    }
+
+    /// streaming variant of [Self::rest] for endpoints returning a `results` array
+    ///
+    /// the elements of the `results` array are deserialized one at a time while
+    /// the response body is received so memory use does not grow with the number
+    /// of results. An element that can not be deserialized is returned as an error
+    /// with a path relative to that element, the stream continues after it.
+    ///
+    /// middleware is called before the response body is received so it sees an
+    /// empty body. When recording to a cassette the whole response body is
+    /// received before the first element is returned.
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if encoding, the actual request, or receiving the
+    /// response body on an error status fail, errors receiving or decoding the
+    /// response body otherwise are returned by the stream
+    pub async fn rest_stream<ApiEndpoint, Item>(
+        &self,
+        api_endpoint: ApiEndpoint,
+    ) -> Result<
+        impl Stream<Item = Result<Item, crate::error::Error>> + use<ApiEndpoint, Item>,
+        crate::error::Error,
+    >
+    where
+        ApiEndpoint: RestApiEndpoint,
+        <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
+        Item: DeserializeOwned + std::fmt::Debug,
+        ResultsWrapper<Item>: RestApiResponse<ApiEndpoint>,
+    {
+        let request = IcingaHttpRequest::new(&self.url, &api_endpoint)?;
+        let (request, extra_headers) = before_request(&self.middleware, &request);
+        let start = Instant::now();
+        let (status, headers, mut chunks, in_flight) = if let Some(cassette) = &self.cassette
+            && cassette.is_replay()
+        {
+            let (status, response_body) = cassette.replay(
+                request.method,
+                &self.url,
+                &request.url,
+                request.body.as_ref(),
+            )?;
+            (
+                status,
+                http::HeaderMap::new(),
+                futures::stream::iter([Ok(response_body.into())]).boxed(),
+                None,
+            )
         } else {
-            method.to_owned()
-        };
-        let mut req = self.client.request(actual_method, url.to_owned());
-        if method == reqwest::Method::GET && request_body.is_some() {
-            tracing::trace!("Sending GET request with body as POST via X-HTTP-Method-Override");
-            req = req.header(
-                "X-HTTP-Method-Override",
-                reqwest::header::HeaderValue::from_static("GET"),
-            );
-        }
-        req = req.basic_auth(&self.username, Some(&self.password));
-        if let Some(request_body) = request_body {
-            tracing::trace!("Request body:\n{:#?}", request_body);
-            req = req.json(&request_body);
-        }
-        let result = req.send().await;
-        if let Err(ref e) = result {
-            tracing::error!(%url, %method, "Icinga2 send error: {:?}", e);
-        }
-        let result = result?;
-        let status = result.status();
-        let response_body = result.bytes().await?;
-        match from_utf8(&response_body) {
-            Ok(response_body) => {
-                tracing::trace!("Response body:\n{}", &response_body);
+            let transport_request =
+                request.transport_request(&self.username, &self.password, extra_headers)?;
+            let in_flight = self.wait_for_limits().await?;
+            let TransportResponse {
+                status,
+                headers,
+                body: result,
+            } = self
+                .transport
+                .send(transport_request)
+                .await
+                .inspect_err(|e| send_error(&self.middleware, &request, e, start.elapsed()))?;
+            if let Some(cassette) = &self.cassette {
+                let response_body = read_body(result)
+                    .await
+                    .inspect_err(|e| send_error(&self.middleware, &request, e, start.elapsed()))?;
+                cassette.record(
+                    request.method,
+                    &self.url,
+                    &request.url,
+                    request.body.as_ref(),
+                    status,
+                    &response_body,
+                )?;
+                (
+                    status,
+                    headers,
+                    futures::stream::iter([Ok(response_body.into())]).boxed(),
+                    in_flight,
+                )
+            } else {
+                (
+                    status,
+                    headers,
+                    result
+                        .map_err(crate::error::Error::CouldNotReadResponseBody)
+                        .boxed(),
+                    in_flight,
+                )
             }
-            Err(e) => {
-                tracing::trace!(
-                    "Response body that could not be parsed as utf8 because of {}:\n{:?}",
-                    &e,
-                    &response_body
-                );
+        };
+        let mut response = IcingaHttpResponse {
+            status,
+            headers,
+            body: Vec::new(),
+        };
+        after_response(&self.middleware, &request, &mut response, start.elapsed());
+        if status.is_client_error() || status.is_server_error() {
+            let url = &request.url;
+            let method = &request.method;
+            tracing::error!(%url, %method, "Icinga2 status error: {:?}", status);
+            let mut error_body = Vec::new();
+            while let Some(chunk) = chunks.next().await
+                && u64::try_from(error_body.len()).unwrap_or(u64::MAX) < MAX_ERROR_BODY_LENGTH
+            {
+                error_body.extend_from_slice(&chunk?);
             }
+            return Err(crate::error::Error::ErrorStatusInResultsStream(
+                status,
+                String::from_utf8_lossy(&error_body).into_owned(),
+            ));
         }
-        if status.is_client_error() {
-            tracing::error!(%url, %method, "Icinga2 status error (client error): {:?}", status);
-        } else if status.is_server_error() {
-            tracing::error!(%url, %method, "Icinga2 status error (server error): {:?}", status);
-        }
-        if response_body.is_empty() {
-            Err(crate::error::Error::EmptyResponseBody(status))
-        } else {
-            let jd = &mut serde_json::Deserializer::from_slice(&response_body);
-            match serde_path_to_error::deserialize(jd) {
-                Ok(response_body) => {
-                    tracing::trace!("Parsed response body:\n{:#?}", response_body);
-                    Ok(response_body)
-                }
-                Err(e) => {
-                    let path = e.path();
-                    tracing::error!("Parsing failed at path {}: {}", path.to_string(), e.inner());
-                    if let Ok(response_body) = serde_json::from_slice(&response_body) {
-                        let mut response_body: serde_json::Value = response_body;
-                        for segment in path {
-                            let next = match (&response_body, segment) {
-                                (
-                                    serde_json::Value::Array(vs),
-                                    serde_path_to_error::Segment::Seq { index },
-                                ) => {
-                                    if let Some(v) = vs.get(*index) {
-                                        v.clone()
-                                    } else {
-                                        // if we can not find the element serde_path_to_error references fall back to just returning the error
-                                        return Err(e.into());
-                                    }
-                                }
-                                (
-                                    serde_json::Value::Object(m),
-                                    serde_path_to_error::Segment::Map { key },
-                                ) => {
-                                    if let Some(v) = m.get(key) {
-                                        v.clone()
-                                    } else {
-                                        // if we can not find the element serde_path_to_error references fall back to just returning the error
-                                        return Err(e.into());
-                                    }
-                                }
-                                _ => {
-                                    // if we can not find the element serde_path_to_error references fall back to just returning the error
-                                    return Err(e.into());
-                                }
-                            };
-                            response_body = next;
+        let parser: ResultsParser<Item> = ResultsParser::new();
+        Ok(futures::stream::unfold(
+            (chunks, parser, in_flight),
+            async |(mut chunks, mut parser, in_flight)| {
+                loop {
+                    if let Some(item) = parser.next_item() {
+                        return Some((item, (chunks, parser, in_flight)));
+                    }
+                    if parser.is_finished() {
+                        return None;
+                    }
+                    let result = match chunks.next().await {
+                        Some(Ok(chunk)) => parser.feed(&chunk),
+                        Some(Err(e)) => {
+                            parser.fail();
+                            Err(e)
                         }
-                        tracing::error!("Value in location path references is: {}", response_body);
+                        None => parser.finish(),
+                    };
+                    if let Err(e) = result {
+                        return Some((Err(e), (chunks, parser, in_flight)));
                     }
-                    Err(e.into())
                 }
-            }
-        }
+            },
+        ))
     }
 
     /// Long-polling on an event stream
@@ -218,7 +390,7 @@ impl Icinga2Async {
         queue: &str,
         filter: Option<IcingaFilter>,
     ) -> Result<impl Stream<Item = Result<IcingaEvent, std::io::Error>>, crate::error::Error> {
-        let method = reqwest::Method::POST;
+        let method = HttpMethod::Post;
         let mut url = self
             .url
             .join("v1/events")
@@ -228,25 +400,28 @@ impl Icinga2Async {
         }
         url.query_pairs_mut().append_pair("queue", queue);
         let request_body = filter;
-        let mut req = self.client.request(method.to_owned(), url.to_owned());
-        req = req.basic_auth(&self.username, Some(&self.password));
-        if let Some(request_body) = request_body {
+        if let Some(request_body) = &request_body {
             tracing::trace!("Request body:\n{:#?}", request_body);
-            req = req.json(&request_body);
         }
-        let result = req.send().await;
+        let req = TransportRequest::new(
+            method,
+            url.to_owned(),
+            request_body.as_ref(),
+            &self.username,
+            &self.password,
+        )?;
+        let result = self.transport.send(req).await;
         if let Err(ref e) = result {
             tracing::error!(%url, %method, "Icinga2 send error: {:?}", e);
         }
         let result = result?;
-        let status = result.status();
+        let status = result.status;
         if status.is_client_error() {
             tracing::error!(%url, %method, "Icinga2 status error (client error): {:?}", status);
         } else if status.is_server_error() {
             tracing::error!(%url, %method, "Icinga2 status error (server error): {:?}", status);
         }
-        let byte_chunk_stream = result.bytes_stream().map_err(std::io::Error::other);
-        let stream_reader = StreamReader::new(byte_chunk_stream);
+        let stream_reader = StreamReader::new(result.body);
         let line_reader = LinesStream::new(stream_reader.lines());
         let event_reader = line_reader.map(|l| match l {
             Ok(l) => {
@@ -266,6 +441,82 @@ impl Icinga2Async {
     }
 }
 
+/// receive the complete response body
+///
+/// # Errors
+///
+/// this returns an error if the response body could not be received
+async fn read_body(mut body: AsyncBody) -> Result<Vec<u8>, crate::error::Error> {
+    let mut response_body = Vec::new();
+    while let Some(chunk) = body.next().await {
+        response_body
+            .extend_from_slice(&chunk.map_err(crate::error::Error::CouldNotReadResponseBody)?);
+    }
+    Ok(response_body)
+}
+
+impl IcingaClientAsync for Icinga2Async {
+    fn base_url(&self) -> &url::Url {
+        &self.url
+    }
+
+    async fn execute(
+        &self,
+        request: &IcingaHttpRequest,
+    ) -> Result<IcingaHttpResponse, crate::error::Error> {
+        let (request, extra_headers) = before_request(&self.middleware, request);
+        let start = Instant::now();
+        let mut response = if let Some(cassette) = &self.cassette
+            && cassette.is_replay()
+        {
+            let (status, body) = cassette.replay(
+                request.method,
+                &self.url,
+                &request.url,
+                request.body.as_ref(),
+            )?;
+            IcingaHttpResponse {
+                status,
+                headers: http::HeaderMap::new(),
+                body,
+            }
+        } else {
+            let transport_request =
+                request.transport_request(&self.username, &self.password, extra_headers)?;
+            let _in_flight = self.wait_for_limits().await?;
+            let TransportResponse {
+                status,
+                headers,
+                body: result,
+            } = self
+                .transport
+                .send(transport_request)
+                .await
+                .inspect_err(|e| send_error(&self.middleware, &request, e, start.elapsed()))?;
+            let body = read_body(result)
+                .await
+                .inspect_err(|e| send_error(&self.middleware, &request, e, start.elapsed()))?;
+            if let Some(cassette) = &self.cassette {
+                cassette.record(
+                    request.method,
+                    &self.url,
+                    &request.url,
+                    request.body.as_ref(),
+                    status,
+                    &body,
+                )?;
+            }
+            IcingaHttpResponse {
+                status,
+                headers,
+                body,
+            }
+        };
+        after_response(&self.middleware, &request, &mut response, start.elapsed());
+        Ok(response)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -275,10 +526,8 @@ mod test {
     #[traced_test]
     #[tokio::test]
     async fn test_event_stream_async() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2Async::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2Async::from_instance_config(&config)?;
         let mut stream = icinga2
             .event_stream(
                 &[
@@ -302,6 +551,18 @@ mod test {
                 None,
             )
             .await?;
+        if let Some(fake) = &fake {
+            for i in 0..10 {
+                fake.push_event(&IcingaEvent::ObjectCreated(
+                    crate::types::event_stream::object_created::IcingaEventObjectCreated {
+                        timestamp: crate::types::timestamp::IcingaTimestamp::now(),
+                        object_type: crate::types::enums::object_type::IcingaObjectType::Host,
+                        object_name: format!("fake-host-{i}"),
+                    },
+                ))?;
+            }
+            fake.end_event_streams();
+        }
         for _ in 0..100 {
             let event = stream.next().await;
             tracing::trace!("Got event:\n{:#?}", event);
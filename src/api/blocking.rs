@@ -1,72 +1,107 @@
 //! Main API object (blocking version)
 
-use std::{path::Path, str::from_utf8};
+use std::{io::Read as _, sync::Arc, time::Instant};
 
 use serde::{Serialize, de::DeserializeOwned};
 
 use crate::{
+    api::{
+        cassette::{Cassette, CassetteMode},
+        client::IcingaClient,
+        core::{
+            IcingaHttpRequest, IcingaHttpResponse, MAX_ERROR_BODY_LENGTH, after_response,
+            before_request, send_error,
+        },
+        limit::{InFlightGuard, InFlightLimit, RateLimiter},
+        middleware::Middleware,
+        stream::ResultsIter,
+        transport::{Transport, TransportResponse},
+    },
     config::Icinga2Instance,
-    types::rest::{RestApiEndpoint, RestApiResponse},
+    types::{
+        lenient::{LenientDeserialize, SchemaDriftReport},
+        query::ResultsWrapper,
+        rest::{RawResponse, RestApiEndpoint, RestApiResponse},
+    },
 };
 
 /// the runtime object for an Icinga2 instance (blocking variant)
 #[derive(Debug, Clone)]
 pub struct Icinga2 {
-    /// the HTTP client to use
-    client: reqwest::blocking::Client,
+    /// the HTTP backend to send requests with
+    transport: Arc<dyn Transport>,
     /// the base URL for the Icinga API
     pub url: url::Url,
     /// username
     pub username: String,
     /// password
     password: String,
+    /// the cassette interactions are recorded to or replayed from
+    cassette: Option<Cassette>,
+    /// the middleware called around every REST API call
+    middleware: Vec<Arc<dyn Middleware>>,
+    /// the client side rate limit shared between clones
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// the limit on requests in flight shared between clones
+    in_flight_limit: Option<Arc<InFlightLimit>>,
 }
 
 impl Icinga2 {
     /// create a new Icinga2 instance from a config that was
     /// either manually created or previously loaded via [Icinga2Instance::from_config_file]
     ///
+    /// this uses the reqwest backend if the `blocking` feature is enabled and
+    /// the ureq backend otherwise, see [Self::from_transport] to use another one
+    ///
     /// # Errors
     /// this fails if the CA certificate file mentioned in the configuration
     /// can not be found or parsed
+    #[cfg(any(feature = "blocking", feature = "ureq"))]
     pub fn from_instance_config(config: &Icinga2Instance) -> Result<Self, crate::error::Error> {
-        let client_builder = reqwest::blocking::ClientBuilder::new();
-        let client_builder = client_builder.user_agent(concat!(
-            env!("CARGO_PKG_NAME"),
-            "/",
-            env!("CARGO_PKG_VERSION")
-        ));
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert(
-            "Content-Type",
-            reqwest::header::HeaderValue::from_static("application/json"),
-        );
-        headers.insert(
-            "Accept",
-            reqwest::header::HeaderValue::from_static("application/json"),
-        );
-        let client_builder = client_builder.default_headers(headers);
-        let client_builder = if let Some(ca_certificate) = &config.ca_certificate {
-            let ca_cert_content = fs_err::read(ca_certificate)
-                .map_err(crate::error::Error::CouldNotReadCACertFile)?;
-            let ca_cert = reqwest::Certificate::from_pem(&ca_cert_content)
-                .map_err(crate::error::Error::CouldNotParsePEMCACertificate)?;
-            client_builder.tls_certs_only([ca_cert])
-        } else {
-            client_builder
-        };
-        let client = client_builder
-            .build()
-            .map_err(crate::error::Error::CouldNotBuildReqwestClientFromSuppliedInformation)?;
+        #[cfg(feature = "blocking")]
+        let transport =
+            crate::api::transport::reqwest_backend::ReqwestBlockingTransport::from_instance_config(
+                config,
+            )?;
+        #[cfg(not(feature = "blocking"))]
+        let transport =
+            crate::api::transport::ureq_backend::UreqTransport::from_instance_config(config)?;
+        Self::from_transport(config, transport)
+    }
+
+    /// create a new Icinga2 instance from a config sending requests with the
+    /// given HTTP backend
+    ///
+    /// the CA certificate in the config is not used, the backend has to be
+    /// set up to validate the server certificate
+    ///
+    /// # Errors
+    /// this fails if the URL in the configuration can not be parsed
+    pub fn from_transport<T>(
+        config: &Icinga2Instance,
+        transport: T,
+    ) -> Result<Self, crate::error::Error>
+    where
+        T: Transport + 'static,
+    {
         let url =
             url::Url::parse(&config.url).map_err(crate::error::Error::CouldNotParseUrlInConfig)?;
         let username = config.username.clone();
         let password = config.password.clone();
         Ok(Self {
-            client,
+            transport: Arc::new(transport),
             url,
             username,
             password,
+            cassette: None,
+            middleware: Vec::new(),
+            rate_limiter: config
+                .rate_limit
+                .as_ref()
+                .map(|rate_limit| Arc::new(RateLimiter::new(rate_limit))),
+            in_flight_limit: config
+                .max_in_flight
+                .map(|max_in_flight| Arc::new(InFlightLimit::new(max_in_flight))),
         })
     }
 
@@ -76,11 +111,44 @@ impl Icinga2 {
     /// this fails if the configuration file can not be found or parsed
     /// or the CA certificate file mentioned in the configuration file
     /// can not be found or parsed
-    pub fn from_config_file(path: &Path) -> Result<Self, crate::error::Error> {
+    #[cfg(any(feature = "blocking", feature = "ureq"))]
+    pub fn from_config_file(path: &std::path::Path) -> Result<Self, crate::error::Error> {
         let icinga_instance = Icinga2Instance::from_config_file(path)?;
         Self::from_instance_config(&icinga_instance)
     }
 
+    /// record all REST API calls to a cassette file or replay them from one
+    ///
+    /// see [crate::api::cassette] for details
+    ///
+    /// # Errors
+    /// in record mode this fails if the cassette file can not be created, in
+    /// replay mode if it can not be read or parsed
+    pub fn with_cassette(mut self, mode: CassetteMode) -> Result<Self, crate::error::Error> {
+        self.cassette = Some(Cassette::new(mode, &self.password)?);
+        Ok(self)
+    }
+
+    /// install middleware called around every REST API call
+    ///
+    /// see [crate::api::middleware] for details
+    #[must_use]
+    pub fn with_middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// wait until the client side limits allow sending another request
+    ///
+    /// the request counts as in flight until the returned guard is dropped
+    fn wait_for_limits(&self) -> Option<InFlightGuard> {
+        let in_flight = self.in_flight_limit.as_ref().map(InFlightLimit::acquire);
+        if let Some(rate_limiter) = &self.rate_limiter {
+            std::thread::sleep(rate_limiter.reserve());
+        }
+        in_flight
+    }
+
     /// common code for the REST API calls
     ///
     /// # Errors
@@ -95,102 +163,221 @@ impl Icinga2 {
         <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
         Res: DeserializeOwned + std::fmt::Debug + RestApiResponse<ApiEndpoint>,
     {
-        let method = api_endpoint.method()?;
-        let url = api_endpoint.url(&self.url)?;
-        let request_body: Option<std::borrow::Cow<<ApiEndpoint as RestApiEndpoint>::RequestBody>> =
-            api_endpoint.request_body()?;
-        let actual_method = if method == reqwest::Method::GET && request_body.is_some() {
-            reqwest::Method::POST
+        IcingaClient::rest(self, api_endpoint)
+    }
+
+    /// variant of [Self::rest] using lenient deserialization
+    ///
+    /// missing non-essential attributes are filled in with defaults and unknown
+    /// attributes are captured instead of dropped, see [crate::types::lenient]
+    /// for details. The returned report lists both.
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if encoding, the actual request, or decoding of the response fail
+    pub fn rest_lenient<ApiEndpoint, Res>(
+        &self,
+        api_endpoint: ApiEndpoint,
+    ) -> Result<(Res, SchemaDriftReport), crate::error::Error>
+    where
+        ApiEndpoint: RestApiEndpoint,
+        <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
+        Res: LenientDeserialize + RestApiResponse<ApiEndpoint>,
+    {
+        IcingaClient::rest_lenient(self, api_endpoint)
+    }
+
+    /// call any REST API endpoint without parsing the response into a specific type
+    ///
+    /// this is an escape hatch for endpoints the crate does not model yet (see
+    /// [crate::api::generic::GenericEndpoint]) and for responses the crate's
+    /// types do not match. It does not require a [RestApiResponse]
+    /// implementation and does not fail on error statuses. An empty response
+    /// body is returned as null.
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if encoding, the actual request, or decoding of the response as JSON fail
+    pub fn rest_raw<ApiEndpoint>(
+        &self,
+        api_endpoint: ApiEndpoint,
+    ) -> Result<RawResponse, crate::error::Error>
+    where
+        ApiEndpoint: RestApiEndpoint,
+        <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
+    {
+        IcingaClient::rest_raw(self, api_endpoint)
+    }
+
+    /// streaming variant of [Self::rest] for endpoints returning a `results` array
+    ///
+    /// the elements of the `results` array are deserialized one at a time while
+    /// the response body is read so memory use does not grow with the number of
+    /// results. An element that can not be deserialized is returned as an error
+    /// with a path relative to that element, iteration continues after it.
+    ///
+    /// middleware is called before the response body is read so it sees an
+    /// empty body. When recording to a cassette the whole response body is read
+    /// before the first element is returned.
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if encoding, the actual request, or reading the
+    /// response body on an error status fail, errors reading or decoding the
+    /// response body otherwise are returned by the iterator
+    pub fn rest_stream<ApiEndpoint, Item>(
+        &self,
+        api_endpoint: ApiEndpoint,
+    ) -> Result<
+        impl Iterator<Item = Result<Item, crate::error::Error>> + use<ApiEndpoint, Item>,
+        crate::error::Error,
+    >
+    where
+        ApiEndpoint: RestApiEndpoint,
+        <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
+        Item: DeserializeOwned + std::fmt::Debug,
+        ResultsWrapper<Item>: RestApiResponse<ApiEndpoint>,
+    {
+        let request = IcingaHttpRequest::new(&self.url, &api_endpoint)?;
+        let (request, extra_headers) = before_request(&self.middleware, &request);
+        let start = Instant::now();
+        let (status, headers, mut response_body, in_flight): (
+            _,
+            _,
+            Box<dyn std::io::Read + Send>,
+            _,
+        ) = if let Some(cassette) = &self.cassette
+            && cassette.is_replay()
+        {
+            let (status, response_body) = cassette.replay(
+                request.method,
+                &self.url,
+                &request.url,
+                request.body.as_ref(),
+            )?;
+            (
+                status,
+                http::HeaderMap::new(),
+                Box::new(std::io::Cursor::new(response_body)),
+                None,
+            )
         } else {
-            method.to_owned()
+            let transport_request =
+                request.transport_request(&self.username, &self.password, extra_headers)?;
+            let in_flight = self.wait_for_limits();
+            let TransportResponse {
+                status,
+                headers,
+                body: mut result,
+            } = self
+                .transport
+                .send(transport_request)
+                .inspect_err(|e| send_error(&self.middleware, &request, e, start.elapsed()))?;
+            if let Some(cassette) = &self.cassette {
+                let mut response_body = Vec::new();
+                result
+                    .read_to_end(&mut response_body)
+                    .map_err(crate::error::Error::CouldNotReadResponseBody)
+                    .inspect_err(|e| send_error(&self.middleware, &request, e, start.elapsed()))?;
+                cassette.record(
+                    request.method,
+                    &self.url,
+                    &request.url,
+                    request.body.as_ref(),
+                    status,
+                    &response_body,
+                )?;
+                (
+                    status,
+                    headers,
+                    Box::new(std::io::Cursor::new(response_body)),
+                    in_flight,
+                )
+            } else {
+                (status, headers, result, in_flight)
+            }
         };
-        let mut req = self.client.request(actual_method, url.to_owned());
-        if method == reqwest::Method::GET && request_body.is_some() {
-            tracing::trace!("Sending GET request with body as POST via X-HTTP-Method-Override");
-            req = req.header(
-                "X-HTTP-Method-Override",
-                reqwest::header::HeaderValue::from_static("GET"),
-            );
-        }
-        req = req.basic_auth(&self.username, Some(&self.password));
-        if let Some(request_body) = request_body {
-            tracing::trace!("Request body:\n{:#?}", request_body);
-            req = req.json(&request_body);
-        }
-        let result = req.send();
-        if let Err(ref e) = result {
-            tracing::error!(%url, %method, "Icinga2 send error: {:?}", e);
+        let mut response = IcingaHttpResponse {
+            status,
+            headers,
+            body: Vec::new(),
+        };
+        after_response(&self.middleware, &request, &mut response, start.elapsed());
+        if status.is_client_error() || status.is_server_error() {
+            let url = &request.url;
+            let method = &request.method;
+            tracing::error!(%url, %method, "Icinga2 status error: {:?}", status);
+            let mut error_body = String::new();
+            std::io::Read::take(&mut response_body, MAX_ERROR_BODY_LENGTH)
+                .read_to_string(&mut error_body)
+                .map_err(crate::error::Error::CouldNotReadResponseBody)?;
+            return Err(crate::error::Error::ErrorStatusInResultsStream(
+                status, error_body,
+            ));
         }
-        let result = result?;
-        let status = result.status();
-        let response_body = result.bytes()?;
-        match from_utf8(&response_body) {
-            Ok(response_body) => {
-                tracing::trace!("Response body:\n{}", &response_body);
-            }
-            Err(e) => {
-                tracing::trace!(
-                    "Response body that could not be parsed as utf8 because of {}:\n{:?}",
-                    &e,
-                    &response_body
-                );
+        Ok(ResultsIter::new(response_body, in_flight))
+    }
+}
+
+impl IcingaClient for Icinga2 {
+    fn base_url(&self) -> &url::Url {
+        &self.url
+    }
+
+    fn execute(
+        &self,
+        request: &IcingaHttpRequest,
+    ) -> Result<IcingaHttpResponse, crate::error::Error> {
+        let (request, extra_headers) = before_request(&self.middleware, request);
+        let start = Instant::now();
+        let mut response = if let Some(cassette) = &self.cassette
+            && cassette.is_replay()
+        {
+            let (status, body) = cassette.replay(
+                request.method,
+                &self.url,
+                &request.url,
+                request.body.as_ref(),
+            )?;
+            IcingaHttpResponse {
+                status,
+                headers: http::HeaderMap::new(),
+                body,
             }
-        }
-        if status.is_client_error() {
-            tracing::error!(%url, %method, "Icinga2 status error (client error): {:?}", status);
-        } else if status.is_server_error() {
-            tracing::error!(%url, %method, "Icinga2 status error (server error): {:?}", status);
-        }
-        if response_body.is_empty() {
-            Err(crate::error::Error::EmptyResponseBody(status))
         } else {
-            let jd = &mut serde_json::Deserializer::from_slice(&response_body);
-            match serde_path_to_error::deserialize(jd) {
-                Ok(response_body) => {
-                    tracing::trace!("Parsed response body:\n{:#?}", response_body);
-                    Ok(response_body)
-                }
-                Err(e) => {
-                    let path = e.path();
-                    tracing::error!("Parsing failed at path {}: {}", path.to_string(), e.inner());
-                    if let Ok(response_body) = serde_json::from_slice(&response_body) {
-                        let mut response_body: serde_json::Value = response_body;
-                        for segment in path {
-                            let next = match (&response_body, segment) {
-                                (
-                                    serde_json::Value::Array(vs),
-                                    serde_path_to_error::Segment::Seq { index },
-                                ) => {
-                                    if let Some(v) = vs.get(*index) {
-                                        v.clone()
-                                    } else {
-                                        // if we can not find the element serde_path_to_error references fall back to just returning the error
-                                        return Err(e.into());
-                                    }
-                                }
-                                (
-                                    serde_json::Value::Object(m),
-                                    serde_path_to_error::Segment::Map { key },
-                                ) => {
-                                    if let Some(v) = m.get(key) {
-                                        v.clone()
-                                    } else {
-                                        // if we can not find the element serde_path_to_error references fall back to just returning the error
-                                        return Err(e.into());
-                                    }
-                                }
-                                _ => {
-                                    // if we can not find the element serde_path_to_error references fall back to just returning the error
-                                    return Err(e.into());
-                                }
-                            };
-                            response_body = next;
-                        }
-                        tracing::error!("Value in location path references is: {}", response_body);
-                    }
-                    Err(e.into())
-                }
+            let transport_request =
+                request.transport_request(&self.username, &self.password, extra_headers)?;
+            let _in_flight = self.wait_for_limits();
+            let TransportResponse {
+                status,
+                headers,
+                body: mut result,
+            } = self
+                .transport
+                .send(transport_request)
+                .inspect_err(|e| send_error(&self.middleware, &request, e, start.elapsed()))?;
+            let mut body = Vec::new();
+            result
+                .read_to_end(&mut body)
+                .map_err(crate::error::Error::CouldNotReadResponseBody)
+                .inspect_err(|e| send_error(&self.middleware, &request, e, start.elapsed()))?;
+            if let Some(cassette) = &self.cassette {
+                cassette.record(
+                    request.method,
+                    &self.url,
+                    &request.url,
+                    request.body.as_ref(),
+                    status,
+                    &body,
+                )?;
             }
-        }
+            IcingaHttpResponse {
+                status,
+                headers,
+                body,
+            }
+        };
+        after_response(&self.middleware, &request, &mut response, start.elapsed());
+        Ok(response)
     }
 }
@@ -0,0 +1,456 @@
+//! Recording and replay of API interactions
+//!
+//! A cassette is a JSON lines file containing one request/response pair per
+//! line. In
+//! [CassetteMode::Record] every REST API call made by a client is performed
+//! normally and the interaction is appended to the cassette file. In
+//! [CassetteMode::Replay] no network requests are made at all, responses are
+//! served from the cassette file instead and requests without a matching
+//! recorded interaction fail with [crate::error::Error::NoMatchingCassetteInteraction].
+//!
+//! Credentials are never written to the cassette. Authentication happens via
+//! the `Authorization` header which is not recorded, user info in the URL is
+//! stripped and strings in request or response bodies which are exactly the
+//! configured password are replaced by a placeholder.
+//!
+//! URLs are stored relative to the base URL of the instance so a cassette
+//! recorded against one instance can be replayed by a client configured
+//! for another one.
+//!
+//! Event streams are not recorded.
+
+use std::{
+    io::Write as _,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::rest::HttpMethod;
+
+/// the placeholder the password is replaced with in cassettes
+pub const REDACTED: &str = "[REDACTED]";
+
+/// selects whether a client records interactions or replays them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// perform requests normally and append them to the cassette file,
+    /// an existing file is truncated
+    Record(PathBuf),
+    /// serve responses from the cassette file without any network requests
+    Replay(PathBuf),
+}
+
+/// a single recorded request/response pair
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CassetteInteraction {
+    /// the HTTP method of the API endpoint (before any method override)
+    pub method: String,
+    /// the URL of the request relative to the base URL of the instance
+    pub url: String,
+    /// the JSON request body if there was one
+    pub request_body: Option<serde_json::Value>,
+    /// the HTTP status code of the response
+    pub status: u16,
+    /// the response body
+    pub response_body: String,
+}
+
+/// the contents of a cassette file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CassetteContents {
+    /// the recorded interactions in the order they occurred
+    pub interactions: Vec<CassetteInteraction>,
+}
+
+/// the mutable part of a cassette
+#[derive(Debug)]
+struct CassetteState {
+    /// the interactions loaded for replay
+    contents: CassetteContents,
+    /// which of the interactions have already been replayed
+    used: Vec<bool>,
+    /// the cassette file interactions are appended to in record mode
+    file: Option<fs_err::File>,
+}
+
+/// a cassette attached to a client, shared between clones of that client
+#[derive(Debug, Clone)]
+pub struct Cassette {
+    /// whether we record or replay
+    mode: CassetteMode,
+    /// the password to redact, None if it is empty
+    password: Option<String>,
+    /// the loaded interactions and the file recorded to
+    state: Arc<Mutex<CassetteState>>,
+}
+
+impl Cassette {
+    /// create a cassette for the given mode
+    ///
+    /// `password` is the password of the client which is redacted when
+    /// recording
+    ///
+    /// # Errors
+    ///
+    /// in record mode this fails if the cassette file can not be created, in
+    /// replay mode if it can not be read or parsed
+    pub fn new(mode: CassetteMode, password: &str) -> Result<Self, crate::error::Error> {
+        let (contents, file) = match &mode {
+            CassetteMode::Record(path) => (
+                CassetteContents::default(),
+                Some(
+                    fs_err::File::create(path)
+                        .map_err(crate::error::Error::CouldNotWriteCassette)?,
+                ),
+            ),
+            CassetteMode::Replay(path) => (Self::load(path)?, None),
+        };
+        let used = vec![false; contents.interactions.len()];
+        Ok(Self {
+            mode,
+            password: (!password.is_empty()).then(|| password.to_owned()),
+            state: Arc::new(Mutex::new(CassetteState {
+                contents,
+                used,
+                file,
+            })),
+        })
+    }
+
+    /// load the contents of a cassette file
+    ///
+    /// # Errors
+    ///
+    /// this fails if the cassette file can not be read or parsed
+    pub fn load(path: &Path) -> Result<CassetteContents, crate::error::Error> {
+        let content =
+            fs_err::read_to_string(path).map_err(crate::error::Error::CouldNotReadCassette)?;
+        let interactions = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let jd = &mut serde_json::Deserializer::from_str(line);
+                serde_path_to_error::deserialize(jd)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(CassetteContents { interactions })
+    }
+
+    /// returns true if this cassette serves responses instead of performing requests
+    #[must_use]
+    pub const fn is_replay(&self) -> bool {
+        matches!(self.mode, CassetteMode::Replay(_))
+    }
+
+    /// the URL relative to the base URL with any user info removed
+    fn relative_url(base: &url::Url, url: &url::Url) -> String {
+        let mut url = url.to_owned();
+        // setting these can only fail for URLs that can not have user info
+        // at all in which case there is nothing to strip
+        let _no_user_info: Result<(), ()> =
+            url.set_username("").and_then(|()| url.set_password(None));
+        base.make_relative(&url).unwrap_or_else(|| url.to_string())
+    }
+
+    /// returns true if a string is exactly the password
+    fn is_password(&self, s: &str) -> bool {
+        self.password.as_deref() == Some(s)
+    }
+
+    /// replace JSON strings which are exactly the password with a placeholder
+    ///
+    /// returns true if anything was replaced
+    fn redact_json(&self, value: &mut serde_json::Value) -> bool {
+        match value {
+            serde_json::Value::String(s) if self.is_password(s) => {
+                REDACTED.clone_into(s);
+                true
+            }
+            serde_json::Value::Array(vs) => {
+                let mut redacted = false;
+                for v in vs {
+                    redacted |= self.redact_json(v);
+                }
+                redacted
+            }
+            serde_json::Value::Object(m) => {
+                let mut redacted = false;
+                for v in m.values_mut() {
+                    redacted |= self.redact_json(v);
+                }
+                redacted
+            }
+            _ => false,
+        }
+    }
+
+    /// the request body with the password redacted
+    fn redact_request_body(
+        &self,
+        request_body: Option<&serde_json::Value>,
+    ) -> Option<serde_json::Value> {
+        request_body.map(|b| {
+            let mut b = b.to_owned();
+            self.redact_json(&mut b);
+            b
+        })
+    }
+
+    /// the response body with the password redacted
+    ///
+    /// the original text is kept unless something had to be redacted,
+    /// bodies which are not JSON are redacted if they are the password
+    fn redact_response_body(&self, response_body: &[u8]) -> String {
+        let text = String::from_utf8_lossy(response_body).into_owned();
+        match serde_json::from_str::<serde_json::Value>(&text) {
+            Ok(mut value) => {
+                if self.redact_json(&mut value) {
+                    value.to_string()
+                } else {
+                    text
+                }
+            }
+            Err(_) if self.is_password(&text) => REDACTED.to_owned(),
+            Err(_) => text,
+        }
+    }
+
+    /// look up the response for a request in replay mode
+    ///
+    /// each recorded interaction is only used once, identical requests are
+    /// answered by the recorded interactions in the order they were recorded
+    ///
+    /// # Errors
+    ///
+    /// this fails if there is no unused interaction matching the request
+    pub fn replay(
+        &self,
+        method: HttpMethod,
+        base: &url::Url,
+        url: &url::Url,
+        request_body: Option<&serde_json::Value>,
+    ) -> Result<(http::StatusCode, Vec<u8>), crate::error::Error> {
+        let relative_url = Self::relative_url(base, url);
+        let request_body = self.redact_request_body(request_body);
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let CassetteState { contents, used, .. } = &mut *state;
+        let Some((interaction, used)) =
+            contents
+                .interactions
+                .iter()
+                .zip(used.iter_mut())
+                .find(|(interaction, used)| {
+                    !**used
+                        && interaction.method == method.as_str()
+                        && interaction.url == relative_url
+                        && interaction.request_body == request_body
+                })
+        else {
+            drop(state);
+            tracing::error!(%url, %method, "No matching interaction in cassette");
+            return Err(crate::error::Error::NoMatchingCassetteInteraction(
+                method.to_string(),
+                relative_url,
+            ));
+        };
+        *used = true;
+        let status = interaction.status;
+        let response_body = interaction.response_body.as_bytes().to_vec();
+        drop(state);
+        let status = http::StatusCode::from_u16(status)
+            .map_err(|_err| crate::error::Error::InvalidStatusInCassette(status))?;
+        Ok((status, response_body))
+    }
+
+    /// append an interaction to the cassette file in record mode
+    ///
+    /// this does nothing in replay mode
+    ///
+    /// # Errors
+    ///
+    /// this fails if the cassette file can not be written
+    pub fn record(
+        &self,
+        method: HttpMethod,
+        base: &url::Url,
+        url: &url::Url,
+        request_body: Option<&serde_json::Value>,
+        status: http::StatusCode,
+        response_body: &[u8],
+    ) -> Result<(), crate::error::Error> {
+        if self.is_replay() {
+            return Ok(());
+        }
+        let interaction = CassetteInteraction {
+            method: method.to_string(),
+            url: Self::relative_url(base, url),
+            request_body: self.redact_request_body(request_body),
+            status: status.as_u16(),
+            response_body: self.redact_response_body(response_body),
+        };
+        let mut line = serde_json::to_string(&interaction)
+            .map_err(crate::error::Error::CouldNotSerializeCassette)?;
+        line.push('\n');
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let written = state
+            .file
+            .as_mut()
+            .map_or(Ok(()), |file| file.write_all(line.as_bytes()));
+        drop(state);
+        written.map_err(crate::error::Error::CouldNotWriteCassette)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::{collections::BTreeMap, error::Error};
+    use tracing_test::traced_test;
+
+    use crate::{
+        api::{
+            async_client::Icinga2Async, blocking::Icinga2,
+            query::monitoring_objects::host::ListHosts,
+        },
+        types::{
+            enums::object_type::IcingaObjectType,
+            filter::IcingaFilter,
+            join_types::host::IcingaHostJoins,
+            monitoring_objects::host::IcingaHost,
+            query::{QueryResultObjectWithJoins, ResultsWrapper},
+        },
+    };
+
+    /// the response type of host queries in these tests
+    type HostsResponse = ResultsWrapper<QueryResultObjectWithJoins<IcingaHost, IcingaHostJoins>>;
+
+    /// a filter selecting a single host by name
+    fn host_filter(name: &str) -> IcingaFilter {
+        IcingaFilter {
+            object_type: IcingaObjectType::Host,
+            filter: "host.name == name".to_string(),
+            filter_vars: BTreeMap::from([("name".to_string(), serde_json::json!(name))]),
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_record_and_replay() -> Result<(), Box<dyn Error>> {
+        let fake = crate::testing::FakeIcinga::start()?;
+        fake.add_host("cassette-host");
+        fake.add_host("other-host");
+        // credentials which do not occur anywhere else in the fake data,
+        // the fake server does not verify them
+        let config = crate::config::Icinga2Instance {
+            username: "cassette-user".to_owned(),
+            password: "cassette-secret".to_owned(),
+            ..fake.instance_config()
+        };
+        fake.set_attribute(
+            IcingaObjectType::Host,
+            "other-host",
+            "vars",
+            serde_json::json!({
+                "secret": config.password,
+                "user": config.username,
+                "command_line": format!("check_api --user {}", config.username),
+            }),
+        );
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("cassette.jsonl");
+
+        let icinga2 = Icinga2::from_instance_config(&config)?
+            .with_cassette(CassetteMode::Record(path.to_owned()))?;
+        let recorded_filtered: HostsResponse = icinga2.rest(
+            ListHosts::builder()
+                .filter(host_filter("cassette-host"))
+                .build()?,
+        )?;
+        let recorded_all: HostsResponse = icinga2.rest(ListHosts::builder().build()?)?;
+        drop(icinga2);
+        drop(fake);
+
+        let contents = Cassette::load(&path)?;
+        let response: serde_json::Value = serde_json::from_str(
+            &contents
+                .interactions
+                .get(1)
+                .ok_or("second interaction missing")?
+                .response_body,
+        )?;
+        assert_eq!(
+            response.pointer("/results/1/attrs/vars/secret"),
+            Some(&serde_json::json!(REDACTED)),
+            "password is redacted in the cassette"
+        );
+        assert_eq!(
+            response.pointer("/results/1/attrs/vars/user"),
+            Some(&serde_json::json!(config.username)),
+            "the username is not redacted"
+        );
+        assert_eq!(
+            response.pointer("/results/1/attrs/vars/command_line"),
+            Some(&serde_json::json!("check_api --user cassette-user")),
+            "strings merely containing the username are not redacted"
+        );
+        assert_eq!(
+            fs_err::read_to_string(&path)?.lines().count(),
+            2,
+            "one line per interaction"
+        );
+        let urls: Vec<(&str, &str)> = contents
+            .interactions
+            .iter()
+            .map(|i| (i.method.as_str(), i.url.as_str()))
+            .collect();
+        assert_eq!(
+            urls,
+            vec![("GET", "v1/objects/hosts"), ("GET", "v1/objects/hosts")]
+        );
+
+        let icinga2 = Icinga2::from_instance_config(&config)?
+            .with_cassette(CassetteMode::Replay(path.to_owned()))?;
+        let replayed_filtered: HostsResponse = icinga2.rest(
+            ListHosts::builder()
+                .filter(host_filter("cassette-host"))
+                .build()?,
+        )?;
+        assert_eq!(
+            format!("{recorded_filtered:?}"),
+            format!("{replayed_filtered:?}"),
+            "replayed response matches the recorded one"
+        );
+        let unmatched: Result<HostsResponse, _> = icinga2.rest(
+            ListHosts::builder()
+                .filter(host_filter("missing-host"))
+                .build()?,
+        );
+        assert!(
+            matches!(
+                unmatched,
+                Err(crate::error::Error::NoMatchingCassetteInteraction(_, _))
+            ),
+            "requests without a recorded interaction fail"
+        );
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let icinga2 = Icinga2Async::from_instance_config(&config)?
+            .with_cassette(CassetteMode::Replay(path))?;
+        let replayed_all: HostsResponse =
+            runtime.block_on(icinga2.rest(ListHosts::builder().build()?))?;
+        let names = |response: &HostsResponse| -> Vec<String> {
+            response.results.iter().map(|r| r.name.to_owned()).collect()
+        };
+        assert_eq!(
+            names(&recorded_all),
+            names(&replayed_all),
+            "the async client replays the same cassette"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,293 @@
+//! Traits shared by the blocking and async clients
+//!
+//! Code that only needs to call REST API endpoints can be written once
+//! against [IcingaClient] or [IcingaClientAsync] instead of a concrete client.
+//! Implementations only provide the base URL and a way to execute an
+//! [IcingaHttpRequest], everything else is provided on top of that, so mock
+//! implementations for tests only need to return canned responses.
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    api::core::{IcingaHttpRequest, IcingaHttpResponse},
+    types::{
+        lenient::{LenientDeserialize, SchemaDriftReport},
+        rest::{RawResponse, RestApiEndpoint, RestApiResponse},
+    },
+};
+
+/// a blocking Icinga API client
+pub trait IcingaClient {
+    /// the base URL requests are built relative to
+    fn base_url(&self) -> &url::Url;
+
+    /// send a request to Icinga and return the response
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the request could not be sent or the
+    /// response could not be received
+    fn execute(
+        &self,
+        request: &IcingaHttpRequest,
+    ) -> Result<IcingaHttpResponse, crate::error::Error>;
+
+    /// call a REST API endpoint and parse the response
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if encoding, the actual request, or decoding of the response fail
+    fn rest<ApiEndpoint, Res>(&self, api_endpoint: ApiEndpoint) -> Result<Res, crate::error::Error>
+    where
+        ApiEndpoint: RestApiEndpoint,
+        <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
+        Res: DeserializeOwned + std::fmt::Debug + RestApiResponse<ApiEndpoint>,
+    {
+        let request = IcingaHttpRequest::new(self.base_url(), &api_endpoint)?;
+        let response = self.execute(&request)?;
+        request.parse_response(response)
+    }
+
+    /// call a REST API endpoint and parse the response leniently
+    ///
+    /// see [crate::types::lenient] for details
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if encoding, the actual request, or decoding of the response fail
+    fn rest_lenient<ApiEndpoint, Res>(
+        &self,
+        api_endpoint: ApiEndpoint,
+    ) -> Result<(Res, SchemaDriftReport), crate::error::Error>
+    where
+        ApiEndpoint: RestApiEndpoint,
+        <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
+        Res: LenientDeserialize + RestApiResponse<ApiEndpoint>,
+    {
+        let request = IcingaHttpRequest::new(self.base_url(), &api_endpoint)?;
+        let response = self.execute(&request)?;
+        request.parse_lenient(response)
+    }
+
+    /// call any REST API endpoint without parsing the response into a specific type
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if encoding, the actual request, or decoding of the response as JSON fail
+    fn rest_raw<ApiEndpoint>(
+        &self,
+        api_endpoint: ApiEndpoint,
+    ) -> Result<RawResponse, crate::error::Error>
+    where
+        ApiEndpoint: RestApiEndpoint,
+        <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
+    {
+        let request = IcingaHttpRequest::new(self.base_url(), &api_endpoint)?;
+        let response = self.execute(&request)?;
+        request.parse_raw(response)
+    }
+}
+
+/// an async Icinga API client
+///
+/// the request is built before the returned futures are created so they
+/// are Send even if the API endpoint is not
+pub trait IcingaClientAsync: Sync {
+    /// the base URL requests are built relative to
+    fn base_url(&self) -> &url::Url;
+
+    /// send a request to Icinga and return the response
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the request could not be sent or the
+    /// response could not be received
+    fn execute(
+        &self,
+        request: &IcingaHttpRequest,
+    ) -> impl Future<Output = Result<IcingaHttpResponse, crate::error::Error>> + Send;
+
+    /// call a REST API endpoint and parse the response
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if encoding, the actual request, or decoding of the response fail
+    fn rest<ApiEndpoint, Res>(
+        &self,
+        api_endpoint: ApiEndpoint,
+    ) -> impl Future<Output = Result<Res, crate::error::Error>> + Send
+    where
+        ApiEndpoint: RestApiEndpoint,
+        <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
+        Res: DeserializeOwned + std::fmt::Debug + RestApiResponse<ApiEndpoint> + Send,
+    {
+        let request = IcingaHttpRequest::new(self.base_url(), &api_endpoint);
+        async move {
+            let request = request?;
+            let response = self.execute(&request).await?;
+            request.parse_response(response)
+        }
+    }
+
+    /// call a REST API endpoint and parse the response leniently
+    ///
+    /// see [crate::types::lenient] for details
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if encoding, the actual request, or decoding of the response fail
+    fn rest_lenient<ApiEndpoint, Res>(
+        &self,
+        api_endpoint: ApiEndpoint,
+    ) -> impl Future<Output = Result<(Res, SchemaDriftReport), crate::error::Error>> + Send
+    where
+        ApiEndpoint: RestApiEndpoint,
+        <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
+        Res: LenientDeserialize + RestApiResponse<ApiEndpoint> + Send,
+    {
+        let request = IcingaHttpRequest::new(self.base_url(), &api_endpoint);
+        async move {
+            let request = request?;
+            let response = self.execute(&request).await?;
+            request.parse_lenient(response)
+        }
+    }
+
+    /// call any REST API endpoint without parsing the response into a specific type
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if encoding, the actual request, or decoding of the response as JSON fail
+    fn rest_raw<ApiEndpoint>(
+        &self,
+        api_endpoint: ApiEndpoint,
+    ) -> impl Future<Output = Result<RawResponse, crate::error::Error>> + Send
+    where
+        ApiEndpoint: RestApiEndpoint,
+        <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
+    {
+        let request = IcingaHttpRequest::new(self.base_url(), &api_endpoint);
+        async move {
+            let request = request?;
+            let response = self.execute(&request).await?;
+            request.parse_raw(response)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+    use tracing_test::traced_test;
+
+    use crate::{
+        api::query::monitoring_objects::host::ListHosts,
+        types::{
+            monitoring_objects::host::IcingaHost,
+            query::{QueryResultObject, ResultsWrapper},
+        },
+    };
+
+    /// a client returning the same canned response for every request
+    #[derive(Debug)]
+    struct MockClient {
+        /// the base URL of the mock
+        base_url: url::Url,
+        /// the body returned for every request
+        body: Vec<u8>,
+    }
+
+    impl MockClient {
+        /// a mock returning the given hosts for every request
+        fn with_hosts(names: &[&str]) -> Result<Self, Box<dyn Error>> {
+            let results = names
+                .iter()
+                .map(|name| {
+                    serde_json::json!({
+                        "attrs": crate::testing::fixtures::host(name),
+                        "meta": {},
+                        "name": name,
+                        "type": "Host",
+                    })
+                })
+                .collect::<Vec<_>>();
+            Ok(Self {
+                base_url: url::Url::parse("https://icinga.example.com:5665/")?,
+                body: serde_json::to_vec(&serde_json::json!({ "results": results }))?,
+            })
+        }
+
+        /// the canned response
+        fn response(&self) -> IcingaHttpResponse {
+            IcingaHttpResponse {
+                status: http::StatusCode::OK,
+                headers: http::HeaderMap::new(),
+                body: self.body.to_owned(),
+            }
+        }
+    }
+
+    impl IcingaClient for MockClient {
+        fn base_url(&self) -> &url::Url {
+            &self.base_url
+        }
+
+        fn execute(
+            &self,
+            request: &IcingaHttpRequest,
+        ) -> Result<IcingaHttpResponse, crate::error::Error> {
+            assert_eq!(request.url.path(), "/v1/objects/hosts");
+            Ok(self.response())
+        }
+    }
+
+    impl IcingaClientAsync for MockClient {
+        fn base_url(&self) -> &url::Url {
+            &self.base_url
+        }
+
+        async fn execute(
+            &self,
+            request: &IcingaHttpRequest,
+        ) -> Result<IcingaHttpResponse, crate::error::Error> {
+            assert_eq!(request.url.path(), "/v1/objects/hosts");
+            Ok(self.response())
+        }
+    }
+
+    /// code written once against the trait instead of a concrete client
+    fn host_names(client: &impl IcingaClient) -> Result<Vec<String>, Box<dyn Error>> {
+        let hosts: ResultsWrapper<QueryResultObject<IcingaHost>> =
+            client.rest(ListHosts::builder().build()?)?;
+        Ok(hosts.results.into_iter().map(|h| h.name).collect())
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_mock_client() -> Result<(), Box<dyn Error>> {
+        let client = MockClient::with_hosts(&["host1", "host2"])?;
+        assert_eq!(host_names(&client)?, vec!["host1", "host2"]);
+        let response = IcingaClient::rest_raw(&client, ListHosts::builder().build()?)?;
+        assert_eq!(response.status, http::StatusCode::OK);
+        assert_eq!(
+            response.body.pointer("/results/1/name"),
+            Some(&serde_json::json!("host2"))
+        );
+        Ok(())
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn test_mock_client_async() -> Result<(), Box<dyn Error>> {
+        let client = MockClient::with_hosts(&["host1"])?;
+        let hosts: ResultsWrapper<QueryResultObject<IcingaHost>> =
+            IcingaClientAsync::rest(&client, ListHosts::builder().build()?).await?;
+        assert_eq!(
+            hosts.results.first().map(|h| h.name.as_str()),
+            Some("host1")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,482 @@
+//! Request building and response parsing shared by all clients
+//!
+//! An [IcingaHttpRequest] is built from a [RestApiEndpoint] and contains
+//! everything needed to send the request. A client only has to transport it
+//! to Icinga and return the [IcingaHttpResponse], parsing the response into
+//! the endpoint's response type happens here.
+
+use std::str::from_utf8;
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    api::transport::TransportRequest,
+    types::{
+        lenient::{LenientDeserialize, SchemaDriftReport},
+        query::ResultsWrapper,
+        rest::{HttpMethod, RawResponse, RestApiEndpoint},
+    },
+};
+
+/// the maximum number of bytes of the response body included in errors
+/// for responses with an error status
+pub(crate) const MAX_ERROR_BODY_LENGTH: u64 = 64 * 1024;
+
+/// a REST API request ready to be sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcingaHttpRequest {
+    /// the type name of the [RestApiEndpoint] the request was built from
+    pub endpoint: &'static str,
+    /// the HTTP method of the API endpoint (before any method override)
+    pub method: HttpMethod,
+    /// the full URL of the request
+    pub url: url::Url,
+    /// the JSON request body if there is one
+    pub body: Option<serde_json::Value>,
+    /// whether the endpoint returns a single object instead of a `results` array
+    pub returns_single_object: bool,
+}
+
+/// the response to an [IcingaHttpRequest] before parsing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcingaHttpResponse {
+    /// the HTTP status of the response
+    pub status: http::StatusCode,
+    /// the response headers
+    pub headers: http::HeaderMap,
+    /// the raw response body
+    pub body: Vec<u8>,
+}
+
+impl IcingaHttpRequest {
+    /// build the request for an API endpoint relative to the base URL of an instance
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the endpoint can not determine its method, URL
+    /// or request body or the request body can not be serialized
+    pub fn new<ApiEndpoint>(
+        base_url: &url::Url,
+        api_endpoint: &ApiEndpoint,
+    ) -> Result<Self, crate::error::Error>
+    where
+        ApiEndpoint: RestApiEndpoint,
+        <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + std::fmt::Debug,
+    {
+        let body = api_endpoint
+            .request_body()?
+            .map(|b| serde_path_to_error::serialize(&b, serde_json::value::Serializer))
+            .transpose()?;
+        Ok(Self {
+            endpoint: std::any::type_name::<ApiEndpoint>(),
+            method: api_endpoint.method()?,
+            url: api_endpoint.url(base_url)?,
+            body,
+            returns_single_object: api_endpoint.returns_single_object(),
+        })
+    }
+
+    /// the HTTP method to send the request with
+    ///
+    /// GET requests with a body are sent as POST with X-HTTP-Method-Override
+    /// (see [Self::method_override])
+    #[must_use]
+    pub fn http_method(&self) -> HttpMethod {
+        if self.method_override().is_some() {
+            HttpMethod::Post
+        } else {
+            self.method
+        }
+    }
+
+    /// the value of the X-HTTP-Method-Override header if one is needed
+    #[must_use]
+    pub fn method_override(&self) -> Option<&'static str> {
+        if self.method == HttpMethod::Get && self.body.is_some() {
+            Some("GET")
+        } else {
+            None
+        }
+    }
+
+    /// the request as passed to the HTTP backend
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the request body can not be serialized or the
+    /// credentials can not be sent in a header
+    pub fn transport_request(
+        &self,
+        username: &str,
+        password: &str,
+        extra_headers: http::HeaderMap,
+    ) -> Result<TransportRequest, crate::error::Error> {
+        let mut transport_request = TransportRequest::new(
+            self.http_method(),
+            self.url.to_owned(),
+            self.body.as_ref(),
+            username,
+            password,
+        )?;
+        if let Some(method_override) = self.method_override() {
+            tracing::trace!("Sending GET request with body as POST via X-HTTP-Method-Override");
+            transport_request.headers.insert(
+                "X-HTTP-Method-Override",
+                http::header::HeaderValue::from_static(method_override),
+            );
+        }
+        transport_request.headers.extend(extra_headers);
+        if let Some(request_body) = &self.body {
+            tracing::trace!("Request body:\n{:#}", request_body);
+        }
+        Ok(transport_request)
+    }
+
+    /// log the status of the response and its body
+    fn log_response(&self, status: http::StatusCode, response_body: &[u8]) {
+        match from_utf8(response_body) {
+            Ok(response_body) => {
+                tracing::trace!("Response body:\n{}", &response_body);
+            }
+            Err(e) => {
+                tracing::trace!(
+                    "Response body that could not be parsed as utf8 because of {}:\n{:?}",
+                    &e,
+                    &response_body
+                );
+            }
+        }
+        let url = &self.url;
+        let method = &self.method;
+        if status.is_client_error() {
+            tracing::error!(%url, %method, "Icinga2 status error (client error): {:?}", status);
+        } else if status.is_server_error() {
+            tracing::error!(%url, %method, "Icinga2 status error (server error): {:?}", status);
+        }
+    }
+
+    /// log the response and check that it can be parsed
+    ///
+    /// returns false if the endpoint returns a single object and that object
+    /// does not exist
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the response body is empty or the response of
+    /// a single object endpoint has an error status other than 404
+    fn check_response(&self, response: &IcingaHttpResponse) -> Result<bool, crate::error::Error> {
+        self.log_response(response.status, &response.body);
+        if self.returns_single_object && !single_object_exists(response.status, &response.body)? {
+            return Ok(false);
+        }
+        if response.body.is_empty() {
+            Err(crate::error::Error::EmptyResponseBody(response.status))
+        } else {
+            Ok(true)
+        }
+    }
+
+    /// parse the response into the response type of the endpoint
+    ///
+    /// for single object endpoints the response type is deserialized from the
+    /// only element of the `results` array, or from `null` if the object does
+    /// not exist
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the response body is empty or can not be parsed
+    pub(crate) fn parse_response<Res>(
+        &self,
+        response: IcingaHttpResponse,
+    ) -> Result<Res, crate::error::Error>
+    where
+        Res: DeserializeOwned + std::fmt::Debug,
+    {
+        let parsed = if !self.check_response(&response)? {
+            Ok(None)
+        } else if self.returns_single_object {
+            single_object_response_body(&response.body)
+        } else {
+            let jd = &mut serde_json::Deserializer::from_slice(&response.body);
+            serde_path_to_error::deserialize(jd).map(Some)
+        };
+        match parsed {
+            Ok(Some(response_body)) => {
+                tracing::trace!("Parsed response body:\n{:#?}", response_body);
+                Ok(response_body)
+            }
+            Ok(None) => Ok(serde_path_to_error::deserialize(serde_json::Value::Null)?),
+            Err(e) => {
+                let path = e.path();
+                tracing::error!("Parsing failed at path {}: {}", path.to_string(), e.inner());
+                if let Ok(response_body) =
+                    serde_json::from_slice::<serde_json::Value>(&response.body)
+                    && let Some(value) = value_at_path(&response_body, path)
+                {
+                    tracing::error!("Value in location path references is: {}", value);
+                }
+                Err(e.into())
+            }
+        }
+    }
+
+    /// parse the response leniently (see [crate::types::lenient])
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the response body is empty or can not be
+    /// parsed even leniently
+    pub(crate) fn parse_lenient<Res>(
+        &self,
+        response: IcingaHttpResponse,
+    ) -> Result<(Res, SchemaDriftReport), crate::error::Error>
+    where
+        Res: LenientDeserialize,
+    {
+        let response_body = if !self.check_response(&response)? {
+            serde_json::Value::Null
+        } else if self.returns_single_object {
+            single_object_response_body(&response.body)?.unwrap_or_default()
+        } else {
+            let jd = &mut serde_json::Deserializer::from_slice(&response.body);
+            serde_path_to_error::deserialize(jd)?
+        };
+        let mut report = SchemaDriftReport::default();
+        let response = Res::deserialize_lenient(response_body, &mut report)?;
+        if !report.is_empty() {
+            let url = &self.url;
+            let method = &self.method;
+            tracing::warn!(%url, %method, "Icinga2 response does not match the modelled attributes: {:?}", report);
+        }
+        Ok((response, report))
+    }
+
+    /// parse the response body as untyped JSON, an empty body is returned as null
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the response body is not valid JSON
+    pub(crate) fn parse_raw(
+        &self,
+        response: IcingaHttpResponse,
+    ) -> Result<RawResponse, crate::error::Error> {
+        let url = &self.url;
+        let method = &self.method;
+        tracing::trace!(%url, %method, "Raw response body:\n{}", String::from_utf8_lossy(&response.body));
+        let body = if response.body.is_empty() {
+            serde_json::Value::Null
+        } else {
+            let jd = &mut serde_json::Deserializer::from_slice(&response.body);
+            serde_path_to_error::deserialize(jd)?
+        };
+        Ok(RawResponse {
+            status: response.status,
+            headers: response.headers,
+            body,
+        })
+    }
+}
+
+/// check the status of the response of an endpoint returning a single object
+///
+/// returns false if the object does not exist
+///
+/// # Errors
+///
+/// this returns an error if the response has an error status other than 404
+fn single_object_exists(
+    status: http::StatusCode,
+    response_body: &[u8],
+) -> Result<bool, crate::error::Error> {
+    if status == http::StatusCode::NOT_FOUND {
+        return Ok(false);
+    }
+    if !status.is_success() {
+        let error_body = usize::try_from(MAX_ERROR_BODY_LENGTH)
+            .ok()
+            .and_then(|max| response_body.get(..max))
+            .unwrap_or(response_body);
+        return Err(crate::error::Error::ErrorStatus(
+            status,
+            String::from_utf8_lossy(error_body).into_owned(),
+        ));
+    }
+    Ok(true)
+}
+
+/// deserialize the only element of the `results` array in the response body
+/// of an endpoint returning a single object, None if the array is empty
+///
+/// # Errors
+///
+/// this returns an error if the response body can not be parsed
+fn single_object_response_body<T>(
+    response_body: &[u8],
+) -> Result<Option<T>, serde_path_to_error::Error<serde_json::Error>>
+where
+    T: DeserializeOwned,
+{
+    let jd = &mut serde_json::Deserializer::from_slice(response_body);
+    let response: ResultsWrapper<T> = serde_path_to_error::deserialize(jd)?;
+    Ok(response.results.into_iter().next())
+}
+
+/// log an error sending a request or receiving the response and call the
+/// `on_error` hooks of all middleware
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub(crate) fn send_error(
+    middleware: &[std::sync::Arc<dyn crate::api::middleware::Middleware>],
+    request: &IcingaHttpRequest,
+    error: &crate::error::Error,
+    elapsed: std::time::Duration,
+) {
+    let url = &request.url;
+    let method = &request.method;
+    tracing::error!(%url, %method, "Icinga2 send error: {:?}", error);
+    let middleware_error = crate::api::middleware::MiddlewareError {
+        endpoint: request.endpoint,
+        method: request.method,
+        url: &request.url,
+        error,
+        elapsed,
+    };
+    for middleware in middleware {
+        middleware.on_error(&middleware_error);
+    }
+}
+
+/// call the `before_request` hooks of all middleware
+///
+/// returns the request with the body as modified by the middleware and the
+/// headers added by the middleware
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub(crate) fn before_request<'r>(
+    middleware: &[std::sync::Arc<dyn crate::api::middleware::Middleware>],
+    request: &'r IcingaHttpRequest,
+) -> (std::borrow::Cow<'r, IcingaHttpRequest>, http::HeaderMap) {
+    if middleware.is_empty() {
+        return (std::borrow::Cow::Borrowed(request), http::HeaderMap::new());
+    }
+    let mut middleware_request = crate::api::middleware::MiddlewareRequest {
+        endpoint: request.endpoint,
+        method: request.method,
+        url: &request.url,
+        headers: http::HeaderMap::new(),
+        body: request.body.clone(),
+    };
+    for middleware in middleware {
+        middleware.before_request(&mut middleware_request);
+    }
+    let crate::api::middleware::MiddlewareRequest { headers, body, .. } = middleware_request;
+    if body == request.body {
+        (std::borrow::Cow::Borrowed(request), headers)
+    } else {
+        let mut request = request.to_owned();
+        request.body = body;
+        (std::borrow::Cow::Owned(request), headers)
+    }
+}
+
+/// call the `after_response` hooks of all middleware
+///
+/// the response body is replaced by the one modified by the middleware
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub(crate) fn after_response(
+    middleware: &[std::sync::Arc<dyn crate::api::middleware::Middleware>],
+    request: &IcingaHttpRequest,
+    response: &mut IcingaHttpResponse,
+    elapsed: std::time::Duration,
+) {
+    if middleware.is_empty() {
+        return;
+    }
+    let mut middleware_response = crate::api::middleware::MiddlewareResponse {
+        endpoint: request.endpoint,
+        method: request.method,
+        url: &request.url,
+        status: response.status,
+        headers: &response.headers,
+        body: std::mem::take(&mut response.body),
+        elapsed,
+    };
+    for middleware in middleware {
+        middleware.after_response(&mut middleware_response);
+    }
+    response.body = middleware_response.body;
+}
+
+/// find the value a [serde_path_to_error::Path] references
+pub(crate) fn value_at_path<'a>(
+    value: &'a serde_json::Value,
+    path: &serde_path_to_error::Path,
+) -> Option<&'a serde_json::Value> {
+    path.iter()
+        .try_fold(value, |value, segment| match (value, segment) {
+            (serde_json::Value::Array(vs), serde_path_to_error::Segment::Seq { index }) => {
+                vs.get(*index)
+            }
+            (serde_json::Value::Object(m), serde_path_to_error::Segment::Map { key }) => m.get(key),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+
+    /// a request to an endpoint returning a single object
+    fn single_object_request() -> Result<IcingaHttpRequest, Box<dyn Error>> {
+        Ok(IcingaHttpRequest {
+            endpoint: "single object endpoint",
+            method: HttpMethod::Get,
+            url: url::Url::parse("https://localhost:5665/v1/objects/hosts/host1")?,
+            body: None,
+            returns_single_object: true,
+        })
+    }
+
+    /// a response with the given status and body
+    fn response(status: http::StatusCode, body: &str) -> IcingaHttpResponse {
+        IcingaHttpResponse {
+            status,
+            headers: http::HeaderMap::new(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_single_object_response() -> Result<(), Box<dyn Error>> {
+        let request = single_object_request()?;
+        let object: Option<serde_json::Value> = request.parse_response(response(
+            http::StatusCode::OK,
+            r#"{"results":[{"name":"host1"}]}"#,
+        ))?;
+        assert_eq!(object, Some(serde_json::json!({"name": "host1"})));
+        let object: Option<serde_json::Value> =
+            request.parse_response(response(http::StatusCode::OK, r#"{"results":[]}"#))?;
+        assert_eq!(object, None);
+        let object: Option<serde_json::Value> = request.parse_response(response(
+            http::StatusCode::NOT_FOUND,
+            r#"{"error":404,"status":"No objects found."}"#,
+        ))?;
+        assert_eq!(object, None);
+        for (status, body) in [
+            (
+                http::StatusCode::UNAUTHORIZED,
+                "<html><body>Unauthorized</body></html>",
+            ),
+            (http::StatusCode::SERVICE_UNAVAILABLE, ""),
+        ] {
+            match request.parse_response::<Option<serde_json::Value>>(response(status, body)) {
+                Err(crate::error::Error::ErrorStatus(error_status, error_body)) => {
+                    assert_eq!(error_status, status);
+                    assert_eq!(error_body, body);
+                }
+                other => return Err(format!("unexpected result {other:?}").into()),
+            }
+        }
+        Ok(())
+    }
+}
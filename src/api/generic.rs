@@ -0,0 +1,199 @@
+//! Generic REST API endpoint for calls this crate does not model yet
+//!
+//! A [GenericEndpoint] is described only by its method, path, query parameters
+//! and JSON body. It is usually used with the `rest_raw` methods of the clients
+//! but can also be used with `rest` and [serde_json::Value] as the response type.
+
+use crate::types::rest::{HttpMethod, RestApiEndpoint, RestApiResponse};
+
+/// REST API Endpoint for any call described by method, path, query and body
+#[derive(Debug, Clone, PartialEq, Eq, derive_builder::Builder)]
+#[builder(
+    build_fn(error = "crate::error::Error", validate = "Self::validate"),
+    derive(Debug)
+)]
+pub struct GenericEndpoint {
+    /// the HTTP method, GET requests with a body are sent as POST with X-HTTP-Method-Override
+    #[builder(default = "HttpMethod::Get")]
+    pub method: HttpMethod,
+    /// the path relative to the base URL of the instance, e.g. `v1/status`
+    #[builder(setter(into))]
+    pub path: String,
+    /// the query parameters, the same key may be used more than once
+    #[builder(setter(each(name = "query_pair")), default)]
+    pub query: Vec<(String, String)>,
+    /// the JSON request body
+    #[builder(setter(strip_option), default)]
+    pub body: Option<serde_json::Value>,
+}
+
+impl GenericEndpoint {
+    /// create a new builder for this endpoint
+    ///
+    /// this is usually the first step to calling this REST API endpoint
+    #[must_use]
+    pub fn builder() -> GenericEndpointBuilder {
+        GenericEndpointBuilder::default()
+    }
+}
+
+impl GenericEndpointBuilder {
+    /// makes sure the path can not leave the API of the instance
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the path is absolute, has a scheme or an
+    /// authority or contains `..` segments
+    fn validate(&self) -> Result<(), crate::error::Error> {
+        if let Some(path) = &self.path
+            && !is_relative_path(path)
+        {
+            Err(crate::error::Error::AbsolutePathInGenericEndpoint(
+                path.to_owned(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// whether the path is a plain relative path which stays below the base URL
+/// when joined to it
+fn is_relative_path(path: &str) -> bool {
+    // backslashes are treated like slashes in http and https URLs
+    if path.starts_with(['/', '\\']) || url::Url::parse(path).is_ok() {
+        return false;
+    }
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    !path
+        .split(['/', '\\'])
+        .any(|segment| segment.to_ascii_lowercase().replace("%2e", ".") == "..")
+}
+
+impl RestApiEndpoint for GenericEndpoint {
+    type RequestBody = serde_json::Value;
+
+    fn method(&self) -> Result<HttpMethod, crate::error::Error> {
+        Ok(self.method)
+    }
+
+    fn url(&self, base_url: &url::Url) -> Result<url::Url, crate::error::Error> {
+        let mut url = base_url
+            .join(&self.path)
+            .map_err(crate::error::Error::CouldNotParseUrlFragment)?;
+        // the part of the base path join keeps, everything up to the last slash
+        let base_path = base_url
+            .path()
+            .rsplit_once('/')
+            .map_or("", |(directory, _)| directory);
+        if url.origin() != base_url.origin() || !url.path().starts_with(base_path) {
+            return Err(crate::error::Error::AbsolutePathInGenericEndpoint(
+                self.path.to_owned(),
+            ));
+        }
+        if !self.query.is_empty() {
+            url.query_pairs_mut().extend_pairs(&self.query);
+        }
+        Ok(url)
+    }
+
+    fn request_body(
+        &self,
+    ) -> Result<Option<std::borrow::Cow<'_, Self::RequestBody>>, crate::error::Error>
+    where
+        Self::RequestBody: Clone + serde::Serialize + std::fmt::Debug,
+    {
+        Ok(self.body.as_ref().map(std::borrow::Cow::Borrowed))
+    }
+}
+
+impl RestApiResponse<GenericEndpoint> for serde_json::Value {}
+
+#[cfg(test)]
+#[cfg(feature = "blocking")]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+    use tracing_test::traced_test;
+
+    use crate::api::blocking::Icinga2;
+
+    #[traced_test]
+    #[test]
+    fn test_generic_endpoint_raw() -> Result<(), Box<dyn Error>> {
+        let fake = crate::testing::FakeIcinga::start()?;
+        fake.add_host("generic-host");
+        let icinga2 = Icinga2::from_instance_config(&fake.instance_config())?;
+        let response = icinga2.rest_raw(
+            GenericEndpoint::builder()
+                .path("v1/objects/hosts")
+                .query_pair(("attrs".to_string(), "name".to_string()))
+                .build()?,
+        )?;
+        assert_eq!(response.status, http::StatusCode::OK);
+        assert_eq!(
+            response.body.pointer("/results/0/attrs/name"),
+            Some(&serde_json::json!("generic-host"))
+        );
+        let response = icinga2.rest_raw(
+            GenericEndpoint::builder()
+                .path("v1/not-modelled")
+                .body(serde_json::json!({ "pretty": true }))
+                .build()?,
+        )?;
+        assert_eq!(response.status, http::StatusCode::NOT_FOUND);
+        assert!(response.body.get("status").is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_generic_endpoint_absolute_path() {
+        for path in [
+            "/v1/status",
+            "https://evil.example/x",
+            "//evil.example/x",
+            "\\\\evil.example/x",
+            "../../x",
+            "v1/../../x",
+            "v1/%2E%2e/x",
+            "localhost:5665/v1/status",
+        ] {
+            assert!(
+                matches!(
+                    GenericEndpoint::builder().path(path).build(),
+                    Err(crate::error::Error::AbsolutePathInGenericEndpoint(_))
+                ),
+                "{path} is rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_generic_endpoint_url() -> Result<(), Box<dyn Error>> {
+        let base_url = url::Url::parse("https://icinga.example:5665/api/")?;
+        let endpoint = GenericEndpoint::builder()
+            .path("v1/status")
+            .query_pair(("pretty".to_string(), "1".to_string()))
+            .build()?;
+        assert_eq!(
+            endpoint.url(&base_url)?.as_str(),
+            "https://icinga.example:5665/api/v1/status?pretty=1"
+        );
+        // bypassing the builder validation is still caught when building the URL
+        for path in ["https://evil.example/x", "//evil.example/x", "../x"] {
+            let endpoint = GenericEndpoint {
+                path: path.to_owned(),
+                ..endpoint.clone()
+            };
+            assert!(
+                matches!(
+                    endpoint.url(&base_url),
+                    Err(crate::error::Error::AbsolutePathInGenericEndpoint(_))
+                ),
+                "{path} is rejected"
+            );
+        }
+        Ok(())
+    }
+}
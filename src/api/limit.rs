@@ -0,0 +1,182 @@
+//! Client side limits on the requests sent to an Icinga instance
+//!
+//! Both limits are configured via [crate::config::Icinga2Instance] and are
+//! shared between clones of a client.
+
+use std::{
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use crate::config::Icinga2RateLimit;
+
+/// the state of the token bucket
+#[derive(Debug)]
+struct TokenBucket {
+    /// the number of tokens currently available, negative if requests
+    /// already reserved tokens that have not been refilled yet
+    tokens: f64,
+    /// the last time the tokens were refilled
+    last_refill: Instant,
+}
+
+/// a token bucket rate limiter
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    /// tokens added per second
+    rate: f64,
+    /// maximum number of tokens in the bucket
+    burst: f64,
+    /// the bucket itself
+    bucket: Mutex<TokenBucket>,
+}
+
+impl RateLimiter {
+    /// create a new rate limiter with a full bucket
+    pub(crate) fn new(config: &Icinga2RateLimit) -> Self {
+        Self::new_at(config, Instant::now())
+    }
+
+    /// create a new rate limiter with a bucket that was full at `now`
+    fn new_at(config: &Icinga2RateLimit, now: Instant) -> Self {
+        let burst = f64::from(config.burst.get());
+        Self {
+            rate: f64::from(config.requests_per_second.get()),
+            burst,
+            bucket: Mutex::new(TokenBucket {
+                tokens: burst,
+                last_refill: now,
+            }),
+        }
+    }
+
+    /// take a token from the bucket and return how long the caller has to
+    /// wait before it may send its request
+    ///
+    /// requests are served in the order they call this even if they have to wait
+    pub(crate) fn reserve(&self) -> Duration {
+        self.reserve_at(Instant::now())
+    }
+
+    /// [Self::reserve] for a request made at `now`
+    fn reserve_at(&self, now: Instant) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+        let elapsed = now.saturating_duration_since(bucket.last_refill);
+        let refilled = elapsed.as_secs_f64().mul_add(self.rate, bucket.tokens);
+        let tokens = refilled.min(self.burst) - 1.0;
+        bucket.tokens = tokens;
+        bucket.last_refill = now;
+        drop(bucket);
+        if tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::try_from_secs_f64(-tokens / self.rate).unwrap_or(Duration::MAX)
+        }
+    }
+}
+
+/// a limit on the number of requests in flight for the blocking client
+#[cfg(feature = "blocking-client")]
+#[derive(Debug)]
+pub(crate) struct InFlightLimit {
+    /// the maximum number of requests in flight
+    max: usize,
+    /// the number of requests currently in flight
+    in_flight: Mutex<usize>,
+    /// notified whenever a request finishes
+    finished: std::sync::Condvar,
+}
+
+#[cfg(feature = "blocking-client")]
+impl InFlightLimit {
+    /// create a new limit
+    pub(crate) const fn new(max: std::num::NonZeroUsize) -> Self {
+        Self {
+            max: max.get(),
+            in_flight: Mutex::new(0),
+            finished: std::sync::Condvar::new(),
+        }
+    }
+
+    /// block until fewer than the maximum number of requests are in flight
+    ///
+    /// the request counts as in flight until the returned guard is dropped
+    pub(crate) fn acquire(self: &std::sync::Arc<Self>) -> InFlightGuard {
+        let mut in_flight = self
+            .finished
+            .wait_while(
+                self.in_flight
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner),
+                |in_flight| *in_flight >= self.max,
+            )
+            .unwrap_or_else(PoisonError::into_inner);
+        *in_flight = in_flight.saturating_add(1);
+        drop(in_flight);
+        InFlightGuard {
+            limit: std::sync::Arc::clone(self),
+        }
+    }
+}
+
+/// marks a request as in flight while it exists
+#[cfg(feature = "blocking-client")]
+#[derive(Debug)]
+pub(crate) struct InFlightGuard {
+    /// the limit this request counts against
+    limit: std::sync::Arc<InFlightLimit>,
+}
+
+#[cfg(feature = "blocking-client")]
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut in_flight = self
+            .limit
+            .in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *in_flight = in_flight.saturating_sub(1);
+        drop(in_flight);
+        self.limit.finished.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::num::NonZeroU32;
+
+    #[test]
+    fn test_rate_limiter_burst() -> Result<(), Box<dyn std::error::Error>> {
+        let start = Instant::now();
+        let limiter = RateLimiter::new_at(
+            &Icinga2RateLimit {
+                requests_per_second: NonZeroU32::new(1).ok_or("zero")?,
+                burst: NonZeroU32::new(2).ok_or("zero")?,
+            },
+            start,
+        );
+        assert_eq!(limiter.reserve_at(start), Duration::ZERO);
+        assert_eq!(limiter.reserve_at(start), Duration::ZERO);
+        assert_eq!(
+            limiter.reserve_at(start),
+            Duration::from_secs(1),
+            "third request waits for a refill"
+        );
+        assert_eq!(
+            limiter.reserve_at(start),
+            Duration::from_secs(2),
+            "fourth request queues behind the third"
+        );
+        let later = start
+            .checked_add(Duration::from_secs(3))
+            .ok_or("overflow")?;
+        assert_eq!(
+            limiter.reserve_at(later),
+            Duration::ZERO,
+            "the bucket refilled after the reserved requests were sent"
+        );
+        Ok(())
+    }
+}
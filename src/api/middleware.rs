@@ -0,0 +1,231 @@
+//! Hooks called by the clients around every REST API call
+//!
+//! Middleware is installed on a client with `with_middleware` and is shared
+//! between clones of that client. All installed middleware is called in the
+//! order it was installed, before the request is sent and after the response
+//! body has been received but before it is parsed, or when the request could
+//! not be sent or the response could not be received.
+//!
+//! Middleware may modify the request body before it is sent and the
+//! response body before it is parsed, e.g. to redact sensitive values. The
+//! modified request body is also what is recorded to or matched against a
+//! cassette, the response body is recorded before middleware is called.
+//!
+//! For `rest_stream` the response hooks are called once the response headers
+//! have been received, the body passed to them is empty and modifications to
+//! it are ignored.
+//!
+//! Event streams do not pass through middleware.
+
+use std::time::Duration;
+
+use crate::types::rest::HttpMethod;
+
+/// the information about a request available to middleware
+#[derive(Debug)]
+pub struct MiddlewareRequest<'a> {
+    /// the type name of the [crate::types::rest::RestApiEndpoint] used for the request
+    pub endpoint: &'static str,
+    /// the HTTP method of the API endpoint (before any method override)
+    pub method: HttpMethod,
+    /// the full URL of the request
+    pub url: &'a url::Url,
+    /// additional headers to send with the request, middleware may add to these
+    pub headers: http::HeaderMap,
+    /// the JSON request body if there is one, middleware may modify it
+    pub body: Option<serde_json::Value>,
+}
+
+/// the information about a response available to middleware
+#[derive(Debug)]
+pub struct MiddlewareResponse<'a> {
+    /// the type name of the [crate::types::rest::RestApiEndpoint] used for the request
+    pub endpoint: &'static str,
+    /// the HTTP method of the API endpoint (before any method override)
+    pub method: HttpMethod,
+    /// the full URL of the request
+    pub url: &'a url::Url,
+    /// the HTTP status of the response
+    pub status: http::StatusCode,
+    /// the response headers, empty for responses replayed from a cassette
+    pub headers: &'a http::HeaderMap,
+    /// the raw response body, empty for `rest_stream`, middleware may modify it
+    pub body: Vec<u8>,
+    /// the time between sending the request and receiving the complete response body
+    pub elapsed: Duration,
+}
+
+/// the information about a failed request available to middleware
+#[derive(Debug)]
+pub struct MiddlewareError<'a> {
+    /// the type name of the [crate::types::rest::RestApiEndpoint] used for the request
+    pub endpoint: &'static str,
+    /// the HTTP method of the API endpoint (before any method override)
+    pub method: HttpMethod,
+    /// the full URL of the request
+    pub url: &'a url::Url,
+    /// why sending the request or receiving the response failed
+    pub error: &'a crate::error::Error,
+    /// the time between sending the request and the failure
+    pub elapsed: Duration,
+}
+
+/// hooks called around every REST API call of a client
+///
+/// all methods do nothing by default so implementations only need to
+/// implement the ones they are interested in
+pub trait Middleware: std::fmt::Debug + Send + Sync {
+    /// called before the request is sent
+    fn before_request(&self, _request: &mut MiddlewareRequest<'_>) {}
+
+    /// called after the response body has been received
+    fn after_response(&self, _response: &mut MiddlewareResponse<'_>) {}
+
+    /// called instead of [Self::after_response] if the request could not be
+    /// sent or the response could not be received
+    fn on_error(&self, _error: &MiddlewareError<'_>) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::{
+        collections::BTreeMap,
+        error::Error,
+        sync::{Arc, Mutex, PoisonError},
+    };
+    use tracing_test::traced_test;
+
+    use crate::{
+        api::{blocking::Icinga2, query::monitoring_objects::host::ListHosts},
+        types::{
+            enums::object_type::IcingaObjectType, filter::IcingaFilter,
+            join_types::host::IcingaHostJoins, monitoring_objects::host::IcingaHost,
+            query::QueryResultObjectWithJoins, query::ResultsWrapper,
+        },
+    };
+
+    /// middleware remembering what it saw
+    #[derive(Debug, Default)]
+    struct RecordingMiddleware {
+        /// one line per callback
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Middleware for RecordingMiddleware {
+        fn before_request(&self, request: &mut MiddlewareRequest<'_>) {
+            request.headers.insert(
+                "X-Audit",
+                http::header::HeaderValue::from_static("middleware-test"),
+            );
+            if let Some(serde_json::Value::Object(body)) = &mut request.body {
+                body.insert("audit".to_owned(), serde_json::json!("middleware-test"));
+            }
+            self.calls
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(format!(
+                    "before {} {} {}",
+                    request.endpoint.rsplit("::").next().unwrap_or_default(),
+                    request.method,
+                    request.url.path()
+                ));
+        }
+
+        fn after_response(&self, response: &mut MiddlewareResponse<'_>) {
+            response.body = String::from_utf8_lossy(&response.body)
+                .replace("middleware-host", "redacted-host")
+                .into_bytes();
+            self.calls
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(format!(
+                    "after {} {} {} {}",
+                    response.endpoint.rsplit("::").next().unwrap_or_default(),
+                    response.method,
+                    response.url.path(),
+                    response.status
+                ));
+        }
+
+        fn on_error(&self, error: &MiddlewareError<'_>) {
+            self.calls
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(format!(
+                    "error {} {} {}",
+                    error.endpoint.rsplit("::").next().unwrap_or_default(),
+                    error.method,
+                    error.url.path()
+                ));
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_middleware_callbacks() -> Result<(), Box<dyn Error>> {
+        let fake = crate::testing::FakeIcinga::start()?;
+        fake.add_host("middleware-host");
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let icinga2 = Icinga2::from_instance_config(&fake.instance_config())?.with_middleware(
+            RecordingMiddleware {
+                calls: Arc::clone(&calls),
+            },
+        );
+        let response: ResultsWrapper<QueryResultObjectWithJoins<IcingaHost, IcingaHostJoins>> =
+            icinga2.rest(
+                ListHosts::builder()
+                    .filter(IcingaFilter {
+                        object_type: IcingaObjectType::Host,
+                        filter: "host.name == host_name".to_owned(),
+                        filter_vars: BTreeMap::from([(
+                            "host_name".to_owned(),
+                            serde_json::json!("middleware-host"),
+                        )]),
+                    })
+                    .build()?,
+            )?;
+        assert_eq!(
+            response
+                .results
+                .iter()
+                .map(|result| result.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["redacted-host"],
+            "the response body modified by the middleware is parsed"
+        );
+        let requests = fake.requests();
+        let request = requests.last().ok_or("no request received")?;
+        assert_eq!(
+            request
+                .headers
+                .get("X-Audit")
+                .and_then(|value| value.to_str().ok()),
+            Some("middleware-test"),
+            "the header added by the middleware is sent"
+        );
+        assert_eq!(
+            request.body.get("audit"),
+            Some(&serde_json::json!("middleware-test")),
+            "the request body modified by the middleware is sent"
+        );
+        drop(fake);
+        let failed: Result<
+            ResultsWrapper<QueryResultObjectWithJoins<IcingaHost, IcingaHostJoins>>,
+            _,
+        > = icinga2.rest(ListHosts::builder().build()?);
+        assert!(failed.is_err(), "the fake server is gone");
+        let calls = calls.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        assert_eq!(
+            calls,
+            vec![
+                "before ListHosts GET /v1/objects/hosts".to_string(),
+                "after ListHosts GET /v1/objects/hosts 200 OK".to_string(),
+                "before ListHosts GET /v1/objects/hosts".to_string(),
+                "error ListHosts GET /v1/objects/hosts".to_string(),
+            ]
+        );
+        Ok(())
+    }
+}
@@ -6,11 +6,26 @@ crate::types::query::query!(
     monitoring_objects,
     api_user,
     IcingaApiUser,
+    IcingaApiUserAttribute,
+    IcingaApiUserPartial,
     IcingaObjectType::ApiUser,
     "v1/objects/apiusers"
 );
 
+crate::types::query::get_object!(
+    GetApiUser,
+    GetApiUserBuilder,
+    monitoring_objects,
+    api_user,
+    IcingaApiUser,
+    IcingaApiUserAttribute,
+    IcingaApiUserPartial,
+    "v1/objects/apiusers",
+    name: String
+);
+
 #[cfg(test)]
+#[cfg(feature = "blocking")]
 mod test {
     use super::*;
     use crate::api::blocking::Icinga2;
@@ -20,10 +35,8 @@ mod test {
     #[traced_test]
     #[test]
     fn test_api_users() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
         let api_endpoint = ListApiUsers::builder()
             .meta([IcingaMetadataType::UsedBy, IcingaMetadataType::Location])
             .build()?;
@@ -8,15 +8,31 @@ crate::types::query::query_with_joins!(
     monitoring_objects,
     host,
     IcingaHost,
+    IcingaHostAttribute,
+    IcingaHostPartial,
     IcingaHostJoinTypes,
     IcingaHostJoins,
     IcingaObjectType::Host,
     "v1/objects/hosts"
 );
 
+crate::types::query::get_object!(
+    GetHost,
+    GetHostBuilder,
+    monitoring_objects,
+    host,
+    IcingaHost,
+    IcingaHostAttribute,
+    IcingaHostPartial,
+    "v1/objects/hosts",
+    name: crate::types::names::IcingaHostName
+);
+
 #[cfg(test)]
+#[cfg(all(feature = "async", feature = "blocking"))]
 mod test {
     use super::*;
+    use pretty_assertions::assert_eq;
     use std::error::Error;
     use tracing_test::traced_test;
 
@@ -28,10 +44,8 @@ mod test {
     #[traced_test]
     #[test]
     fn test_hosts() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
         let api_endpoint = ListHosts::builder()
             .joins(IcingaJoins::AllJoins)
             .meta([IcingaMetadataType::UsedBy, IcingaMetadataType::Location])
@@ -44,10 +58,8 @@ mod test {
     #[traced_test]
     #[test]
     fn test_hosts_filtered() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
         let api_endpoint = ListHosts::builder()
             .filter(IcingaFilter {
                 object_type: IcingaObjectType::Host,
@@ -66,10 +78,8 @@ mod test {
     #[traced_test]
     #[tokio::test]
     async fn test_hosts_async() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2Async::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2Async::from_instance_config(&config)?;
         let api_endpoint = ListHosts::builder()
             .joins(IcingaJoins::AllJoins)
             .meta([IcingaMetadataType::UsedBy, IcingaMetadataType::Location])
@@ -78,4 +88,34 @@ mod test {
             icinga2.rest(api_endpoint).await?;
         Ok(())
     }
+
+    #[traced_test]
+    #[test]
+    fn test_get_host() -> Result<(), Box<dyn Error>> {
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
+        let hosts: ResultsWrapper<QueryResultObject<IcingaHostPartial>> = icinga2.rest(
+            ListHosts::builder()
+                .attrs([IcingaHostAttribute::Name])
+                .build()?,
+        )?;
+        let Some(first) = hosts.results.first() else {
+            return Ok(());
+        };
+        let host: Option<QueryResultObject<IcingaHost>> = icinga2.rest(
+            GetHost::builder()
+                .name(crate::types::names::IcingaHostName(first.name.to_owned()))
+                .build()?,
+        )?;
+        assert_eq!(host.map(|h| h.name), Some(first.name.to_owned()));
+        let missing: Option<QueryResultObject<IcingaHost>> = icinga2.rest(
+            GetHost::builder()
+                .name(crate::types::names::IcingaHostName(
+                    "this-host-does-not-exist".to_string(),
+                ))
+                .build()?,
+        )?;
+        assert!(missing.is_none());
+        Ok(())
+    }
 }
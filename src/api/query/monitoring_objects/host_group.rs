@@ -6,11 +6,26 @@ crate::types::query::query!(
     monitoring_objects,
     host_group,
     IcingaHostGroup,
+    IcingaHostGroupAttribute,
+    IcingaHostGroupPartial,
     IcingaObjectType::HostGroup,
     "v1/objects/hostgroups"
 );
 
+crate::types::query::get_object!(
+    GetHostGroup,
+    GetHostGroupBuilder,
+    monitoring_objects,
+    host_group,
+    IcingaHostGroup,
+    IcingaHostGroupAttribute,
+    IcingaHostGroupPartial,
+    "v1/objects/hostgroups",
+    name: crate::types::names::IcingaHostGroupName
+);
+
 #[cfg(test)]
+#[cfg(feature = "blocking")]
 mod test {
     use super::*;
     use crate::api::blocking::Icinga2;
@@ -20,10 +35,8 @@ mod test {
     #[traced_test]
     #[test]
     fn test_host_groups() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
         let api_endpoint = ListHostGroups::builder()
             .meta([IcingaMetadataType::UsedBy, IcingaMetadataType::Location])
             .build()?;
@@ -6,11 +6,26 @@ crate::types::query::query!(
     monitoring_objects,
     notification_command,
     IcingaNotificationCommand,
+    IcingaNotificationCommandAttribute,
+    IcingaNotificationCommandPartial,
     IcingaObjectType::NotificationCommand,
     "v1/objects/notificationcommands"
 );
 
+crate::types::query::get_object!(
+    GetNotificationCommand,
+    GetNotificationCommandBuilder,
+    monitoring_objects,
+    notification_command,
+    IcingaNotificationCommand,
+    IcingaNotificationCommandAttribute,
+    IcingaNotificationCommandPartial,
+    "v1/objects/notificationcommands",
+    name: crate::types::names::IcingaNotificationCommandName
+);
+
 #[cfg(test)]
+#[cfg(feature = "blocking")]
 mod test {
     use super::*;
     use crate::api::blocking::Icinga2;
@@ -20,10 +35,8 @@ mod test {
     #[traced_test]
     #[test]
     fn test_notification_commands() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
         let api_endpoint = ListNotificationCommands::builder()
             .meta([IcingaMetadataType::UsedBy, IcingaMetadataType::Location])
             .build()?;
@@ -6,11 +6,26 @@ crate::types::query::query!(
     monitoring_objects,
     scheduled_downtime,
     IcingaScheduledDowntime,
+    IcingaScheduledDowntimeAttribute,
+    IcingaScheduledDowntimePartial,
     IcingaObjectType::ScheduledDowntime,
     "v1/objects/scheduleddowntimes"
 );
 
+crate::types::query::get_object!(
+    GetScheduledDowntime,
+    GetScheduledDowntimeBuilder,
+    monitoring_objects,
+    scheduled_downtime,
+    IcingaScheduledDowntime,
+    IcingaScheduledDowntimeAttribute,
+    IcingaScheduledDowntimePartial,
+    "v1/objects/scheduleddowntimes",
+    name: crate::types::names::IcingaScheduledDowntimeName
+);
+
 #[cfg(test)]
+#[cfg(feature = "blocking")]
 mod test {
     use super::*;
     use crate::api::blocking::Icinga2;
@@ -20,10 +35,8 @@ mod test {
     #[traced_test]
     #[test]
     fn test_scheduled_downtimes() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
         let api_endpoint = ListScheduledDowntimes::builder()
             .meta([IcingaMetadataType::UsedBy, IcingaMetadataType::Location])
             .build()?;
@@ -8,15 +8,32 @@ crate::types::query::query_with_joins!(
     monitoring_objects,
     service,
     IcingaService,
+    IcingaServiceAttribute,
+    IcingaServicePartial,
     IcingaServiceJoinTypes,
     IcingaServiceJoins,
     IcingaObjectType::Service,
     "v1/objects/services"
 );
 
+crate::types::query::get_object!(
+    GetService,
+    GetServiceBuilder,
+    monitoring_objects,
+    service,
+    IcingaService,
+    IcingaServiceAttribute,
+    IcingaServicePartial,
+    "v1/objects/services",
+    host_name: crate::types::names::IcingaHostName,
+    name: crate::types::names::IcingaServiceName
+);
+
 #[cfg(test)]
+#[cfg(feature = "blocking")]
 mod test {
     use super::*;
+    use pretty_assertions::assert_eq;
     use std::error::Error;
     use tracing_test::traced_test;
 
@@ -25,10 +42,8 @@ mod test {
     #[traced_test]
     #[test]
     fn test_services() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
         let api_endpoint = ListServices::builder()
             .joins(IcingaJoins::AllJoins)
             .meta([IcingaMetadataType::UsedBy, IcingaMetadataType::Location])
@@ -42,10 +57,8 @@ mod test {
     #[traced_test]
     #[test]
     fn test_services_partial_host_join() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
         let mut partial = BTreeMap::new();
         partial.insert(IcingaServiceJoinTypes::Host, vec!["name"]);
         let api_endpoint = ListServices::builder()
@@ -64,10 +77,8 @@ mod test {
     #[traced_test]
     #[test]
     fn test_services_filtered() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
         let api_endpoint = ListServices::builder()
             .filter(IcingaFilter {
                 object_type: IcingaObjectType::Service,
@@ -80,4 +91,74 @@ mod test {
         > = icinga2.rest(api_endpoint)?;
         Ok(())
     }
+
+    #[traced_test]
+    #[test]
+    fn test_services_attrs() -> Result<(), Box<dyn Error>> {
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
+        let api_endpoint = ListServices::builder()
+            .attrs([
+                IcingaServiceAttribute::Name,
+                IcingaServiceAttribute::State,
+                IcingaServiceAttribute::DowntimeDepth,
+            ])
+            .build()?;
+        let response: ResultsWrapper<QueryResultObject<IcingaServicePartial>> =
+            icinga2.rest(api_endpoint)?;
+        for result in response.results {
+            assert!(
+                result
+                    .attrs
+                    .checkable
+                    .custom_var
+                    .config_object
+                    .name
+                    .is_some()
+            );
+            assert!(result.attrs.state.is_some());
+            assert!(result.attrs.checkable.downtime_depth.is_some());
+            assert!(result.attrs.checkable.last_check_result.is_none());
+        }
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_get_service() -> Result<(), Box<dyn Error>> {
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
+        let services: ResultsWrapper<QueryResultObject<IcingaServicePartial>> = icinga2.rest(
+            ListServices::builder()
+                .attrs([
+                    IcingaServiceAttribute::HostName,
+                    IcingaServiceAttribute::Name,
+                ])
+                .build()?,
+        )?;
+        let Some(first) = services.results.first() else {
+            return Ok(());
+        };
+        let (Some(host_name), Some(name)) = (
+            first.attrs.host_name.to_owned(),
+            first
+                .attrs
+                .checkable
+                .custom_var
+                .config_object
+                .name
+                .to_owned(),
+        ) else {
+            return Err("requested attributes missing".into());
+        };
+        let service: Option<QueryResultObject<IcingaServicePartial>> = icinga2.rest(
+            GetService::builder()
+                .host_name(host_name)
+                .name(crate::types::names::IcingaServiceName(name))
+                .attrs([IcingaServiceAttribute::State])
+                .build()?,
+        )?;
+        assert_eq!(service.map(|s| s.name), Some(first.name.to_owned()));
+        Ok(())
+    }
 }
@@ -6,11 +6,26 @@ crate::types::query::query!(
     monitoring_objects,
     service_group,
     IcingaServiceGroup,
+    IcingaServiceGroupAttribute,
+    IcingaServiceGroupPartial,
     IcingaObjectType::ServiceGroup,
     "v1/objects/servicegroups"
 );
 
+crate::types::query::get_object!(
+    GetServiceGroup,
+    GetServiceGroupBuilder,
+    monitoring_objects,
+    service_group,
+    IcingaServiceGroup,
+    IcingaServiceGroupAttribute,
+    IcingaServiceGroupPartial,
+    "v1/objects/servicegroups",
+    name: crate::types::names::IcingaServiceGroupName
+);
+
 #[cfg(test)]
+#[cfg(feature = "blocking")]
 mod test {
     use super::*;
     use crate::api::blocking::Icinga2;
@@ -20,10 +35,8 @@ mod test {
     #[traced_test]
     #[test]
     fn test_service_groups() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
         let api_endpoint = ListServiceGroups::builder()
             .meta([IcingaMetadataType::UsedBy, IcingaMetadataType::Location])
             .build()?;
@@ -6,13 +6,28 @@ crate::types::query::query_with_joins!(
     monitoring_objects,
     user,
     IcingaUser,
+    IcingaUserAttribute,
+    IcingaUserPartial,
     IcingaUserJoinTypes,
     IcingaUserJoins,
     IcingaObjectType::User,
     "v1/objects/users"
 );
 
+crate::types::query::get_object!(
+    GetUser,
+    GetUserBuilder,
+    monitoring_objects,
+    user,
+    IcingaUser,
+    IcingaUserAttribute,
+    IcingaUserPartial,
+    "v1/objects/users",
+    name: crate::types::names::IcingaUserName
+);
+
 #[cfg(test)]
+#[cfg(feature = "blocking")]
 mod test {
     use super::*;
     use std::error::Error;
@@ -23,10 +38,8 @@ mod test {
     #[traced_test]
     #[test]
     fn test_users() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
         let api_endpoint = ListUsers::builder()
             .joins(IcingaJoins::AllJoins)
             .meta([IcingaMetadataType::UsedBy, IcingaMetadataType::Location])
@@ -6,11 +6,26 @@ crate::types::query::query!(
     runtime_objects,
     comment,
     IcingaComment,
+    IcingaCommentAttribute,
+    IcingaCommentPartial,
     IcingaObjectType::Comment,
     "v1/objects/comments"
 );
 
+crate::types::query::get_object!(
+    GetComment,
+    GetCommentBuilder,
+    runtime_objects,
+    comment,
+    IcingaComment,
+    IcingaCommentAttribute,
+    IcingaCommentPartial,
+    "v1/objects/comments",
+    name: String
+);
+
 #[cfg(test)]
+#[cfg(feature = "blocking")]
 mod test {
     use super::*;
     use crate::api::blocking::Icinga2;
@@ -20,10 +35,8 @@ mod test {
     #[traced_test]
     #[test]
     fn test_comments() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
         let api_endpoint = ListComments::builder()
             .meta([IcingaMetadataType::UsedBy, IcingaMetadataType::Location])
             .build()?;
@@ -6,11 +6,26 @@ crate::types::query::query!(
     runtime_objects,
     downtime,
     IcingaDowntime,
+    IcingaDowntimeAttribute,
+    IcingaDowntimePartial,
     IcingaObjectType::Downtime,
     "v1/objects/downtimes"
 );
 
+crate::types::query::get_object!(
+    GetDowntime,
+    GetDowntimeBuilder,
+    runtime_objects,
+    downtime,
+    IcingaDowntime,
+    IcingaDowntimeAttribute,
+    IcingaDowntimePartial,
+    "v1/objects/downtimes",
+    name: crate::types::names::IcingaDowntimeName
+);
+
 #[cfg(test)]
+#[cfg(feature = "blocking")]
 mod test {
     use super::*;
     use crate::api::blocking::Icinga2;
@@ -20,10 +35,8 @@ mod test {
     #[traced_test]
     #[test]
     fn test_downtimes() -> Result<(), Box<dyn Error>> {
-        dotenvy::dotenv()?;
-        let icinga2 = Icinga2::from_config_file(std::path::Path::new(&std::env::var(
-            "ICINGA_TEST_INSTANCE_CONFIG",
-        )?))?;
+        let (config, _fake) = crate::testing::test_instance()?;
+        let icinga2 = Icinga2::from_instance_config(&config)?;
         let api_endpoint = ListDowntimes::builder()
             .meta([IcingaMetadataType::UsedBy, IcingaMetadataType::Location])
             .build()?;
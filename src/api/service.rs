@@ -0,0 +1,141 @@
+//! [tower::Service] implementation for the async client
+//!
+//! This allows composing [Icinga2Async] with tower layers like timeouts,
+//! rate and concurrency limits, load balancing or retries.
+//!
+//! Since a [tower::Service] only accepts a single request type the
+//! [RestApiEndpoint] type is erased in [IcingaRequest]. The response type is
+//! kept as a type parameter so the service still returns parsed responses.
+
+use std::{
+    fmt,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::future::BoxFuture;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    api::async_client::Icinga2Async,
+    types::rest::{RestApiEndpoint, RestApiResponse},
+};
+
+/// the type of the closure performing the request for an [IcingaRequest]
+type IcingaRequestCall<Res> =
+    dyn Fn(Icinga2Async) -> BoxFuture<'static, Result<Res, crate::error::Error>> + Send + Sync;
+
+/// a request to any REST API endpoint returning a `Res`
+///
+/// cloning the request is cheap so it can be used with retry layers
+pub struct IcingaRequest<Res> {
+    /// the type name of the endpoint, for debugging
+    endpoint: &'static str,
+    /// performs the request on the given client
+    call: Arc<IcingaRequestCall<Res>>,
+}
+
+impl<Res> IcingaRequest<Res>
+where
+    Res: DeserializeOwned + fmt::Debug + Send + 'static,
+{
+    /// create a request for the given endpoint
+    pub fn new<ApiEndpoint>(api_endpoint: ApiEndpoint) -> Self
+    where
+        ApiEndpoint: RestApiEndpoint + Clone + Send + Sync + 'static,
+        <ApiEndpoint as RestApiEndpoint>::RequestBody: Clone + Serialize + fmt::Debug + Send + Sync,
+        Res: RestApiResponse<ApiEndpoint>,
+    {
+        Self {
+            endpoint: std::any::type_name::<ApiEndpoint>(),
+            call: Arc::new(move |client: Icinga2Async| {
+                let api_endpoint = api_endpoint.to_owned();
+                Box::pin(async move { client.rest(api_endpoint).await })
+            }),
+        }
+    }
+}
+
+impl<Res> IcingaRequest<Res> {
+    /// the type name of the endpoint this request is for
+    #[must_use]
+    pub const fn endpoint(&self) -> &'static str {
+        self.endpoint
+    }
+}
+
+impl<Res> Clone for IcingaRequest<Res> {
+    fn clone(&self) -> Self {
+        Self {
+            endpoint: self.endpoint,
+            call: Arc::clone(&self.call),
+        }
+    }
+}
+
+impl<Res> fmt::Debug for IcingaRequest<Res> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IcingaRequest")
+            .field("endpoint", &self.endpoint)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Res> tower::Service<IcingaRequest<Res>> for Icinga2Async
+where
+    Res: Send + 'static,
+{
+    type Response = Res;
+    type Error = crate::error::Error;
+    type Future = BoxFuture<'static, Result<Res, crate::error::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: IcingaRequest<Res>) -> Self::Future {
+        (req.call)(self.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+    use tower::ServiceExt as _;
+    use tracing_test::traced_test;
+
+    use crate::{
+        api::query::monitoring_objects::host::ListHosts,
+        types::{
+            join_types::host::IcingaHostJoins, monitoring_objects::host::IcingaHost,
+            query::QueryResultObjectWithJoins, query::ResultsWrapper,
+        },
+    };
+
+    #[traced_test]
+    #[test]
+    fn test_service() -> Result<(), Box<dyn Error>> {
+        let fake = crate::testing::FakeIcinga::start()?;
+        fake.add_host("service-host");
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let icinga2 = Icinga2Async::from_instance_config(&fake.instance_config())?;
+        let request: IcingaRequest<
+            ResultsWrapper<QueryResultObjectWithJoins<IcingaHost, IcingaHostJoins>>,
+        > = IcingaRequest::new(ListHosts::builder().build()?);
+        assert!(request.endpoint().ends_with("ListHosts"));
+        let response = runtime.block_on(icinga2.oneshot(request))?;
+        assert_eq!(
+            response
+                .results
+                .iter()
+                .map(|r| r.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["service-host"]
+        );
+        Ok(())
+    }
+}
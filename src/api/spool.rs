@@ -0,0 +1,700 @@
+//! Durable spool for passive check results
+//!
+//! A [PassiveCheckSpool] buffers [ProcessCheckResult] submissions in a file
+//! while the Icinga API is unreachable and replays them in order once it is
+//! reachable again. Results keep their original `execution_start` and
+//! `execution_end` so Icinga records when the checks actually ran.
+//!
+//! The spool file contains one JSON encoded check result per line. New
+//! results are appended and synced to disk before [PassiveCheckSpool::push]
+//! returns, whenever results are removed the file is rewritten to a
+//! temporary file next to it which is then renamed over the spool file.
+//!
+//! Delivery is at least once, if the process dies during a replay results
+//! submitted since the last rewrite are submitted again.
+
+use std::{
+    collections::VecDeque,
+    ffi::OsString,
+    io::Write as _,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    api::{
+        action::process_check_result::ProcessCheckResult,
+        client::{IcingaClient, IcingaClientAsync},
+        core::{IcingaHttpRequest, IcingaHttpResponse},
+    },
+    types::{action::StatusResponse, query::ResultsWrapper},
+};
+
+/// a file backed queue of passive check results
+///
+/// results for the same checkable (an identical filter on the result) are
+/// superseded by newer ones, only the most recent result per checkable is
+/// kept since Icinga would immediately overwrite the older ones anyway
+#[derive(Debug)]
+pub struct PassiveCheckSpool {
+    /// the spool file
+    path: PathBuf,
+    /// the queued results, oldest first
+    results: VecDeque<ProcessCheckResult>,
+}
+
+/// a spooled check result Icinga did not accept
+///
+/// only results rejected for the object they target (e.g. because it does
+/// not exist) are removed from the spool, submitting them again would fail
+/// the same way
+#[derive(Debug, Clone)]
+pub struct SpoolRejection {
+    /// the rejected check result
+    pub result: ProcessCheckResult,
+    /// why Icinga rejected the result
+    pub reason: String,
+}
+
+/// the outcome of replaying the spool
+#[derive(Debug, Clone, Default)]
+pub struct SpoolReplayReport {
+    /// the number of results accepted by Icinga
+    pub submitted: usize,
+    /// the results Icinga did not accept
+    pub rejected: Vec<SpoolRejection>,
+    /// the number of results still in the spool
+    pub remaining: usize,
+    /// why the replay stopped before the spool was empty, e.g. because the
+    /// API is still unreachable
+    pub interrupted: Option<String>,
+}
+
+/// what happened when submitting a single spooled result
+#[derive(Debug)]
+enum SubmitOutcome {
+    /// Icinga accepted the result
+    Submitted,
+    /// Icinga rejected the result for the object it targets, retrying will
+    /// not help
+    Rejected(String),
+    /// Icinga could not be reached, did not handle the request (e.g. because
+    /// the credentials were rejected) or the request could not be sent, the
+    /// result should be retried later
+    Retry(String),
+}
+
+impl PassiveCheckSpool {
+    /// open a spool file, creating an empty spool if it does not exist yet
+    ///
+    /// lines that can not be parsed (e.g. a partially written last line after
+    /// a crash) are skipped with a warning and the spool file is rewritten
+    /// without them so results appended later start on a line of their own
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the spool file exists but can not be read or
+    /// it needs to be rewritten and can not be written
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, crate::error::Error> {
+        let path = path.into();
+        let content = match fs_err::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(crate::error::Error::CouldNotReadSpool(e)),
+        };
+        let mut results = VecDeque::new();
+        let mut skipped = false;
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<ProcessCheckResult>(line) {
+                Ok(result) => results.push_back(result),
+                Err(e) => {
+                    skipped = true;
+                    tracing::warn!(
+                        path = %path.display(),
+                        line = index.saturating_add(1),
+                        "skipping unparsable entry in passive check spool: {e}"
+                    );
+                }
+            }
+        }
+        let spool = Self { path, results };
+        if skipped || !(content.is_empty() || content.ends_with('\n')) {
+            spool.persist()?;
+        }
+        Ok(spool)
+    }
+
+    /// the path of the spool file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// the number of results waiting to be submitted
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// whether there are no results waiting to be submitted
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// the results waiting to be submitted, oldest first
+    pub fn results(&self) -> impl Iterator<Item = &ProcessCheckResult> {
+        self.results.iter()
+    }
+
+    /// add a result to the end of the spool
+    ///
+    /// queued results for the same checkable are removed. If a queued result
+    /// for the same checkable ended its execution after this one, this one
+    /// is outdated and dropped instead. Returns whether the result was queued.
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the spool file can not be written
+    pub fn push(&mut self, result: ProcessCheckResult) -> Result<bool, crate::error::Error> {
+        let Some(filter) = result.filter() else {
+            self.append(result)?;
+            return Ok(true);
+        };
+        let mut superseded = false;
+        for queued in &self.results {
+            if queued.filter() == Some(filter) {
+                if Self::finished_at(queued) > Self::finished_at(&result) {
+                    tracing::debug!(
+                        "dropping passive check result older than the queued one for the same checkable"
+                    );
+                    return Ok(false);
+                }
+                superseded = true;
+            }
+        }
+        if superseded {
+            self.results
+                .retain(|queued| queued.filter() != Some(filter));
+            self.results.push_back(result);
+            self.persist()?;
+        } else {
+            self.append(result)?;
+        }
+        Ok(true)
+    }
+
+    /// add a result to the spool and replay the spool including the new
+    /// result
+    ///
+    /// use this instead of submitting results directly to make sure results
+    /// never overtake results spooled earlier
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the spool file can not be written
+    pub fn submit<C>(
+        &mut self,
+        client: &C,
+        result: ProcessCheckResult,
+    ) -> Result<SpoolReplayReport, crate::error::Error>
+    where
+        C: IcingaClient,
+    {
+        self.push(result)?;
+        self.replay(client)
+    }
+
+    /// add a result to the spool and replay the spool including the new
+    /// result, the async version of [Self::submit]
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the spool file can not be written
+    pub async fn submit_async<C>(
+        &mut self,
+        client: &C,
+        result: ProcessCheckResult,
+    ) -> Result<SpoolReplayReport, crate::error::Error>
+    where
+        C: IcingaClientAsync,
+    {
+        self.push(result)?;
+        self.replay_async(client).await
+    }
+
+    /// submit the spooled results in order
+    ///
+    /// the replay stops at the first result that can not be submitted because
+    /// the API is unreachable or did not handle the request (e.g. the
+    /// credentials were rejected), that result and all following ones stay in
+    /// the spool
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the spool file can not be written
+    pub fn replay<C>(&mut self, client: &C) -> Result<SpoolReplayReport, crate::error::Error>
+    where
+        C: IcingaClient,
+    {
+        let mut report = SpoolReplayReport::default();
+        while let Some(result) = self.results.front() {
+            let response = IcingaHttpRequest::new(client.base_url(), result)
+                .and_then(|request| client.execute(&request));
+            if !self.record_outcome(Self::outcome(response), &mut report) {
+                break;
+            }
+        }
+        self.finish_replay(report)
+    }
+
+    /// submit the spooled results in order, the async version of
+    /// [Self::replay]
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the spool file can not be written
+    pub async fn replay_async<C>(
+        &mut self,
+        client: &C,
+    ) -> Result<SpoolReplayReport, crate::error::Error>
+    where
+        C: IcingaClientAsync,
+    {
+        let mut report = SpoolReplayReport::default();
+        while let Some(result) = self.results.front() {
+            let response = match IcingaHttpRequest::new(client.base_url(), result) {
+                Ok(request) => client.execute(&request).await,
+                Err(e) => Err(e),
+            };
+            if !self.record_outcome(Self::outcome(response), &mut report) {
+                break;
+            }
+        }
+        self.finish_replay(report)
+    }
+
+    /// the time a result finished executing, used to decide which of two
+    /// results for the same checkable is newer
+    fn finished_at(result: &ProcessCheckResult) -> Option<time::OffsetDateTime> {
+        result.execution_end.or(result.execution_start)
+    }
+
+    /// classify the response to submitting a single result
+    ///
+    /// only a positive rejection by Icinga (no matching object or a client
+    /// error status for one of the objects) drops the result, everything
+    /// else, including local errors like an invalid configuration, keeps it
+    /// in the spool
+    fn outcome(response: Result<IcingaHttpResponse, crate::error::Error>) -> SubmitOutcome {
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => return SubmitOutcome::Retry(e.to_string()),
+        };
+        if let Ok(results) =
+            serde_json::from_slice::<ResultsWrapper<StatusResponse>>(&response.body)
+            && !results.results.is_empty()
+        {
+            let failures = |codes: std::ops::Range<f64>| {
+                results
+                    .results
+                    .iter()
+                    .filter(|result| codes.contains(&result.code))
+                    .map(|result| result.status.as_str())
+                    .collect::<Vec<_>>()
+            };
+            let server_errors = failures(500f64..600f64);
+            if !server_errors.is_empty() {
+                return SubmitOutcome::Retry(server_errors.join("; "));
+            }
+            let rejections = failures(300f64..500f64);
+            return if rejections.is_empty() {
+                SubmitOutcome::Submitted
+            } else {
+                SubmitOutcome::Rejected(rejections.join("; "))
+            };
+        }
+        let status = response.status;
+        if status.is_success() {
+            return SubmitOutcome::Submitted;
+        }
+        let message = serde_json::from_slice::<serde_json::Value>(&response.body)
+            .ok()
+            .and_then(|body| {
+                body.get("status")
+                    .and_then(serde_json::Value::as_str)
+                    .map(str::to_owned)
+            })
+            .unwrap_or_else(|| String::from_utf8_lossy(&response.body).into_owned());
+        let reason = format!("HTTP status {status}: {message}");
+        if status == http::StatusCode::NOT_FOUND {
+            SubmitOutcome::Rejected(reason)
+        } else {
+            SubmitOutcome::Retry(reason)
+        }
+    }
+
+    /// apply the outcome of submitting the first result in the spool,
+    /// returns whether the replay should continue
+    fn record_outcome(&mut self, outcome: SubmitOutcome, report: &mut SpoolReplayReport) -> bool {
+        match outcome {
+            SubmitOutcome::Submitted => {
+                self.results.pop_front();
+                report.submitted = report.submitted.saturating_add(1);
+                true
+            }
+            SubmitOutcome::Rejected(reason) => {
+                if let Some(result) = self.results.pop_front() {
+                    tracing::warn!("dropping passive check result rejected by Icinga: {reason}");
+                    report.rejected.push(SpoolRejection { result, reason });
+                }
+                true
+            }
+            SubmitOutcome::Retry(reason) => {
+                tracing::debug!("stopping passive check spool replay: {reason}");
+                report.interrupted = Some(reason);
+                false
+            }
+        }
+    }
+
+    /// write the spool after a replay removed results from it
+    fn finish_replay(
+        &self,
+        mut report: SpoolReplayReport,
+    ) -> Result<SpoolReplayReport, crate::error::Error> {
+        if report.submitted > 0 || !report.rejected.is_empty() {
+            self.persist()?;
+        }
+        report.remaining = self.results.len();
+        Ok(report)
+    }
+
+    /// append a single result to the spool file
+    fn append(&mut self, result: ProcessCheckResult) -> Result<(), crate::error::Error> {
+        let mut line = serde_json::to_string(&result)
+            .map_err(crate::error::Error::CouldNotSerializeSpoolEntry)?;
+        line.push('\n');
+        let mut file = fs_err::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(crate::error::Error::CouldNotWriteSpool)?;
+        file.write_all(line.as_bytes())
+            .map_err(crate::error::Error::CouldNotWriteSpool)?;
+        file.sync_data()
+            .map_err(crate::error::Error::CouldNotWriteSpool)?;
+        self.results.push_back(result);
+        Ok(())
+    }
+
+    /// atomically replace the spool file with the current contents of the
+    /// spool
+    fn persist(&self) -> Result<(), crate::error::Error> {
+        let mut content = String::new();
+        for result in &self.results {
+            content.push_str(
+                &serde_json::to_string(result)
+                    .map_err(crate::error::Error::CouldNotSerializeSpoolEntry)?,
+            );
+            content.push('\n');
+        }
+        let mut temporary_path = OsString::from(self.path.as_os_str());
+        temporary_path.push(".tmp");
+        let temporary_path = PathBuf::from(temporary_path);
+        let mut file = fs_err::File::create(&temporary_path)
+            .map_err(crate::error::Error::CouldNotWriteSpool)?;
+        file.write_all(content.as_bytes())
+            .map_err(crate::error::Error::CouldNotWriteSpool)?;
+        file.sync_all()
+            .map_err(crate::error::Error::CouldNotWriteSpool)?;
+        fs_err::rename(&temporary_path, &self.path)
+            .map_err(crate::error::Error::CouldNotWriteSpool)?;
+        self.sync_directory()
+    }
+
+    /// sync the directory containing the spool file so a rename of the spool
+    /// file survives a power loss
+    #[cfg(unix)]
+    fn sync_directory(&self) -> Result<(), crate::error::Error> {
+        let directory = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        fs_err::File::open(directory)
+            .and_then(|directory| directory.sync_all())
+            .map_err(crate::error::Error::CouldNotWriteSpool)
+    }
+
+    /// directories can not be synced on this platform, renames are durable
+    /// once the file system flushes its metadata
+    #[cfg(not(unix))]
+    #[expect(
+        clippy::unnecessary_wraps,
+        clippy::unused_self,
+        reason = "same signature as the unix version"
+    )]
+    const fn sync_directory(&self) -> Result<(), crate::error::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+    use tracing_test::traced_test;
+
+    use crate::{
+        api::{
+            action::process_check_result::ProcessCheckResultExitStatus, async_client::Icinga2Async,
+            blocking::Icinga2,
+        },
+        testing::fixtures::service_check_result,
+        types::enums::object_type::IcingaObjectType,
+    };
+
+    /// a check result for a service that finished at the given unix timestamp
+    fn service_result(
+        host: &str,
+        service: &str,
+        output: &str,
+        finished: i64,
+    ) -> Result<ProcessCheckResult, Box<dyn Error>> {
+        Ok(service_check_result(
+            host,
+            service,
+            ProcessCheckResultExitStatus::ServiceWarning,
+            output,
+            Some(time::OffsetDateTime::from_unix_timestamp(finished)?),
+        )?)
+    }
+
+    /// the plugin outputs of the spooled results
+    fn outputs(spool: &PassiveCheckSpool) -> Vec<&str> {
+        spool
+            .results()
+            .map(|result| result.plugin_output.as_str())
+            .collect()
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_spool_supersedes_and_survives_reopening() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("spool.jsonl");
+        let mut spool = PassiveCheckSpool::open(&path)?;
+        assert!(spool.is_empty());
+        assert!(spool.push(service_result("edge", "disk", "disk 1", 1_700_000_010)?)?);
+        assert!(spool.push(service_result("edge", "load", "load 1", 1_700_000_020)?)?);
+        assert!(spool.push(service_result("edge", "disk", "disk 2", 1_700_000_030)?)?);
+        assert!(!spool.push(service_result("edge", "load", "load 0", 1_700_000_000)?)?);
+        assert_eq!(outputs(&spool), vec!["load 1", "disk 2"]);
+        fs_err::OpenOptions::new()
+            .append(true)
+            .open(&path)?
+            .write_all(b"{\"exit_status\":")?;
+        let mut reopened = PassiveCheckSpool::open(&path)?;
+        assert_eq!(reopened.len(), 2);
+        assert_eq!(outputs(&reopened), vec!["load 1", "disk 2"]);
+        assert_eq!(
+            reopened
+                .results()
+                .map(|result| result.execution_end)
+                .collect::<Vec<_>>(),
+            vec![
+                Some(time::OffsetDateTime::from_unix_timestamp(1_700_000_020)?),
+                Some(time::OffsetDateTime::from_unix_timestamp(1_700_000_030)?),
+            ]
+        );
+        assert!(fs_err::read_to_string(&path)?.ends_with('\n'));
+        assert!(reopened.push(service_result("edge", "ping", "ping 1", 1_700_000_040)?)?);
+        assert_eq!(
+            outputs(&PassiveCheckSpool::open(&path)?),
+            vec!["load 1", "disk 2", "ping 1"]
+        );
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_spool_replays_when_reachable() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("spool.jsonl");
+        let mut spool = PassiveCheckSpool::open(&path)?;
+        let unreachable = {
+            let fake = crate::testing::FakeIcinga::start()?;
+            Icinga2::from_instance_config(&fake.instance_config())?
+        };
+        let report = spool.submit(
+            &unreachable,
+            service_result("edge", "disk", "disk 1", 1_700_000_010)?,
+        )?;
+        assert_eq!(report.submitted, 0);
+        assert_eq!(report.remaining, 1);
+        assert!(report.interrupted.is_some());
+        spool.push(service_result(
+            "gone",
+            "disk",
+            "unknown host",
+            1_700_000_015,
+        )?)?;
+        spool.push(service_result("edge", "load", "load 1", 1_700_000_020)?)?;
+
+        let fake = crate::testing::FakeIcinga::start()?;
+        fake.add_host("edge");
+        fake.add_service("edge", "disk");
+        fake.add_service("edge", "load");
+        let icinga2 = Icinga2::from_instance_config(&fake.instance_config())?;
+        let mut spool = PassiveCheckSpool::open(&path)?;
+        assert_eq!(spool.len(), 3);
+        let report = spool.replay(&icinga2)?;
+        assert_eq!(report.submitted, 2);
+        assert_eq!(report.remaining, 0);
+        assert_eq!(report.interrupted, None);
+        assert_eq!(
+            report
+                .rejected
+                .iter()
+                .map(|rejection| rejection.result.plugin_output.as_str())
+                .collect::<Vec<_>>(),
+            vec!["unknown host"]
+        );
+        assert!(PassiveCheckSpool::open(&path)?.is_empty());
+        let disk = fake
+            .object(IcingaObjectType::Service, "edge!disk")
+            .ok_or("service edge!disk is missing")?;
+        assert_eq!(
+            disk.pointer("/last_check_result/output"),
+            Some(&serde_json::json!("disk 1"))
+        );
+        assert_eq!(
+            disk.pointer("/last_check_result/execution_end"),
+            Some(&serde_json::json!(1_700_000_010f64))
+        );
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_spool_keeps_results_when_unauthorized() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("spool.jsonl");
+        let fake = crate::testing::FakeIcinga::start()?;
+        fake.add_host("edge");
+        fake.add_service("edge", "disk");
+        fake.add_service("edge", "load");
+        fake.fail_requests_with(Some(http::StatusCode::UNAUTHORIZED));
+        let icinga2 = Icinga2::from_instance_config(&fake.instance_config())?;
+        let mut spool = PassiveCheckSpool::open(&path)?;
+        spool.push(service_result("edge", "disk", "disk 1", 1_700_000_010)?)?;
+        let report = spool.submit(
+            &icinga2,
+            service_result("edge", "load", "load 1", 1_700_000_020)?,
+        )?;
+        assert_eq!(report.submitted, 0);
+        assert!(report.rejected.is_empty());
+        assert_eq!(report.remaining, 2);
+        assert!(
+            report
+                .interrupted
+                .is_some_and(|reason| reason.contains("401"))
+        );
+        assert_eq!(fake.requests().len(), 1);
+        assert_eq!(
+            outputs(&PassiveCheckSpool::open(&path)?),
+            vec!["disk 1", "load 1"]
+        );
+
+        fake.fail_requests_with(None);
+        let report = spool.replay(&icinga2)?;
+        assert_eq!(report.submitted, 2);
+        assert_eq!(report.remaining, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_spool_outcome() -> Result<(), Box<dyn Error>> {
+        let response = |status: http::StatusCode, body: serde_json::Value| {
+            Ok(IcingaHttpResponse {
+                status,
+                headers: http::HeaderMap::new(),
+                body: body.to_string().into_bytes(),
+            })
+        };
+        let Err(invalid_header) = http::HeaderValue::from_str("\n") else {
+            return Err("invalid header value was accepted".into());
+        };
+        assert!(matches!(
+            PassiveCheckSpool::outcome(Err(crate::error::Error::CouldNotBuildAuthorizationHeader(
+                invalid_header
+            ))),
+            SubmitOutcome::Retry(_)
+        ));
+        assert!(matches!(
+            PassiveCheckSpool::outcome(response(
+                http::StatusCode::OK,
+                serde_json::json!({"results": [{"code": 200.0, "status": "ok"}]})
+            )),
+            SubmitOutcome::Submitted
+        ));
+        assert!(matches!(
+            PassiveCheckSpool::outcome(response(
+                http::StatusCode::INTERNAL_SERVER_ERROR,
+                serde_json::json!({"results": [{"code": 500.0, "status": "internal error"}]})
+            )),
+            SubmitOutcome::Retry(_)
+        ));
+        assert!(matches!(
+            PassiveCheckSpool::outcome(response(
+                http::StatusCode::CONFLICT,
+                serde_json::json!({"results": [{"code": 409.0, "status": "not accepted"}]})
+            )),
+            SubmitOutcome::Rejected(_)
+        ));
+        assert!(matches!(
+            PassiveCheckSpool::outcome(response(
+                http::StatusCode::NOT_FOUND,
+                serde_json::json!({"error": 404.0, "status": "No objects found."})
+            )),
+            SubmitOutcome::Rejected(_)
+        ));
+        assert!(matches!(
+            PassiveCheckSpool::outcome(response(
+                http::StatusCode::SERVICE_UNAVAILABLE,
+                serde_json::json!({"error": 503.0, "status": "unavailable"})
+            )),
+            SubmitOutcome::Retry(_)
+        ));
+        Ok(())
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn test_spool_replays_async() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        let fake = crate::testing::FakeIcinga::start()?;
+        fake.add_host("edge");
+        fake.add_service("edge", "disk");
+        let icinga2 = Icinga2Async::from_instance_config(&fake.instance_config())?;
+        let mut spool = PassiveCheckSpool::open(dir.path().join("spool.jsonl"))?;
+        spool.push(service_result("edge", "disk", "disk 1", 1_700_000_010)?)?;
+        let report = spool
+            .submit_async(
+                &icinga2,
+                service_result("edge", "disk", "disk 2", 1_700_000_020)?,
+            )
+            .await?;
+        assert_eq!(report.submitted, 1);
+        assert_eq!(report.remaining, 0);
+        assert_eq!(
+            fake.object(IcingaObjectType::Service, "edge!disk")
+                .and_then(|disk| disk.pointer("/last_check_result/output").cloned()),
+            Some(serde_json::json!("disk 2"))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,550 @@
+//! Incremental parsing of the `results` array in REST API responses
+//!
+//! This is used by the `rest_stream` methods of the clients. The response
+//! body is fed to a [ResultsParser] in chunks as it arrives. The parser splits
+//! the elements of the `results` array out of the body without parsing them and
+//! then deserializes each element on its own. Only the element currently being
+//! split and the complete elements of the last chunk are kept in memory.
+
+use std::{collections::VecDeque, marker::PhantomData, str::from_utf8};
+
+use serde::de::DeserializeOwned;
+
+use crate::api::core::value_at_path;
+
+/// where the parser currently is in the response body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParserState {
+    /// before the opening brace of the response object
+    Start,
+    /// before a key in the response object
+    BeforeKey,
+    /// inside a key in the response object
+    Key,
+    /// between a key and the colon following it
+    BeforeColon,
+    /// between the colon after the `results` key and the opening bracket of the array
+    BeforeResults,
+    /// inside the value of a key other than `results`
+    SkipValue,
+    /// between two elements of the `results` array
+    BeforeElement,
+    /// inside an element of the `results` array
+    Element,
+    /// after the closing bracket of the `results` array
+    Done,
+    /// after an error, no further input is processed
+    Failed,
+}
+
+/// splits the elements of the `results` array out of a response body
+/// and deserializes them one by one
+#[derive(Debug)]
+pub(crate) struct ResultsParser<Item> {
+    /// where the parser currently is in the response body
+    state: ParserState,
+    /// the key currently being read
+    key: Vec<u8>,
+    /// the element currently being read
+    element: Vec<u8>,
+    /// whether the current value has started (only leading whitespace seen otherwise)
+    value_started: bool,
+    /// nesting depth of objects and arrays inside the current value
+    depth: usize,
+    /// whether we are inside a string in the current key or value
+    in_string: bool,
+    /// whether the previous byte was a backslash inside a string
+    escaped: bool,
+    /// complete elements not yet deserialized
+    elements: VecDeque<Vec<u8>>,
+    /// the index of the next element to deserialize
+    index: usize,
+    /// the type of the elements
+    item: PhantomData<fn() -> Item>,
+}
+
+impl<Item> ResultsParser<Item>
+where
+    Item: DeserializeOwned + std::fmt::Debug,
+{
+    /// create a parser for a new response body
+    pub(crate) const fn new() -> Self {
+        Self {
+            state: ParserState::Start,
+            key: Vec::new(),
+            element: Vec::new(),
+            value_started: false,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            elements: VecDeque::new(),
+            index: 0,
+            item: PhantomData,
+        }
+    }
+
+    /// whether no more elements can be produced by feeding more input
+    pub(crate) const fn is_finished(&self) -> bool {
+        matches!(self.state, ParserState::Done | ParserState::Failed)
+    }
+
+    /// stop processing input, e.g. because reading the response body failed
+    pub(crate) const fn fail(&mut self) {
+        self.state = ParserState::Failed;
+    }
+
+    /// feed the next chunk of the response body to the parser
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the response body is not an object
+    /// with a `results` array
+    pub(crate) fn feed(&mut self, chunk: &[u8]) -> Result<(), crate::error::Error> {
+        for byte in chunk {
+            if self.is_finished() {
+                break;
+            }
+            if let Err(e) = self.push_byte(*byte) {
+                self.state = ParserState::Failed;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// signal the end of the response body to the parser
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the response body ended before the end of
+    /// the `results` array
+    pub(crate) const fn finish(&mut self) -> Result<(), crate::error::Error> {
+        match self.state {
+            ParserState::Done | ParserState::Failed => Ok(()),
+            _ => {
+                self.state = ParserState::Failed;
+                Err(crate::error::Error::MalformedResultsStream(
+                    "response body ended before the end of the results array",
+                ))
+            }
+        }
+    }
+
+    /// deserialize the next complete element if there is one
+    pub(crate) fn next_item(&mut self) -> Option<Result<Item, crate::error::Error>> {
+        let element = self.elements.pop_front()?;
+        let index = self.index;
+        self.index = self.index.saturating_add(1);
+        Some(parse_element(index, &element))
+    }
+
+    /// process a single byte of the response body
+    fn push_byte(&mut self, byte: u8) -> Result<(), crate::error::Error> {
+        match self.state {
+            ParserState::Start => match byte {
+                b'{' => self.state = ParserState::BeforeKey,
+                _ if byte.is_ascii_whitespace() => {}
+                _ => {
+                    return Err(crate::error::Error::MalformedResultsStream(
+                        "response body is not a JSON object",
+                    ));
+                }
+            },
+            ParserState::BeforeKey => match byte {
+                b'"' => {
+                    self.key.clear();
+                    self.state = ParserState::Key;
+                }
+                b'}' => {
+                    return Err(crate::error::Error::MalformedResultsStream(
+                        "response body has no results array",
+                    ));
+                }
+                b',' => {}
+                _ if byte.is_ascii_whitespace() => {}
+                _ => {
+                    return Err(crate::error::Error::MalformedResultsStream(
+                        "expected a key in the response object",
+                    ));
+                }
+            },
+            ParserState::Key => {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.state = ParserState::BeforeColon;
+                    return Ok(());
+                }
+                self.key.push(byte);
+            }
+            ParserState::BeforeColon => match byte {
+                b':' if self.key == b"results" => self.state = ParserState::BeforeResults,
+                b':' => {
+                    self.start_value();
+                    self.state = ParserState::SkipValue;
+                }
+                _ if byte.is_ascii_whitespace() => {}
+                _ => {
+                    return Err(crate::error::Error::MalformedResultsStream(
+                        "expected a colon after a key in the response object",
+                    ));
+                }
+            },
+            ParserState::BeforeResults => match byte {
+                b'[' => self.state = ParserState::BeforeElement,
+                _ if byte.is_ascii_whitespace() => {}
+                _ => {
+                    return Err(crate::error::Error::MalformedResultsStream(
+                        "results in the response object is not an array",
+                    ));
+                }
+            },
+            ParserState::BeforeElement => match byte {
+                b']' => self.state = ParserState::Done,
+                b',' => {}
+                _ if byte.is_ascii_whitespace() => {}
+                _ => {
+                    self.start_value();
+                    self.state = ParserState::Element;
+                    return self.push_byte(byte);
+                }
+            },
+            ParserState::SkipValue | ParserState::Element => {
+                let keep = self.state == ParserState::Element;
+                match self.value_byte(byte, keep) {
+                    ValueByte::Incomplete => {}
+                    ValueByte::Complete => self.finish_value(),
+                    ValueByte::CompleteBefore => {
+                        self.finish_value();
+                        return self.push_byte(byte);
+                    }
+                }
+            }
+            ParserState::Done | ParserState::Failed => {}
+        }
+        Ok(())
+    }
+
+    /// reset the state for a new value
+    fn start_value(&mut self) {
+        self.element.clear();
+        self.value_started = false;
+        self.depth = 0;
+        self.in_string = false;
+        self.escaped = false;
+    }
+
+    /// store the current value if it is an element and continue after it
+    fn finish_value(&mut self) {
+        if self.state == ParserState::Element {
+            self.elements.push_back(std::mem::take(&mut self.element));
+            self.state = ParserState::BeforeElement;
+        } else {
+            self.state = ParserState::BeforeKey;
+        }
+    }
+
+    /// process a single byte of a value, storing it if `keep` is set
+    fn value_byte(&mut self, byte: u8, keep: bool) -> ValueByte {
+        let mut result = ValueByte::Incomplete;
+        if self.in_string {
+            if self.escaped {
+                self.escaped = false;
+            } else if byte == b'\\' {
+                self.escaped = true;
+            } else if byte == b'"' {
+                self.in_string = false;
+                if self.depth == 0 {
+                    result = ValueByte::Complete;
+                }
+            }
+        } else {
+            match byte {
+                b'"' => self.in_string = true,
+                b'{' | b'[' => self.depth = self.depth.saturating_add(1),
+                b'}' | b']' | b',' if self.depth == 0 => return ValueByte::CompleteBefore,
+                b'}' | b']' => {
+                    self.depth = self.depth.saturating_sub(1);
+                    if self.depth == 0 {
+                        result = ValueByte::Complete;
+                    }
+                }
+                _ if byte.is_ascii_whitespace() && self.depth == 0 => {
+                    return if self.value_started {
+                        ValueByte::CompleteBefore
+                    } else {
+                        ValueByte::Incomplete
+                    };
+                }
+                _ => {}
+            }
+        }
+        self.value_started = true;
+        if keep {
+            self.element.push(byte);
+        }
+        result
+    }
+}
+
+/// the effect of a single byte on the value currently being read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueByte {
+    /// the value continues after this byte
+    Incomplete,
+    /// this byte was the last byte of the value
+    Complete,
+    /// the value ended before this byte, the byte belongs to the surrounding structure
+    CompleteBefore,
+}
+
+/// deserialize a single element of the `results` array
+///
+/// # Errors
+///
+/// this returns an error if the element can not be deserialized, the path in
+/// the error is relative to the element
+fn parse_element<Item>(index: usize, element: &[u8]) -> Result<Item, crate::error::Error>
+where
+    Item: DeserializeOwned + std::fmt::Debug,
+{
+    match from_utf8(element) {
+        Ok(element) => {
+            tracing::trace!("Result {}:\n{}", index, element);
+        }
+        Err(e) => {
+            tracing::trace!(
+                "Result {} that could not be parsed as utf8 because of {}:\n{:?}",
+                index,
+                &e,
+                element
+            );
+        }
+    }
+    let jd = &mut serde_json::Deserializer::from_slice(element);
+    match serde_path_to_error::deserialize(jd) {
+        Ok(item) => {
+            tracing::trace!("Parsed result {}:\n{:#?}", index, item);
+            Ok(item)
+        }
+        Err(e) => {
+            let path = e.path();
+            tracing::error!(
+                "Parsing result {} failed at path {}: {}",
+                index,
+                path.to_string(),
+                e.inner()
+            );
+            if let Ok(value) = serde_json::from_slice::<serde_json::Value>(element)
+                && let Some(value) = value_at_path(&value, path)
+            {
+                tracing::error!("Value in location path references is: {}", value);
+            }
+            Err(e.into())
+        }
+    }
+}
+
+/// iterator over the elements of the `results` array for the blocking client
+#[cfg(feature = "blocking-client")]
+#[derive(Debug)]
+pub(crate) struct ResultsIter<R, Item> {
+    /// the response body
+    reader: R,
+    /// the parser for the response body
+    parser: ResultsParser<Item>,
+    /// the buffer for chunks read from the response body
+    buffer: Vec<u8>,
+    /// the request counts as in flight until the response body was read
+    _in_flight: Option<crate::api::limit::InFlightGuard>,
+}
+
+#[cfg(feature = "blocking-client")]
+impl<R, Item> ResultsIter<R, Item>
+where
+    R: std::io::Read,
+    Item: DeserializeOwned + std::fmt::Debug,
+{
+    /// the size of the chunks read from the response body
+    const CHUNK_SIZE: usize = 8192;
+
+    /// create an iterator reading from the given response body
+    pub(crate) fn new(reader: R, in_flight: Option<crate::api::limit::InFlightGuard>) -> Self {
+        Self {
+            reader,
+            parser: ResultsParser::new(),
+            buffer: vec![0; Self::CHUNK_SIZE],
+            _in_flight: in_flight,
+        }
+    }
+}
+
+#[cfg(feature = "blocking-client")]
+impl<R, Item> Iterator for ResultsIter<R, Item>
+where
+    R: std::io::Read,
+    Item: DeserializeOwned + std::fmt::Debug,
+{
+    type Item = Result<Item, crate::error::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.parser.next_item() {
+                return Some(item);
+            }
+            if self.parser.is_finished() {
+                return None;
+            }
+            let result = match self.reader.read(&mut self.buffer) {
+                Ok(0) => self.parser.finish(),
+                Ok(len) => match self.buffer.get(..len) {
+                    Some(chunk) => self.parser.feed(chunk),
+                    None => Ok(()),
+                },
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => Ok(()),
+                Err(e) => {
+                    self.parser.fail();
+                    Err(crate::error::Error::CouldNotReadResponseBody(e))
+                }
+            };
+            if let Err(e) = result {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+    use tracing_test::traced_test;
+
+    use crate::{
+        api::query::monitoring_objects::host::ListHosts,
+        types::{
+            join_types::host::IcingaHostJoins, monitoring_objects::host::IcingaHost,
+            query::QueryResultObjectWithJoins,
+        },
+    };
+
+    /// feed the body in chunks of the given size and collect all results
+    fn parse_in_chunks(
+        body: &[u8],
+        chunk_size: usize,
+    ) -> Result<Vec<serde_json::Value>, crate::error::Error> {
+        let mut parser = ResultsParser::new();
+        let mut items = Vec::new();
+        for chunk in body.chunks(chunk_size) {
+            parser.feed(chunk)?;
+            while let Some(item) = parser.next_item() {
+                items.push(item?);
+            }
+        }
+        parser.finish()?;
+        while let Some(item) = parser.next_item() {
+            items.push(item?);
+        }
+        Ok(items)
+    }
+
+    #[test]
+    fn test_results_parser_chunks() -> Result<(), Box<dyn Error>> {
+        let body = br#" { "meta": {"results": [1, 2]}, "results" : [ {"name": "a\"]}", "v": [1, {"x": null}]},
+            "plain", 42 , true,[]], "trailing": [3] }"#;
+        let expected = vec![
+            serde_json::json!({"name": "a\"]}", "v": [1, {"x": null}]}),
+            serde_json::json!("plain"),
+            serde_json::json!(42),
+            serde_json::json!(true),
+            serde_json::json!([]),
+        ];
+        for chunk_size in [1, 2, 3, 7, body.len()] {
+            assert_eq!(parse_in_chunks(body, chunk_size)?, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_results_parser_errors() {
+        assert!(matches!(
+            parse_in_chunks(br#"{"error": 404, "status": "No objects found."}"#, 4),
+            Err(crate::error::Error::MalformedResultsStream(_))
+        ));
+        assert!(matches!(
+            parse_in_chunks(br#"{"results": [{"name": "a"}"#, 4),
+            Err(crate::error::Error::MalformedResultsStream(_))
+        ));
+        assert!(matches!(
+            parse_in_chunks(br#"{"results": 5}"#, 4),
+            Err(crate::error::Error::MalformedResultsStream(_))
+        ));
+        assert!(matches!(
+            parse_in_chunks(br"[]", 4),
+            Err(crate::error::Error::MalformedResultsStream(_))
+        ));
+    }
+
+    #[test]
+    fn test_results_parser_element_error() -> Result<(), Box<dyn Error>> {
+        let mut parser: ResultsParser<u64> = ResultsParser::new();
+        parser.feed(br#"{"results": [1, "two", 3]}"#)?;
+        parser.finish()?;
+        assert_eq!(parser.next_item().transpose()?, Some(1));
+        assert!(parser.next_item().is_some_and(|item| item.is_err()));
+        assert_eq!(parser.next_item().transpose()?, Some(3));
+        assert!(parser.next_item().is_none());
+        Ok(())
+    }
+
+    #[cfg(feature = "blocking")]
+    #[traced_test]
+    #[test]
+    fn test_rest_stream_blocking() -> Result<(), Box<dyn Error>> {
+        let fake = crate::testing::FakeIcinga::start()?;
+        for i in 0..50 {
+            fake.add_host(&format!("stream-host-{i}"));
+        }
+        let icinga2 = crate::api::blocking::Icinga2::from_instance_config(&fake.instance_config())?;
+        let hosts = icinga2
+            .rest_stream::<_, QueryResultObjectWithJoins<IcingaHost, IcingaHostJoins>>(
+                ListHosts::builder().build()?,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(hosts.len(), 50);
+        assert!(
+            hosts
+                .iter()
+                .any(|host| host.name.as_str() == "stream-host-49")
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    #[traced_test]
+    #[tokio::test]
+    async fn test_rest_stream_async() -> Result<(), Box<dyn Error>> {
+        use futures::TryStreamExt as _;
+
+        let fake = crate::testing::FakeIcinga::start()?;
+        fake.add_host("stream-host");
+        let icinga2 =
+            crate::api::async_client::Icinga2Async::from_instance_config(&fake.instance_config())?;
+        let hosts: Vec<QueryResultObjectWithJoins<IcingaHost, IcingaHostJoins>> = icinga2
+            .rest_stream(ListHosts::builder().build()?)
+            .await?
+            .try_collect()
+            .await?;
+        assert_eq!(
+            hosts
+                .iter()
+                .map(|host| host.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["stream-host"]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+//! Concurrent submission of passive check results
+//!
+//! A [PassiveResultSubmitter] accepts [ProcessCheckResult]s over a channel
+//! and submits them with a fixed number of requests in flight, which is
+//! considerably faster than awaiting every submission before sending the
+//! next one. Failures are collected into a [PassiveSubmissionReport]
+//! returned by [PassiveResultSubmitter::shutdown] instead of being returned
+//! one at a time.
+
+use std::num::NonZeroUsize;
+
+use futures::{FutureExt as _, StreamExt as _};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::{
+    api::{action::process_check_result::ProcessCheckResult, async_client::Icinga2Async},
+    types::{action::StatusResponse, query::ResultsWrapper},
+};
+
+/// submits passive check results in the background
+///
+/// create it with [Icinga2Async::passive_result_submitter]. Results are
+/// submitted in the order they are received but with several requests in
+/// flight, so they may be processed by Icinga in a different order.
+#[derive(Debug)]
+pub struct PassiveResultSubmitter {
+    /// the sending half of the channel the results are submitted from
+    sender: mpsc::Sender<ProcessCheckResult>,
+    /// tells the background task to stop accepting results, also when
+    /// dropped
+    stop: oneshot::Sender<()>,
+    /// the task submitting the results
+    worker: tokio::task::JoinHandle<PassiveSubmissionReport>,
+}
+
+/// why a passive check result was not accepted
+#[derive(Debug)]
+pub enum PassiveSubmissionFailureReason {
+    /// the request failed, e.g. because the API was unreachable or no object
+    /// matched the filter
+    Request(crate::error::Error),
+    /// Icinga returned an error status for some of the objects the filter
+    /// matched, only the failed statuses are included
+    Status(Vec<StatusResponse>),
+}
+
+/// a passive check result that was not accepted
+#[derive(Debug)]
+pub struct PassiveSubmissionFailure {
+    /// the check result
+    pub result: ProcessCheckResult,
+    /// why it was not accepted
+    pub reason: PassiveSubmissionFailureReason,
+}
+
+/// the outcome of all submissions of a [PassiveResultSubmitter]
+#[derive(Debug, Default)]
+pub struct PassiveSubmissionReport {
+    /// the number of results accepted by Icinga for all matching objects
+    pub submitted: usize,
+    /// the results that were not accepted
+    pub failures: Vec<PassiveSubmissionFailure>,
+}
+
+impl PassiveSubmissionReport {
+    /// whether all results were accepted
+    #[must_use]
+    pub const fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// record the outcome of a single submission
+    fn record(
+        &mut self,
+        result: ProcessCheckResult,
+        response: Result<ResultsWrapper<StatusResponse>, crate::error::Error>,
+    ) {
+        let reason = match response {
+            Ok(response) => {
+                let failed = response
+                    .results
+                    .into_iter()
+                    .filter(|status| !(200f64..300f64).contains(&status.code))
+                    .collect::<Vec<_>>();
+                if failed.is_empty() {
+                    self.submitted = self.submitted.saturating_add(1);
+                    return;
+                }
+                PassiveSubmissionFailureReason::Status(failed)
+            }
+            Err(e) => PassiveSubmissionFailureReason::Request(e),
+        };
+        tracing::debug!("passive check result was not accepted: {:?}", reason);
+        self.failures
+            .push(PassiveSubmissionFailure { result, reason });
+    }
+}
+
+impl PassiveResultSubmitter {
+    /// start the background task submitting results with `concurrency`
+    /// requests in flight
+    ///
+    /// up to `concurrency` results are buffered in the channel, sending more
+    /// waits until a submission finished
+    ///
+    /// # Panics
+    ///
+    /// this panics if called outside of a tokio runtime
+    pub(crate) fn start(client: Icinga2Async, concurrency: NonZeroUsize) -> Self {
+        let (sender, receiver) = mpsc::channel(concurrency.get());
+        let (stop, stopped) = oneshot::channel();
+        let worker = tokio::spawn(Self::run(client, receiver, stopped, concurrency));
+        Self {
+            sender,
+            stop,
+            worker,
+        }
+    }
+
+    /// submit results until the channel is closed or the stop signal is
+    /// received and all queued results were submitted
+    async fn run(
+        client: Icinga2Async,
+        mut receiver: mpsc::Receiver<ProcessCheckResult>,
+        stopped: oneshot::Receiver<()>,
+        concurrency: NonZeroUsize,
+    ) -> PassiveSubmissionReport {
+        let mut stopped = Some(stopped);
+        let results = futures::stream::poll_fn(move |cx| {
+            if let Some(signal) = stopped.as_mut()
+                && signal.poll_unpin(cx).is_ready()
+            {
+                stopped = None;
+                receiver.close();
+            }
+            receiver.poll_recv(cx)
+        });
+        let mut submissions = results
+            .map(|result| {
+                let client = client.clone();
+                async move {
+                    let response = client.rest(result.clone()).await;
+                    (result, response)
+                }
+            })
+            .buffer_unordered(concurrency.get());
+        let mut report = PassiveSubmissionReport::default();
+        while let Some((result, response)) = submissions.next().await {
+            report.record(result, response);
+        }
+        report
+    }
+
+    /// queue a result for submission
+    ///
+    /// this waits if the channel is full
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the background task stopped
+    pub async fn submit(&self, result: ProcessCheckResult) -> Result<(), crate::error::Error> {
+        self.sender
+            .send(result)
+            .await
+            .map_err(|_err| crate::error::Error::PassiveResultSubmitterStopped)
+    }
+
+    /// a sender for the channel results are submitted from, e.g. to hand to
+    /// producer tasks
+    ///
+    /// once [Self::shutdown] was called sending on it fails, results sent
+    /// before are still submitted
+    #[must_use]
+    pub fn sender(&self) -> mpsc::Sender<ProcessCheckResult> {
+        self.sender.clone()
+    }
+
+    /// stop accepting results, also on senders returned by [Self::sender],
+    /// wait until all queued and in flight results were submitted and return
+    /// the report
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the background task panicked or was cancelled
+    pub async fn shutdown(self) -> Result<PassiveSubmissionReport, crate::error::Error> {
+        drop(self.sender);
+        if self.stop.send(()).is_err() {
+            tracing::trace!("passive result submitter already stopped before shutdown");
+        }
+        self.worker
+            .await
+            .map_err(crate::error::Error::PassiveResultSubmitterFailed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+    use tracing_test::traced_test;
+
+    use crate::{
+        api::action::process_check_result::ProcessCheckResultExitStatus,
+        testing::fixtures::service_check_result, types::enums::object_type::IcingaObjectType,
+    };
+
+    /// a critical check result for a service
+    fn service_result(host: &str, service: &str) -> Result<ProcessCheckResult, Box<dyn Error>> {
+        Ok(service_check_result(
+            host,
+            service,
+            ProcessCheckResultExitStatus::ServiceCritical,
+            &format!("{service} on {host} is critical"),
+            None,
+        )?)
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn test_passive_result_submitter() -> Result<(), Box<dyn Error>> {
+        let fake = crate::testing::FakeIcinga::start()?;
+        fake.add_host("bulk");
+        for index in 0..20 {
+            fake.add_service("bulk", &format!("svc{index}"));
+        }
+        let icinga2 = Icinga2Async::from_instance_config(&fake.instance_config())?;
+        let submitter =
+            icinga2.passive_result_submitter(NonZeroUsize::new(4).ok_or("zero concurrency")?);
+        let sender = submitter.sender();
+        let produced = (0..10)
+            .map(|index| service_result("bulk", &format!("svc{index}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let producer = tokio::spawn(async move {
+            for result in produced {
+                sender.send(result).await?;
+            }
+            Ok::<_, mpsc::error::SendError<ProcessCheckResult>>(())
+        });
+        for index in 10..20 {
+            submitter
+                .submit(service_result("bulk", &format!("svc{index}"))?)
+                .await?;
+        }
+        submitter.submit(service_result("missing", "svc0")?).await?;
+        producer.await??;
+        let late_sender = submitter.sender();
+        let report = tokio::time::timeout(std::time::Duration::from_secs(10), submitter.shutdown())
+            .await??;
+        assert!(
+            late_sender
+                .send(service_result("bulk", "svc0")?)
+                .await
+                .is_err()
+        );
+        assert_eq!(report.submitted, 20);
+        assert!(!report.is_success());
+        assert_eq!(
+            report
+                .failures
+                .iter()
+                .map(|failure| failure.result.plugin_output.as_str())
+                .collect::<Vec<_>>(),
+            vec!["svc0 on missing is critical"]
+        );
+        for index in 0..20 {
+            assert_eq!(
+                fake.object(IcingaObjectType::Service, &format!("bulk!svc{index}"))
+                    .and_then(|service| service.pointer("/state").cloned()),
+                Some(serde_json::json!(2)),
+                "svc{index} should be critical"
+            );
+        }
+        Ok(())
+    }
+}
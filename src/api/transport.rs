@@ -0,0 +1,212 @@
+//! HTTP backends used by the clients to send requests
+//!
+//! The clients build a complete [TransportRequest] including authentication
+//! and all headers and only use a backend to send it and receive the
+//! response. [Transport] is used by the blocking client, [AsyncTransport] by
+//! the async client.
+//!
+//! The backends shipped with this crate are enabled by features:
+//!
+//! * `blocking` and `async` enable the reqwest backends (`reqwest_backend`)
+//! * `ureq` enables a blocking backend using ureq (`ureq_backend`)
+//! * `hyper` enables an async backend using hyper directly (`hyper_backend`)
+//!
+//! The `blocking-client` and `async-client` features enable the clients
+//! without any backend, a backend is then passed to `from_transport`.
+
+#[cfg(feature = "hyper")]
+pub mod hyper_backend;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub mod reqwest_backend;
+#[cfg(feature = "ureq")]
+pub mod ureq_backend;
+
+use base64::Engine as _;
+use serde::Serialize;
+
+use crate::types::rest::HttpMethod;
+
+/// a request as passed to a backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransportRequest {
+    /// the HTTP method to send the request with
+    pub method: HttpMethod,
+    /// the full URL of the request
+    pub url: url::Url,
+    /// all headers to send, including the Authorization header
+    pub headers: http::HeaderMap,
+    /// the serialized JSON request body if there is one
+    pub body: Option<Vec<u8>>,
+}
+
+impl TransportRequest {
+    /// build a request with the headers every request to Icinga needs
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the request body can not be serialized or the
+    /// credentials can not be sent in a header
+    pub fn new<Body>(
+        method: HttpMethod,
+        url: url::Url,
+        body: Option<&Body>,
+        username: &str,
+        password: &str,
+    ) -> Result<Self, crate::error::Error>
+    where
+        Body: Serialize + ?Sized,
+    {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(
+            http::header::USER_AGENT,
+            http::HeaderValue::from_static(concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION")
+            )),
+        );
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            http::HeaderValue::from_static("application/json"),
+        );
+        headers.insert(
+            http::header::ACCEPT,
+            http::HeaderValue::from_static("application/json"),
+        );
+        let credentials =
+            base64::engine::general_purpose::STANDARD.encode(format!("{username}:{password}"));
+        let mut authorization = http::HeaderValue::try_from(format!("Basic {credentials}"))
+            .map_err(crate::error::Error::CouldNotBuildAuthorizationHeader)?;
+        authorization.set_sensitive(true);
+        headers.insert(http::header::AUTHORIZATION, authorization);
+        let body = body
+            .map(|body| {
+                let mut buffer = Vec::new();
+                serde_path_to_error::serialize(body, &mut serde_json::Serializer::new(&mut buffer))
+                    .map(|()| buffer)
+            })
+            .transpose()?;
+        Ok(Self {
+            method,
+            url,
+            headers,
+            body,
+        })
+    }
+}
+
+/// the response returned by a backend, the body is not read yet
+#[derive(Debug)]
+pub struct TransportResponse<Body> {
+    /// the HTTP status of the response
+    pub status: http::StatusCode,
+    /// the response headers
+    pub headers: http::HeaderMap,
+    /// the response body
+    pub body: Body,
+}
+
+/// the response body as returned by a blocking backend
+pub type BlockingBody = Box<dyn std::io::Read + Send>;
+
+/// the response body as returned by an async backend
+#[cfg(feature = "async-client")]
+pub type AsyncBody = futures::stream::BoxStream<'static, Result<bytes::Bytes, std::io::Error>>;
+
+/// a blocking HTTP backend
+pub trait Transport: std::fmt::Debug + Send + Sync {
+    /// send the request and return the response once its headers were received
+    ///
+    /// error statuses are returned as responses, not as errors
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the request could not be sent or no response
+    /// was received
+    fn send(
+        &self,
+        request: TransportRequest,
+    ) -> Result<TransportResponse<BlockingBody>, crate::error::Error>;
+}
+
+/// an async HTTP backend
+#[cfg(feature = "async-client")]
+pub trait AsyncTransport: std::fmt::Debug + Send + Sync {
+    /// send the request and return the response once its headers were received
+    ///
+    /// error statuses are returned as responses, not as errors
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the request could not be sent or no response
+    /// was received
+    fn send(
+        &self,
+        request: TransportRequest,
+    ) -> futures::future::BoxFuture<'_, Result<TransportResponse<AsyncBody>, crate::error::Error>>;
+}
+
+/// read the CA certificate configured for an instance if there is one
+///
+/// # Errors
+///
+/// this returns an error if the CA certificate file can not be read
+#[cfg(any(
+    feature = "async",
+    feature = "blocking",
+    feature = "hyper",
+    feature = "ureq"
+))]
+pub(crate) fn read_ca_certificate(
+    config: &crate::config::Icinga2Instance,
+) -> Result<Option<Vec<u8>>, crate::error::Error> {
+    config
+        .ca_certificate
+        .as_ref()
+        .map(|ca_certificate| {
+            fs_err::read(ca_certificate).map_err(crate::error::Error::CouldNotReadCACertFile)
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+
+    #[test]
+    fn test_transport_request() -> Result<(), Box<dyn Error>> {
+        let request = TransportRequest::new(
+            HttpMethod::Post,
+            url::Url::parse("https://icinga.example.com:5665/v1/actions/reschedule-check")?,
+            Some(&serde_json::json!({ "type": "Host" })),
+            "root",
+            "icinga",
+        )?;
+        assert_eq!(
+            request
+                .headers
+                .get(http::header::AUTHORIZATION)
+                .map(http::HeaderValue::as_bytes),
+            Some(&b"Basic cm9vdDppY2luZ2E="[..])
+        );
+        assert_eq!(
+            request
+                .headers
+                .get(http::header::CONTENT_TYPE)
+                .map(http::HeaderValue::as_bytes),
+            Some(&b"application/json"[..])
+        );
+        assert_eq!(request.body, Some(br#"{"type":"Host"}"#.to_vec()));
+        let request = TransportRequest::new::<serde_json::Value>(
+            HttpMethod::Get,
+            url::Url::parse("https://icinga.example.com:5665/v1/status")?,
+            None,
+            "root",
+            "icinga",
+        )?;
+        assert_eq!(request.body, None);
+        Ok(())
+    }
+}
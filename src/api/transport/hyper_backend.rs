@@ -0,0 +1,152 @@
+//! async HTTP backend using hyper directly
+//!
+//! this avoids the dependencies reqwest adds on top of hyper, without a CA
+//! certificate in the instance config the Mozilla root certificates are used
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::stream::{StreamExt as _, TryStreamExt as _};
+use http_body_util::{BodyExt as _, Full};
+use rustls::pki_types::{CertificateDer, pem::PemObject as _};
+
+use crate::{
+    api::transport::{
+        AsyncBody, AsyncTransport, TransportRequest, TransportResponse, read_ca_certificate,
+    },
+    config::Icinga2Instance,
+};
+
+/// the type of the hyper client used by [HyperTransport]
+type HyperClient = hyper_util::client::legacy::Client<
+    hyper_rustls::HttpsConnector<hyper_util::client::legacy::connect::HttpConnector>,
+    Full<Bytes>,
+>;
+
+/// async backend using the hyper client from hyper-util with rustls
+#[derive(Debug, Clone)]
+pub struct HyperTransport {
+    /// the HTTP client to use
+    client: HyperClient,
+}
+
+impl HyperTransport {
+    /// create a backend using the CA certificate from the instance config
+    ///
+    /// # Errors
+    /// this fails if the CA certificate file mentioned in the configuration
+    /// can not be found or parsed
+    pub fn from_instance_config(config: &Icinga2Instance) -> Result<Self, crate::error::Error> {
+        let mut roots = rustls::RootCertStore::empty();
+        if let Some(ca_cert_content) = read_ca_certificate(config)? {
+            for ca_cert in CertificateDer::pem_slice_iter(&ca_cert_content) {
+                let ca_cert = ca_cert
+                    .map_err(|e| crate::error::Error::CouldNotBuildTransport(Box::new(e)))?;
+                roots
+                    .add(ca_cert)
+                    .map_err(|e| crate::error::Error::CouldNotBuildTransport(Box::new(e)))?;
+            }
+            if roots.is_empty() {
+                return Err(crate::error::Error::CouldNotBuildTransport(
+                    "no certificate in CA certificate file".into(),
+                ));
+            }
+        } else {
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        }
+        let tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| crate::error::Error::CouldNotBuildTransport(Box::new(e)))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_tls_config(tls_config)
+            .https_or_http()
+            .enable_http1()
+            .build();
+        let client =
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                .build(connector);
+        Ok(Self { client })
+    }
+}
+
+impl AsyncTransport for HyperTransport {
+    fn send(
+        &self,
+        request: TransportRequest,
+    ) -> futures::future::BoxFuture<'_, Result<TransportResponse<AsyncBody>, crate::error::Error>>
+    {
+        Box::pin(async move {
+            let mut req = http::Request::builder()
+                .method(http::Method::from(request.method))
+                .uri(request.url.as_str());
+            if let Some(headers) = req.headers_mut() {
+                headers.extend(request.headers);
+            }
+            let req = req
+                .body(Full::new(Bytes::from(request.body.unwrap_or_default())))
+                .map_err(|e| crate::error::Error::TransportError(Box::new(e)))?;
+            let response = self
+                .client
+                .request(req)
+                .await
+                .map_err(|e| crate::error::Error::TransportError(Box::new(e)))?;
+            let (parts, body) = response.into_parts();
+            Ok(TransportResponse {
+                status: parts.status,
+                headers: parts.headers,
+                body: body
+                    .into_data_stream()
+                    .map_err(std::io::Error::other)
+                    .boxed(),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+    use tracing_test::traced_test;
+
+    use crate::{
+        api::{async_client::Icinga2Async, query::monitoring_objects::host::ListHosts},
+        types::{
+            monitoring_objects::host::IcingaHost,
+            query::{QueryResultObject, ResultsWrapper},
+        },
+    };
+
+    #[traced_test]
+    #[tokio::test]
+    async fn test_hyper_transport() -> Result<(), Box<dyn Error>> {
+        let fake = crate::testing::FakeIcinga::start()?;
+        fake.add_host("hyper-host");
+        let config = fake.instance_config();
+        let icinga2 =
+            Icinga2Async::from_transport(&config, HyperTransport::from_instance_config(&config)?)?;
+        let hosts: ResultsWrapper<QueryResultObject<IcingaHost>> =
+            icinga2.rest(ListHosts::builder().build()?).await?;
+        assert_eq!(
+            hosts
+                .results
+                .iter()
+                .map(|h| h.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["hyper-host"]
+        );
+        let names = icinga2
+            .rest_stream::<_, QueryResultObject<IcingaHost>>(ListHosts::builder().build()?)
+            .await?
+            .map_ok(|h| h.name)
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(names, vec!["hyper-host"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+//! HTTP backends using reqwest
+//!
+//! these are the default backends of [crate::api::blocking::Icinga2] and
+//! [crate::api::async_client::Icinga2Async]
+
+#[cfg(feature = "async")]
+use futures::stream::{StreamExt as _, TryStreamExt as _};
+
+#[cfg(feature = "async")]
+use crate::api::transport::{AsyncBody, AsyncTransport};
+#[cfg(feature = "blocking")]
+use crate::api::transport::{BlockingBody, Transport};
+use crate::{
+    api::transport::{TransportRequest, TransportResponse, read_ca_certificate},
+    config::Icinga2Instance,
+};
+
+/// read the CA certificate configured for an instance as a reqwest certificate
+///
+/// # Errors
+///
+/// this returns an error if the CA certificate file can not be read or parsed
+fn ca_certificate(
+    config: &Icinga2Instance,
+) -> Result<Option<reqwest::Certificate>, crate::error::Error> {
+    read_ca_certificate(config)?
+        .map(|ca_cert_content| {
+            reqwest::Certificate::from_pem(&ca_cert_content)
+                .map_err(crate::error::Error::CouldNotParsePEMCACertificate)
+        })
+        .transpose()
+}
+
+/// blocking backend using [reqwest::blocking::Client]
+#[cfg(feature = "blocking")]
+#[derive(Debug, Clone)]
+pub struct ReqwestBlockingTransport {
+    /// the HTTP client to use
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "blocking")]
+impl ReqwestBlockingTransport {
+    /// create a backend using the CA certificate from the instance config
+    ///
+    /// # Errors
+    /// this fails if the CA certificate file mentioned in the configuration
+    /// can not be found or parsed
+    pub fn from_instance_config(config: &Icinga2Instance) -> Result<Self, crate::error::Error> {
+        let client_builder = reqwest::blocking::ClientBuilder::new();
+        let client_builder = if let Some(ca_cert) = ca_certificate(config)? {
+            client_builder.tls_certs_only([ca_cert])
+        } else {
+            client_builder
+        };
+        let client = client_builder
+            .build()
+            .map_err(crate::error::Error::CouldNotBuildReqwestClientFromSuppliedInformation)?;
+        Ok(Self { client })
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl Transport for ReqwestBlockingTransport {
+    fn send(
+        &self,
+        request: TransportRequest,
+    ) -> Result<TransportResponse<BlockingBody>, crate::error::Error> {
+        let mut req = self
+            .client
+            .request(request.method.into(), request.url)
+            .headers(request.headers);
+        if let Some(body) = request.body {
+            req = req.body(body);
+        }
+        let response = req.send()?;
+        Ok(TransportResponse {
+            status: response.status(),
+            headers: response.headers().to_owned(),
+            body: Box::new(response),
+        })
+    }
+}
+
+/// async backend using [reqwest::Client]
+#[cfg(feature = "async")]
+#[derive(Debug, Clone)]
+pub struct ReqwestAsyncTransport {
+    /// the HTTP client to use
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "async")]
+impl ReqwestAsyncTransport {
+    /// create a backend using the CA certificate from the instance config
+    ///
+    /// # Errors
+    /// this fails if the CA certificate file mentioned in the configuration
+    /// can not be found or parsed
+    pub fn from_instance_config(config: &Icinga2Instance) -> Result<Self, crate::error::Error> {
+        let client_builder = reqwest::ClientBuilder::new();
+        let client_builder = if let Some(ca_cert) = ca_certificate(config)? {
+            client_builder.tls_certs_only([ca_cert])
+        } else {
+            client_builder
+        };
+        let client = client_builder
+            .build()
+            .map_err(crate::error::Error::CouldNotBuildReqwestClientFromSuppliedInformation)?;
+        Ok(Self { client })
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncTransport for ReqwestAsyncTransport {
+    fn send(
+        &self,
+        request: TransportRequest,
+    ) -> futures::future::BoxFuture<'_, Result<TransportResponse<AsyncBody>, crate::error::Error>>
+    {
+        let mut req = self
+            .client
+            .request(request.method.into(), request.url)
+            .headers(request.headers);
+        if let Some(body) = request.body {
+            req = req.body(body);
+        }
+        Box::pin(async move {
+            let response = req.send().await?;
+            Ok(TransportResponse {
+                status: response.status(),
+                headers: response.headers().to_owned(),
+                body: response
+                    .bytes_stream()
+                    .map_err(std::io::Error::other)
+                    .boxed(),
+            })
+        })
+    }
+}
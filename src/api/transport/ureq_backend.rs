@@ -0,0 +1,114 @@
+//! blocking HTTP backend using ureq
+//!
+//! this has a much smaller dependency footprint than reqwest, without a CA
+//! certificate in the instance config the Mozilla root certificates are used
+
+use std::sync::Arc;
+
+use crate::{
+    api::transport::{
+        BlockingBody, Transport, TransportRequest, TransportResponse, read_ca_certificate,
+    },
+    config::Icinga2Instance,
+};
+
+/// blocking backend using [ureq::Agent]
+#[derive(Debug, Clone)]
+pub struct UreqTransport {
+    /// the HTTP agent to use
+    agent: ureq::Agent,
+}
+
+impl UreqTransport {
+    /// create a backend using the CA certificate from the instance config
+    ///
+    /// # Errors
+    /// this fails if the CA certificate file mentioned in the configuration
+    /// can not be found or parsed
+    pub fn from_instance_config(config: &Icinga2Instance) -> Result<Self, crate::error::Error> {
+        let tls_config = ureq::tls::TlsConfig::builder();
+        let tls_config = if let Some(ca_cert_content) = read_ca_certificate(config)? {
+            let ca_cert = ureq::tls::Certificate::from_pem(&ca_cert_content)
+                .map_err(|e| crate::error::Error::CouldNotBuildTransport(Box::new(e)))?;
+            tls_config.root_certs(ureq::tls::RootCerts::Specific(Arc::new(vec![ca_cert])))
+        } else {
+            tls_config
+        };
+        let agent = ureq::Agent::new_with_config(
+            ureq::Agent::config_builder()
+                .http_status_as_error(false)
+                .tls_config(tls_config.build())
+                .build(),
+        );
+        Ok(Self { agent })
+    }
+}
+
+impl Transport for UreqTransport {
+    fn send(
+        &self,
+        request: TransportRequest,
+    ) -> Result<TransportResponse<BlockingBody>, crate::error::Error> {
+        let mut req = http::Request::builder()
+            .method(http::Method::from(request.method))
+            .uri(request.url.as_str());
+        if let Some(headers) = req.headers_mut() {
+            headers.extend(request.headers);
+        }
+        let req = req
+            .body(request.body.unwrap_or_default())
+            .map_err(|e| crate::error::Error::TransportError(Box::new(e)))?;
+        let response = self
+            .agent
+            .run(req)
+            .map_err(|e| crate::error::Error::TransportError(Box::new(e)))?;
+        let (parts, body) = response.into_parts();
+        Ok(TransportResponse {
+            status: parts.status,
+            headers: parts.headers,
+            body: Box::new(body.into_reader()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+    use tracing_test::traced_test;
+
+    use crate::{
+        api::{blocking::Icinga2, query::monitoring_objects::host::ListHosts},
+        types::{
+            monitoring_objects::host::IcingaHost,
+            query::{QueryResultObject, ResultsWrapper},
+        },
+    };
+
+    #[traced_test]
+    #[test]
+    fn test_ureq_transport() -> Result<(), Box<dyn Error>> {
+        let fake = crate::testing::FakeIcinga::start()?;
+        fake.add_host("ureq-host");
+        let config = fake.instance_config();
+        let icinga2 =
+            Icinga2::from_transport(&config, UreqTransport::from_instance_config(&config)?)?;
+        let hosts: ResultsWrapper<QueryResultObject<IcingaHost>> =
+            icinga2.rest(ListHosts::builder().build()?)?;
+        assert_eq!(
+            hosts
+                .results
+                .iter()
+                .map(|h| h.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["ureq-host"]
+        );
+        let names = icinga2
+            .rest_stream::<_, QueryResultObject<IcingaHost>>(ListHosts::builder().build()?)?
+            .map(|h| h.map(|h| h.name))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(names, vec!["ureq-host"]);
+        Ok(())
+    }
+}
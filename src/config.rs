@@ -1,11 +1,18 @@
 //! Configuration related code
 
-use std::path::{Path, PathBuf};
+use std::{
+    num::{NonZeroU32, NonZeroUsize},
+    path::{Path, PathBuf},
+};
 
 use serde::Deserialize;
 
 /// this represents the configuration for an Icinga instance we connect to
+///
+/// create it with [Self::new] or [Self::from_config_file], new optional
+/// settings may be added in the future
 #[derive(Debug, Clone, Deserialize)]
+#[non_exhaustive]
 pub struct Icinga2Instance {
     /// the URL to connect to, without the v1 component or anything after that
     pub url: String,
@@ -15,9 +22,56 @@ pub struct Icinga2Instance {
     pub username: String,
     /// password
     pub password: String,
+    /// limit the rate of requests sent to this instance
+    #[serde(default)]
+    pub rate_limit: Option<Icinga2RateLimit>,
+    /// limit the number of requests in flight to this instance at any time
+    #[serde(default)]
+    pub max_in_flight: Option<NonZeroUsize>,
+}
+
+/// the configuration of the token bucket used for client side rate limiting
+#[derive(Debug, Clone, Deserialize)]
+pub struct Icinga2RateLimit {
+    /// the number of requests allowed per second on average
+    pub requests_per_second: NonZeroU32,
+    /// the number of requests that may be sent in a burst without waiting
+    pub burst: NonZeroU32,
 }
 
 impl Icinga2Instance {
+    /// create a new configuration without any client side limits
+    #[must_use]
+    pub const fn new(
+        url: String,
+        ca_certificate: Option<PathBuf>,
+        username: String,
+        password: String,
+    ) -> Self {
+        Self {
+            url,
+            ca_certificate,
+            username,
+            password,
+            rate_limit: None,
+            max_in_flight: None,
+        }
+    }
+
+    /// limit the rate of requests sent to this instance
+    #[must_use]
+    pub const fn with_rate_limit(mut self, rate_limit: Icinga2RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// limit the number of requests in flight to this instance at any time
+    #[must_use]
+    pub const fn with_max_in_flight(mut self, max_in_flight: NonZeroUsize) -> Self {
+        self.max_in_flight = Some(max_in_flight);
+        self
+    }
+
     /// create a new Icinga2 instance from a TOML config file
     ///
     /// # Errors
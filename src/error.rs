@@ -17,23 +17,55 @@ pub enum Error {
     #[error("could not parse URL in config: {0}")]
     CouldNotParseUrlInConfig(url::ParseError),
     /// could not build reqwest client from supplied information
+    #[cfg(feature = "reqwest")]
     #[error("could not build reqwest client from supplied information: {0}")]
     CouldNotBuildReqwestClientFromSuppliedInformation(reqwest::Error),
     /// could not read CA certificate file
     #[error("could not read CA certificate file: {0}")]
     CouldNotReadCACertFile(std::io::Error),
     /// could not parse PEM CA certificate
+    #[cfg(feature = "reqwest")]
     #[error("could not parse PEM CA certificate: {0}")]
     CouldNotParsePEMCACertificate(reqwest::Error),
     /// An error occurred when serializing/deserializing JSON
     #[error("error in json serialization/deserialization: {0}")]
     SerdeJsonError(#[from] serde_path_to_error::Error<serde_json::Error>),
     /// Response body was empty so we can not deserialize it as JSON
+    #[cfg(feature = "client")]
     #[error("empty response body with status: {0}")]
-    EmptyResponseBody(reqwest::StatusCode),
+    EmptyResponseBody(http::StatusCode),
     /// An error occurred in the reqwest library (HTTP)
+    #[cfg(feature = "reqwest")]
     #[error("reqwest error: {0}")]
     ReqwestError(#[from] reqwest::Error),
+    /// could not read the response body
+    #[cfg(feature = "client")]
+    #[error("could not read response body: {0}")]
+    CouldNotReadResponseBody(std::io::Error),
+    /// a streamed response body was not an object containing a `results` array
+    #[cfg(feature = "client")]
+    #[error("malformed results in response body: {0}")]
+    MalformedResultsStream(&'static str),
+    /// a request returned an error status, the body is included
+    #[cfg(feature = "client")]
+    #[error("error status {0} in response: {1}")]
+    ErrorStatus(http::StatusCode, String),
+    /// a streamed request returned an error status, the body is included
+    #[cfg(feature = "client")]
+    #[error("error status {0} in response: {1}")]
+    ErrorStatusInResultsStream(http::StatusCode, String),
+    /// could not build an HTTP backend from the supplied information
+    #[cfg(feature = "client")]
+    #[error("could not build HTTP transport from supplied information: {0}")]
+    CouldNotBuildTransport(Box<dyn std::error::Error + Send + Sync>),
+    /// an error occurred in an HTTP backend (see [crate::api::transport])
+    #[cfg(feature = "client")]
+    #[error("transport error: {0}")]
+    TransportError(Box<dyn std::error::Error + Send + Sync>),
+    /// the credentials can not be sent in an Authorization header
+    #[cfg(feature = "client")]
+    #[error("could not build Authorization header: {0}")]
+    CouldNotBuildAuthorizationHeader(http::header::InvalidHeaderValue),
     /// could not parse URL fragment
     #[error("could not parse URL fragment: {0}")]
     CouldNotParseUrlFragment(url::ParseError),
@@ -43,10 +75,87 @@ pub enum Error {
     /// uninitialized field in builder
     #[error("uninitialized field in builder: {0}")]
     UninitializedFieldInBuilder(#[from] derive_builder::UninitializedFieldError),
+    /// the exit status of a passive check result is for a different object type than the filter
+    #[error("exit status {0:?} can not be submitted for object type {1}")]
+    ExitStatusObjectTypeMismatch(
+        crate::api::action::process_check_result::ProcessCheckResultExitStatus,
+        IcingaObjectType,
+    ),
+    /// the exit status of a passive check result is a state Icinga does not accept
+    #[error("exit status {0:?} can not be submitted in a passive check result")]
+    ExitStatusNotSubmittable(crate::types::enums::host_or_service_state::IcingaHostOrServiceState),
     /// all_services invalid when targeting a service
     #[error("all_services is invalid when targeting a service for a downtime")]
     AllServicesInvalidOnServiceDowntime,
     /// duration parameter is required for flexible downtimes but not for fixed ones
     #[error("duration is required for flexible downtimes")]
     DurationRequiredOnFlexibleDowntime,
+    /// the path of a generic endpoint must be relative to the base URL and
+    /// stay below it
+    #[error("the path of a generic endpoint must be relative to the base URL but was {0}")]
+    AbsolutePathInGenericEndpoint(String),
+    /// could not read cassette file
+    #[cfg(feature = "client")]
+    #[error("could not read cassette file: {0}")]
+    CouldNotReadCassette(std::io::Error),
+    /// could not write cassette file
+    #[cfg(feature = "client")]
+    #[error("could not write cassette file: {0}")]
+    CouldNotWriteCassette(std::io::Error),
+    /// could not serialize cassette
+    #[cfg(feature = "client")]
+    #[error("could not serialize cassette: {0}")]
+    CouldNotSerializeCassette(serde_json::Error),
+    /// the cassette contained an invalid HTTP status code
+    #[cfg(feature = "client")]
+    #[error("invalid HTTP status code in cassette: {0}")]
+    InvalidStatusInCassette(u16),
+    /// no recorded interaction in the cassette matched the request
+    #[cfg(feature = "client")]
+    #[error("no matching interaction in cassette for {0} {1}")]
+    NoMatchingCassetteInteraction(String, String),
+    /// the plugin output can not be parsed unambiguously by Icinga
+    #[error("invalid plugin output: {0}")]
+    InvalidPluginOutput(String),
+    /// could not run a check plugin
+    #[error("could not run check plugin: {0}")]
+    CouldNotRunPlugin(std::io::Error),
+    /// a plugin argument that requires a value was the last argument
+    #[error("missing value for plugin argument {0}")]
+    MissingPluginArgumentValue(String),
+    /// the value of a threshold plugin argument is not a valid range
+    #[error("invalid threshold for plugin argument {0}: {1}")]
+    InvalidPluginThreshold(
+        String,
+        crate::types::threshold_range::ThresholdRangeParseError,
+    ),
+    /// could not read the passive check spool file
+    #[cfg(feature = "client")]
+    #[error("could not read passive check spool file: {0}")]
+    CouldNotReadSpool(std::io::Error),
+    /// could not write the passive check spool file
+    #[cfg(feature = "client")]
+    #[error("could not write passive check spool file: {0}")]
+    CouldNotWriteSpool(std::io::Error),
+    /// could not serialize a check result for the passive check spool
+    #[cfg(feature = "client")]
+    #[error("could not serialize check result for passive check spool: {0}")]
+    CouldNotSerializeSpoolEntry(serde_json::Error),
+    /// the semaphore limiting the requests in flight was closed
+    #[cfg(feature = "async-client")]
+    #[error("the limit on requests in flight was closed: {0}")]
+    InFlightLimitClosed(tokio::sync::AcquireError),
+    /// the background task of a passive result submitter stopped
+    #[cfg(feature = "async-client")]
+    #[error("the passive result submitter stopped")]
+    PassiveResultSubmitterStopped,
+    /// the background task of a passive result submitter panicked or was
+    /// cancelled
+    #[cfg(feature = "async-client")]
+    #[error("the passive result submitter failed: {0}")]
+    PassiveResultSubmitterFailed(tokio::task::JoinError),
+    /// could not start the fake Icinga server
+    #[cfg(any(test, feature = "testing"))]
+    #[error("could not start the fake Icinga server: {0}")]
+    CouldNotStartFakeIcinga(std::io::Error),
 }
@@ -3,5 +3,8 @@
 pub mod api;
 pub mod config;
 pub mod error;
+pub mod plugin;
 pub mod serde;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod types;
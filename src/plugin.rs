@@ -0,0 +1,421 @@
+//! Toolkit for writing check plugins
+//!
+//! [Monitoring Plugins Development Guidelines](https://www.monitoring-plugins.org/doc/guidelines.html)
+//!
+//! [IcingaPluginOutput] builds the output of a plugin so that Icinga can
+//! always split it into status line, long output and performance data and
+//! maps the state to the exit code. [IcingaPluginThresholds] handles the
+//! usual `--warning` and `--critical` arguments and evaluates values against
+//! them.
+
+use crate::types::{
+    common::performance_data::IcingaPerformanceData, enums::object_type::IcingaObjectType,
+    enums::service_state::IcingaServiceState, threshold_range::IcingaThresholdRange,
+};
+
+/// the output of a check plugin
+///
+/// not Eq because [IcingaPerformanceData] can contain floating point values
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[builder(
+    build_fn(error = "crate::error::Error", validate = "Self::validate"),
+    derive(Debug)
+)]
+pub struct IcingaPluginOutput {
+    /// the state of the check, this determines the exit code
+    pub state: IcingaServiceState,
+    /// an optional name at the start of the status line (e.g. `DISK`)
+    #[builder(default, setter(strip_option, into))]
+    pub name: Option<String>,
+    /// the summary on the status line
+    #[builder(setter(into))]
+    pub summary: String,
+    /// additional lines of output after the status line
+    #[builder(default, setter(each(name = "long_output_line", into)))]
+    pub long_output: Vec<String>,
+    /// the performance data
+    #[builder(default, setter(each(name = "perfdata")))]
+    pub performance_data: Vec<IcingaPerformanceData>,
+}
+
+impl IcingaPluginOutput {
+    /// create a new builder for plugin output
+    #[must_use]
+    pub fn builder() -> IcingaPluginOutputBuilder {
+        IcingaPluginOutputBuilder::default()
+    }
+
+    /// output with the state UNKNOWN, e.g. for invalid arguments
+    ///
+    /// the summary is trimmed and `|` and line breaks in it are replaced since
+    /// this is usually used for error messages
+    #[must_use]
+    pub fn unknown(summary: &str) -> Self {
+        Self {
+            state: IcingaServiceState::Unknown,
+            name: None,
+            summary: summary.trim().replace('|', "/").replace(['\r', '\n'], " "),
+            long_output: Vec::new(),
+            performance_data: Vec::new(),
+        }
+    }
+
+    /// the exit code of the plugin for the state
+    #[must_use]
+    pub const fn exit_code(&self) -> i32 {
+        exit_code(self.state)
+    }
+
+    /// print the output and exit the process with the exit code for the state
+    #[expect(
+        clippy::print_stdout,
+        reason = "check plugins return their output to Icinga on stdout"
+    )]
+    pub fn exit(&self) -> ! {
+        println!("{self}");
+        std::process::exit(self.exit_code())
+    }
+}
+
+impl IcingaPluginOutputBuilder {
+    /// makes sure Icinga can parse the output unambiguously
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the name or summary contain a `|` or a line
+    /// break, the long output contains a `|` or the performance data contains
+    /// a `|` or a line break
+    pub fn validate(&self) -> Result<(), crate::error::Error> {
+        let status_line = self.name.iter().flatten().chain(self.summary.iter());
+        for text in status_line {
+            if text.contains(['|', '\r', '\n']) {
+                return Err(crate::error::Error::InvalidPluginOutput(format!(
+                    "status line must be a single line without |: {text}"
+                )));
+            }
+        }
+        for line in self.long_output.iter().flatten() {
+            if line.contains('|') {
+                return Err(crate::error::Error::InvalidPluginOutput(format!(
+                    "long output must not contain |: {line}"
+                )));
+            }
+        }
+        for perfdata in self.performance_data.iter().flatten() {
+            let perfdata = perfdata.to_string();
+            if perfdata.contains(['|', '\r', '\n']) {
+                return Err(crate::error::Error::InvalidPluginOutput(format!(
+                    "performance data must be a single line without |: {perfdata}"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for IcingaPluginOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "{name} ")?;
+        }
+        write!(f, "{} - {}", state_label(self.state), self.summary)?;
+        if !self.performance_data.is_empty() {
+            write!(
+                f,
+                " | {}",
+                IcingaPerformanceData::format_all(&self.performance_data)
+            )?;
+        }
+        for line in &self.long_output {
+            write!(f, "\n{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// the exit code of a plugin for a state
+///
+/// plugins can not return UNREACHABLE or PENDING, those are mapped to UNKNOWN
+#[must_use]
+pub const fn exit_code(state: IcingaServiceState) -> i32 {
+    match state {
+        IcingaServiceState::Ok => 0,
+        IcingaServiceState::Warning => 1,
+        IcingaServiceState::Critical => 2,
+        IcingaServiceState::Unknown
+        | IcingaServiceState::Unreachable
+        | IcingaServiceState::Pending => 3,
+    }
+}
+
+/// the label of a state in the status line
+#[must_use]
+pub const fn state_label(state: IcingaServiceState) -> &'static str {
+    match state {
+        IcingaServiceState::Ok => "OK",
+        IcingaServiceState::Warning => "WARNING",
+        IcingaServiceState::Critical => "CRITICAL",
+        IcingaServiceState::Unknown
+        | IcingaServiceState::Unreachable
+        | IcingaServiceState::Pending => "UNKNOWN",
+    }
+}
+
+/// the worst of multiple states, CRITICAL is worse than WARNING which is
+/// worse than UNKNOWN which is worse than OK
+///
+/// returns OK if there are no states
+#[must_use]
+pub fn worst_state<I>(states: I) -> IcingaServiceState
+where
+    I: IntoIterator<Item = IcingaServiceState>,
+{
+    /// the rank of a state, higher is worse
+    const fn rank(state: IcingaServiceState) -> u8 {
+        match state {
+            IcingaServiceState::Ok => 0,
+            IcingaServiceState::Unknown
+            | IcingaServiceState::Unreachable
+            | IcingaServiceState::Pending => 1,
+            IcingaServiceState::Warning => 2,
+            IcingaServiceState::Critical => 3,
+        }
+    }
+    states
+        .into_iter()
+        .max_by_key(|state| rank(*state))
+        .unwrap_or(IcingaServiceState::Ok)
+}
+
+/// warning and critical thresholds of a plugin
+///
+/// not Eq because [IcingaThresholdRange] contains floating point values
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct IcingaPluginThresholds {
+    /// the warning threshold
+    pub warning: Option<IcingaThresholdRange>,
+    /// the critical threshold
+    pub critical: Option<IcingaThresholdRange>,
+}
+
+impl IcingaPluginThresholds {
+    /// extract the thresholds from command line arguments
+    ///
+    /// `-w`, `--warning`, `-c` and `--critical` are accepted with the
+    /// threshold as the next argument or (for the long options) after a `=`,
+    /// all other arguments are returned in their original order
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if a threshold argument has no value or the
+    /// value is not a valid threshold range
+    pub fn from_args<I, S>(args: I) -> Result<(Self, Vec<String>), crate::error::Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut thresholds = Self::default();
+        let mut remaining = Vec::new();
+        let mut args = args.into_iter().map(Into::into);
+        while let Some(arg) = args.next() {
+            let (option, value) = match arg.split_once('=') {
+                Some((option @ ("--warning" | "--critical"), value)) => {
+                    (option.to_owned(), value.to_owned())
+                }
+                _ if matches!(arg.as_str(), "-w" | "--warning" | "-c" | "--critical") => {
+                    let Some(value) = args.next() else {
+                        return Err(crate::error::Error::MissingPluginArgumentValue(arg));
+                    };
+                    (arg, value)
+                }
+                _ => {
+                    remaining.push(arg);
+                    continue;
+                }
+            };
+            let range = value
+                .parse()
+                .map_err(|e| crate::error::Error::InvalidPluginThreshold(option.to_owned(), e))?;
+            if matches!(option.as_str(), "-w" | "--warning") {
+                thresholds.warning = Some(range);
+            } else {
+                thresholds.critical = Some(range);
+            }
+        }
+        Ok((thresholds, remaining))
+    }
+
+    /// the state for a value, CRITICAL if it alerts for the critical
+    /// threshold, WARNING if it alerts for the warning threshold and OK
+    /// otherwise
+    #[must_use]
+    pub fn evaluate(&self, value: f64) -> IcingaServiceState {
+        if self.critical.is_some_and(|critical| critical.alerts(value)) {
+            IcingaServiceState::Critical
+        } else if self.warning.is_some_and(|warning| warning.alerts(value)) {
+            IcingaServiceState::Warning
+        } else {
+            IcingaServiceState::Ok
+        }
+    }
+
+    /// performance data for a value with these thresholds
+    #[must_use]
+    pub fn performance_data(
+        &self,
+        label: &str,
+        value: f64,
+        unit: Option<&str>,
+    ) -> IcingaPerformanceData {
+        IcingaPerformanceData::PerfDataValue {
+            object_type: IcingaObjectType::PerfdataValue,
+            counter: false,
+            value,
+            crit: self.critical,
+            warn: self.warning,
+            label: Some(label.to_owned()),
+            min: None,
+            max: None,
+            unit: unit.map(ToOwned::to_owned),
+        }
+    }
+}
+
+/// split the raw output of a plugin into the output text and the
+/// performance data like Icinga does
+///
+/// the text before the first `|` on the first line is the status line, the
+/// following lines are long output until a line containing a `|`, the text
+/// after that `|` and all following lines are performance data
+///
+/// performance data values that can not be parsed are kept in string
+/// format, the other values on the same line are still parsed
+#[must_use]
+pub fn parse_plugin_output(stdout: &str) -> (String, Vec<IcingaPerformanceData>) {
+    let mut output = Vec::new();
+    let mut perfdata = Vec::new();
+    let mut in_perfdata = false;
+    for (index, line) in stdout.trim().lines().enumerate() {
+        if in_perfdata {
+            perfdata.push(line);
+        } else if let Some((text, line_perfdata)) = line.split_once('|') {
+            output.push(text.trim_end());
+            perfdata.push(line_perfdata);
+            in_perfdata = index > 0;
+        } else {
+            output.push(line.trim_end());
+        }
+    }
+    let performance_data = perfdata
+        .into_iter()
+        .map(str::trim)
+        .filter(|perfdata| !perfdata.is_empty())
+        .flat_map(IcingaPerformanceData::parse_all_lossy)
+        .collect();
+    (output.join("\n"), performance_data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+
+    #[test]
+    fn test_plugin_output() -> Result<(), Box<dyn Error>> {
+        let (thresholds, args) =
+            IcingaPluginThresholds::from_args(["--warning=80", "--path", "/", "-c", "@90:"])?;
+        assert_eq!(args, vec!["--path", "/"]);
+        let value = 85.5;
+        let state = thresholds.evaluate(value);
+        assert_eq!(state, IcingaServiceState::Warning);
+        let output = IcingaPluginOutput::builder()
+            .state(worst_state([IcingaServiceState::Ok, state]))
+            .name("DISK")
+            .summary("/ is 85.5% full")
+            .long_output_line("/ 85.5%")
+            .long_output_line("/boot 12%")
+            .perfdata(thresholds.performance_data("'/' used", value, Some("%")))
+            .perfdata("boot=12%".parse()?)
+            .build()?;
+        assert_eq!(
+            output.to_string(),
+            "DISK WARNING - / is 85.5% full | '''/'' used'=85.5%;80;@90: boot=12%\n/ 85.5%\n/boot 12%"
+        );
+        assert_eq!(output.exit_code(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_thresholds() -> Result<(), Box<dyn Error>> {
+        let (thresholds, _) = IcingaPluginThresholds::from_args(["-w", "10:", "--critical", "5:"])?;
+        assert_eq!(thresholds.evaluate(20.0), IcingaServiceState::Ok);
+        assert_eq!(thresholds.evaluate(7.0), IcingaServiceState::Warning);
+        assert_eq!(thresholds.evaluate(1.0), IcingaServiceState::Critical);
+        assert_eq!(
+            IcingaPluginThresholds::default().evaluate(1e9),
+            IcingaServiceState::Ok
+        );
+        assert!(matches!(
+            IcingaPluginThresholds::from_args(["-w"]),
+            Err(crate::error::Error::MissingPluginArgumentValue(_))
+        ));
+        assert!(matches!(
+            IcingaPluginThresholds::from_args(["--critical=x"]),
+            Err(crate::error::Error::InvalidPluginThreshold(_, _))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_plugin_output() -> Result<(), Box<dyn Error>> {
+        let (output, perfdata) = parse_plugin_output(
+            "DISK OK - free space | /=2643MB;5948;5958;0;5968\n/ 15272 MB (77%);\n/boot 68 MB (69%);\n/home 69357 MB (27%); | /boot=68MB;88;93;0;98\n/home=69357MB;253404;253409;0;253414 broken\n",
+        );
+        assert_eq!(
+            output,
+            "DISK OK - free space\n/ 15272 MB (77%);\n/boot 68 MB (69%);\n/home 69357 MB (27%);"
+        );
+        assert_eq!(
+            perfdata,
+            vec![
+                "/=2643MB;5948;5958;0;5968".parse()?,
+                "/boot=68MB;88;93;0;98".parse()?,
+                "/home=69357MB;253404;253409;0;253414".parse()?,
+                IcingaPerformanceData::String("broken".to_owned()),
+            ]
+        );
+        let (output, perfdata) = parse_plugin_output("OK - no perfdata\nsecond | line");
+        assert_eq!(output, "OK - no perfdata\nsecond");
+        assert_eq!(
+            perfdata,
+            vec![IcingaPerformanceData::String("line".to_owned())]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_plugin_output() {
+        assert!(matches!(
+            IcingaPluginOutput::builder()
+                .state(IcingaServiceState::Ok)
+                .summary("a | b")
+                .build(),
+            Err(crate::error::Error::InvalidPluginOutput(_))
+        ));
+        assert!(matches!(
+            IcingaPluginOutput::builder()
+                .state(IcingaServiceState::Ok)
+                .summary("two\nlines")
+                .build(),
+            Err(crate::error::Error::InvalidPluginOutput(_))
+        ));
+        let unknown = IcingaPluginOutput::unknown("bad | input\n");
+        assert_eq!(unknown.to_string(), "UNKNOWN - bad / input");
+        assert_eq!(unknown.exit_code(), 3);
+        assert_eq!(
+            worst_state([IcingaServiceState::Unknown, IcingaServiceState::Ok]),
+            IcingaServiceState::Unknown
+        );
+    }
+}
@@ -1,6 +1,74 @@
 //! Custom deserializers for various parts of the Icinga API results
+//!
+//! the `chrono` and `jiff` features add equivalent timestamp (de)serializers
+//! for those crates and conversions from the [time] types used in this crate
 
-use serde::Deserialize as _;
+#[cfg(feature = "chrono")]
+pub mod chrono;
+#[cfg(feature = "jiff")]
+pub mod jiff;
+
+use serde::{Deserialize as _, Serialize as _};
+
+use crate::types::timestamp::IcingaTimestamp;
+
+/// deserializes an Icinga timestamp to unix timestamp microseconds with the
+/// conversion of [IcingaTimestamp], None if the timestamp is not set
+///
+/// shared by the timestamp deserializers for all supported time crates
+///
+/// # Errors
+///
+/// returns an error if the value is not a number or null or does not fit
+pub(crate) fn deserialize_unix_timestamp_micros<'de, D>(
+    deserializer: D,
+) -> Result<Option<i64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let timestamp = IcingaTimestamp::deserialize(deserializer)?;
+    if !timestamp.is_set() {
+        return Ok(None);
+    }
+    timestamp
+        .to_unix_timestamp_micros()
+        .map(Some)
+        .ok_or_else(|| serde::de::Error::custom(format!("timestamp {timestamp} out of range")))
+}
+
+/// serialize unix timestamp microseconds as an Icinga timestamp with the
+/// conversion of [IcingaTimestamp]
+///
+/// shared by the timestamp serializers for all supported time crates
+///
+/// # Errors
+///
+/// this should not return any errors
+pub(crate) fn serialize_unix_timestamp_micros<S>(
+    micros: i64,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    IcingaTimestamp::from_unix_timestamp_micros(micros).serialize(serializer)
+}
+
+/// the unix timestamp microseconds of a [time::OffsetDateTime]
+fn to_unix_timestamp_micros(v: &time::OffsetDateTime) -> i64 {
+    IcingaTimestamp::from(*v)
+        .to_unix_timestamp_micros()
+        .unwrap_or_default()
+}
+
+/// convert unix timestamp microseconds to a [time::OffsetDateTime]
+fn from_unix_timestamp_micros<E>(micros: i64) -> Result<time::OffsetDateTime, E>
+where
+    E: serde::de::Error,
+{
+    time::OffsetDateTime::from_unix_timestamp_nanos(i128::from(micros).saturating_mul(1_000))
+        .map_err(E::custom)
+}
 
 /// deserializes a unix timestamp with sub second accuracy
 /// (usually 6 digits after the decimal point for icinga)
@@ -10,22 +78,16 @@ use serde::Deserialize as _;
 /// returns an error if the value can not be parsed as an f64
 /// or if it can not be converted from a unix timestamp to a
 /// [time::OffsetDateTime]
-#[expect(
-    clippy::cast_possible_truncation,
-    clippy::as_conversions,
-    reason = "intentional conversion from f64 unix timestamp seconds to i128 nanoseconds for the Icinga API"
-)]
 pub fn deserialize_icinga_timestamp<'de, D>(
     deserializer: D,
 ) -> Result<time::OffsetDateTime, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    let f: f64 = f64::deserialize(deserializer)?;
-
-    let i = (f * 1_000_000_000f64) as i128;
-
-    time::OffsetDateTime::from_unix_timestamp_nanos(i).map_err(serde::de::Error::custom)
+    deserialize_unix_timestamp_micros(deserializer)?.map_or(
+        Ok(time::OffsetDateTime::UNIX_EPOCH),
+        from_unix_timestamp_micros,
+    )
 }
 
 /// serialize a unix timestamp with sub second accuracy
@@ -33,11 +95,6 @@ where
 /// # Errors
 ///
 /// this should not return any errors
-#[expect(
-    clippy::cast_precision_loss,
-    clippy::as_conversions,
-    reason = "intentional conversion from i128 nanoseconds to f64 unix timestamp seconds for the Icinga API"
-)]
 pub fn serialize_icinga_timestamp<S>(
     v: &time::OffsetDateTime,
     serializer: S,
@@ -45,8 +102,7 @@ pub fn serialize_icinga_timestamp<S>(
 where
     S: serde::Serializer,
 {
-    let ts = (v.unix_timestamp_nanos() as f64) / 1_000_000_000_f64;
-    <f64 as serde::Serialize>::serialize(&ts, serializer)
+    serialize_unix_timestamp_micros(to_unix_timestamp_micros(v), serializer)
 }
 
 /// deserializes an optional unix timestamp with sub second accuracy
@@ -58,28 +114,15 @@ where
 /// returns an error if the value can not be parsed as an f64
 /// or if it can not be converted from a unix timestamp to a
 /// [time::OffsetDateTime]
-#[expect(
-    clippy::cast_possible_truncation,
-    clippy::as_conversions,
-    reason = "intentional conversion from f64 unix timestamp seconds to i128 nanoseconds for the Icinga API"
-)]
 pub fn deserialize_optional_icinga_timestamp<'de, D>(
     deserializer: D,
 ) -> Result<Option<time::OffsetDateTime>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    let f: f64 = f64::deserialize(deserializer)?;
-
-    if f == 0.0f64 {
-        Ok(None)
-    } else {
-        let i = (f * 1_000_000_000f64) as i128;
-
-        Ok(Some(
-            time::OffsetDateTime::from_unix_timestamp_nanos(i).map_err(serde::de::Error::custom)?,
-        ))
-    }
+    deserialize_unix_timestamp_micros(deserializer)?
+        .map(from_unix_timestamp_micros)
+        .transpose()
 }
 
 /// serialize a unix timestamp with sub second accuracy
@@ -88,11 +131,6 @@ where
 /// # Errors
 ///
 /// this should not return any errors
-#[expect(
-    clippy::cast_precision_loss,
-    clippy::as_conversions,
-    reason = "intentional conversion from i128 nanoseconds to f64 unix timestamp seconds for the Icinga API"
-)]
 pub fn serialize_optional_icinga_timestamp<S>(
     v: &Option<time::OffsetDateTime>,
     serializer: S,
@@ -100,11 +138,7 @@ pub fn serialize_optional_icinga_timestamp<S>(
 where
     S: serde::Serializer,
 {
-    let mut ts = 0f64;
-    if let Some(v) = v {
-        ts = (v.unix_timestamp_nanos() as f64) / 1_000_000_000_f64;
-    }
-    <f64 as serde::Serialize>::serialize(&ts, serializer)
+    serialize_unix_timestamp_micros(v.as_ref().map_or(0, to_unix_timestamp_micros), serializer)
 }
 
 /// deserialize an optional String where None is represented as
@@ -0,0 +1,246 @@
+//! (de)serializers and conversions for [chrono] timestamps
+//!
+//! these mirror the timestamp functions in [crate::serde] and can be used
+//! with `#[serde(deserialize_with = "...")]` on your own types, [ToChrono]
+//! converts the timestamps in the types of this crate
+
+use crate::serde::{deserialize_unix_timestamp_micros, serialize_unix_timestamp_micros};
+
+/// the number of nanoseconds in a second
+const NANOSECONDS_PER_SECOND: i128 = 1_000_000_000;
+
+/// convert unix timestamp nanoseconds to a [chrono::DateTime]
+///
+/// returns None if the value is outside the range supported by chrono
+fn from_unix_timestamp_nanos(nanos: i128) -> Option<chrono::DateTime<chrono::Utc>> {
+    let seconds = i64::try_from(nanos.div_euclid(NANOSECONDS_PER_SECOND)).ok()?;
+    let subsec_nanos = u32::try_from(nanos.rem_euclid(NANOSECONDS_PER_SECOND)).ok()?;
+    chrono::DateTime::from_timestamp(seconds, subsec_nanos)
+}
+
+/// convert unix timestamp microseconds to a [chrono::DateTime]
+fn from_unix_timestamp_micros<E>(micros: i64) -> Result<chrono::DateTime<chrono::Utc>, E>
+where
+    E: serde::de::Error,
+{
+    chrono::DateTime::from_timestamp_micros(micros)
+        .ok_or_else(|| E::custom(format!("timestamp {micros}us out of range")))
+}
+
+/// deserializes a unix timestamp with sub second accuracy
+/// (usually 6 digits after the decimal point for icinga)
+///
+/// # Errors
+///
+/// returns an error if the value can not be parsed as an f64
+/// or if it can not be converted from a unix timestamp to a
+/// [chrono::DateTime]
+pub fn deserialize_icinga_timestamp<'de, D>(
+    deserializer: D,
+) -> Result<chrono::DateTime<chrono::Utc>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    deserialize_unix_timestamp_micros(deserializer)?
+        .map_or(Ok(chrono::DateTime::UNIX_EPOCH), from_unix_timestamp_micros)
+}
+
+/// serialize a unix timestamp with sub second accuracy
+///
+/// # Errors
+///
+/// this should not return any errors
+pub fn serialize_icinga_timestamp<S>(
+    v: &chrono::DateTime<chrono::Utc>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serialize_unix_timestamp_micros(v.timestamp_micros(), serializer)
+}
+
+/// deserializes an optional unix timestamp with sub second accuracy
+/// (usually 6 digits after the decimal point for icinga)
+/// if the value is 0 return None
+///
+/// # Errors
+///
+/// returns an error if the value can not be parsed as an f64
+/// or if it can not be converted from a unix timestamp to a
+/// [chrono::DateTime]
+pub fn deserialize_optional_icinga_timestamp<'de, D>(
+    deserializer: D,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    deserialize_unix_timestamp_micros(deserializer)?
+        .map(from_unix_timestamp_micros)
+        .transpose()
+}
+
+/// serialize a unix timestamp with sub second accuracy
+/// if the value is None serialize 0
+///
+/// # Errors
+///
+/// this should not return any errors
+pub fn serialize_optional_icinga_timestamp<S>(
+    v: &Option<chrono::DateTime<chrono::Utc>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serialize_unix_timestamp_micros(
+        v.as_ref().map_or(0, chrono::DateTime::timestamp_micros),
+        serializer,
+    )
+}
+
+/// conversion of the [time::OffsetDateTime] timestamps used in the types of
+/// this crate to [chrono::DateTime]
+///
+/// timestamps outside of the range supported by chrono saturate at
+/// [chrono::DateTime::MIN_UTC] or [chrono::DateTime::MAX_UTC]
+pub trait ToChrono {
+    /// the chrono representation of the timestamp
+    type Output;
+
+    /// convert the timestamp to chrono
+    #[must_use]
+    fn to_chrono(&self) -> Self::Output;
+}
+
+impl ToChrono for time::OffsetDateTime {
+    type Output = chrono::DateTime<chrono::Utc>;
+
+    fn to_chrono(&self) -> Self::Output {
+        let nanos = self.unix_timestamp_nanos();
+        from_unix_timestamp_nanos(nanos).unwrap_or(if nanos < 0 {
+            chrono::DateTime::<chrono::Utc>::MIN_UTC
+        } else {
+            chrono::DateTime::<chrono::Utc>::MAX_UTC
+        })
+    }
+}
+
+impl ToChrono for Option<time::OffsetDateTime> {
+    type Output = Option<chrono::DateTime<chrono::Utc>>;
+
+    fn to_chrono(&self) -> Self::Output {
+        self.as_ref().map(ToChrono::to_chrono)
+    }
+}
+
+impl ToChrono for crate::types::timestamp::IcingaTimestamp {
+    type Output = Option<chrono::DateTime<chrono::Utc>>;
+
+    fn to_chrono(&self) -> Self::Output {
+        self.to_unix_timestamp_micros()
+            .and_then(chrono::DateTime::from_timestamp_micros)
+    }
+}
+
+impl ToChrono for time::Duration {
+    type Output = chrono::TimeDelta;
+
+    fn to_chrono(&self) -> Self::Output {
+        chrono::TimeDelta::try_seconds(self.whole_seconds())
+            .and_then(|d| {
+                d.checked_add(&chrono::TimeDelta::nanoseconds(i64::from(
+                    self.subsec_nanoseconds(),
+                )))
+            })
+            .unwrap_or_else(|| {
+                if self.is_negative() {
+                    chrono::TimeDelta::MIN
+                } else {
+                    chrono::TimeDelta::MAX
+                }
+            })
+    }
+}
+
+impl ToChrono for Option<time::Duration> {
+    type Output = Option<chrono::TimeDelta>;
+
+    fn to_chrono(&self) -> Self::Output {
+        self.as_ref().map(ToChrono::to_chrono)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+
+    /// a struct using the chrono (de)serializers
+    #[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    struct Timestamps {
+        /// a timestamp
+        #[serde(
+            serialize_with = "serialize_icinga_timestamp",
+            deserialize_with = "deserialize_icinga_timestamp"
+        )]
+        timestamp: chrono::DateTime<chrono::Utc>,
+        /// an optional timestamp
+        #[serde(
+            serialize_with = "serialize_optional_icinga_timestamp",
+            deserialize_with = "deserialize_optional_icinga_timestamp"
+        )]
+        optional: Option<chrono::DateTime<chrono::Utc>>,
+    }
+
+    #[test]
+    fn test_chrono_timestamps() -> Result<(), Box<dyn Error>> {
+        let timestamps: Timestamps =
+            serde_json::from_str(r#"{"timestamp":1700000000.5,"optional":0}"#)?;
+        assert_eq!(
+            timestamps,
+            Timestamps {
+                timestamp: chrono::DateTime::from_timestamp(1_700_000_000, 500_000_000)
+                    .ok_or("invalid timestamp")?,
+                optional: None,
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&timestamps)?,
+            r#"{"timestamp":1700000000.5,"optional":0.0}"#
+        );
+        let timestamps: Timestamps = serde_json::from_str(
+            r#"{"timestamp":1700000000.000001,"optional":1699999999.999999}"#,
+        )?;
+        assert_eq!(
+            timestamps.timestamp.timestamp_micros(),
+            1_700_000_000_000_001
+        );
+        assert_eq!(
+            timestamps
+                .optional
+                .map(|optional| optional.timestamp_micros()),
+            Some(1_699_999_999_999_999)
+        );
+        assert_eq!(
+            serde_json::to_string(&timestamps)?,
+            r#"{"timestamp":1700000000.000001,"optional":1699999999.999999}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_chrono() -> Result<(), Box<dyn Error>> {
+        let timestamp = time::OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_250_000_000)?;
+        assert_eq!(
+            Some(timestamp).to_chrono(),
+            chrono::DateTime::from_timestamp(1_700_000_000, 250_000_000)
+        );
+        assert_eq!(
+            time::Duration::milliseconds(-1500).to_chrono(),
+            chrono::TimeDelta::milliseconds(-1500)
+        );
+        Ok(())
+    }
+}
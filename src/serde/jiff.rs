@@ -0,0 +1,207 @@
+//! (de)serializers and conversions for [jiff] timestamps
+//!
+//! these mirror the timestamp functions in [crate::serde] and can be used
+//! with `#[serde(deserialize_with = "...")]` on your own types, [ToJiff]
+//! converts the timestamps in the types of this crate
+
+use crate::serde::{deserialize_unix_timestamp_micros, serialize_unix_timestamp_micros};
+
+/// convert unix timestamp microseconds to a [jiff::Timestamp]
+fn from_unix_timestamp_micros<E>(micros: i64) -> Result<jiff::Timestamp, E>
+where
+    E: serde::de::Error,
+{
+    jiff::Timestamp::from_microsecond(micros).map_err(E::custom)
+}
+
+/// deserializes a unix timestamp with sub second accuracy
+/// (usually 6 digits after the decimal point for icinga)
+///
+/// # Errors
+///
+/// returns an error if the value can not be parsed as an f64
+/// or if it can not be converted from a unix timestamp to a
+/// [jiff::Timestamp]
+pub fn deserialize_icinga_timestamp<'de, D>(deserializer: D) -> Result<jiff::Timestamp, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    deserialize_unix_timestamp_micros(deserializer)?
+        .map_or(Ok(jiff::Timestamp::UNIX_EPOCH), from_unix_timestamp_micros)
+}
+
+/// serialize a unix timestamp with sub second accuracy
+///
+/// # Errors
+///
+/// this should not return any errors
+pub fn serialize_icinga_timestamp<S>(v: &jiff::Timestamp, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serialize_unix_timestamp_micros(v.as_microsecond(), serializer)
+}
+
+/// deserializes an optional unix timestamp with sub second accuracy
+/// (usually 6 digits after the decimal point for icinga)
+/// if the value is 0 return None
+///
+/// # Errors
+///
+/// returns an error if the value can not be parsed as an f64
+/// or if it can not be converted from a unix timestamp to a
+/// [jiff::Timestamp]
+pub fn deserialize_optional_icinga_timestamp<'de, D>(
+    deserializer: D,
+) -> Result<Option<jiff::Timestamp>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    deserialize_unix_timestamp_micros(deserializer)?
+        .map(from_unix_timestamp_micros)
+        .transpose()
+}
+
+/// serialize a unix timestamp with sub second accuracy
+/// if the value is None serialize 0
+///
+/// # Errors
+///
+/// this should not return any errors
+pub fn serialize_optional_icinga_timestamp<S>(
+    v: &Option<jiff::Timestamp>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serialize_unix_timestamp_micros(v.map_or(0, jiff::Timestamp::as_microsecond), serializer)
+}
+
+/// conversion of the [time::OffsetDateTime] timestamps used in the types of
+/// this crate to [jiff::Timestamp]
+///
+/// timestamps outside of the range supported by jiff saturate at
+/// [jiff::Timestamp::MIN] or [jiff::Timestamp::MAX]
+pub trait ToJiff {
+    /// the jiff representation of the timestamp
+    type Output;
+
+    /// convert the timestamp to jiff
+    #[must_use]
+    fn to_jiff(&self) -> Self::Output;
+}
+
+impl ToJiff for time::OffsetDateTime {
+    type Output = jiff::Timestamp;
+
+    fn to_jiff(&self) -> Self::Output {
+        let nanos = self.unix_timestamp_nanos();
+        jiff::Timestamp::from_nanosecond(nanos).unwrap_or(if nanos < 0 {
+            jiff::Timestamp::MIN
+        } else {
+            jiff::Timestamp::MAX
+        })
+    }
+}
+
+impl ToJiff for Option<time::OffsetDateTime> {
+    type Output = Option<jiff::Timestamp>;
+
+    fn to_jiff(&self) -> Self::Output {
+        self.as_ref().map(ToJiff::to_jiff)
+    }
+}
+
+impl ToJiff for crate::types::timestamp::IcingaTimestamp {
+    type Output = Option<jiff::Timestamp>;
+
+    fn to_jiff(&self) -> Self::Output {
+        self.to_unix_timestamp_micros()
+            .and_then(|micros| jiff::Timestamp::from_microsecond(micros).ok())
+    }
+}
+
+impl ToJiff for time::Duration {
+    type Output = jiff::SignedDuration;
+
+    fn to_jiff(&self) -> Self::Output {
+        jiff::SignedDuration::new(self.whole_seconds(), self.subsec_nanoseconds())
+    }
+}
+
+impl ToJiff for Option<time::Duration> {
+    type Output = Option<jiff::SignedDuration>;
+
+    fn to_jiff(&self) -> Self::Output {
+        self.as_ref().map(ToJiff::to_jiff)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+
+    /// a struct using the jiff (de)serializers
+    #[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    struct Timestamps {
+        /// a timestamp
+        #[serde(
+            serialize_with = "serialize_icinga_timestamp",
+            deserialize_with = "deserialize_icinga_timestamp"
+        )]
+        timestamp: jiff::Timestamp,
+        /// an optional timestamp
+        #[serde(
+            serialize_with = "serialize_optional_icinga_timestamp",
+            deserialize_with = "deserialize_optional_icinga_timestamp"
+        )]
+        optional: Option<jiff::Timestamp>,
+    }
+
+    #[test]
+    fn test_jiff_timestamps() -> Result<(), Box<dyn Error>> {
+        let timestamps: Timestamps =
+            serde_json::from_str(r#"{"timestamp":1700000000.5,"optional":0}"#)?;
+        assert_eq!(
+            timestamps,
+            Timestamps {
+                timestamp: jiff::Timestamp::new(1_700_000_000, 500_000_000)?,
+                optional: None,
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&timestamps)?,
+            r#"{"timestamp":1700000000.5,"optional":0.0}"#
+        );
+        let timestamps: Timestamps = serde_json::from_str(
+            r#"{"timestamp":1700000000.000001,"optional":1699999999.999999}"#,
+        )?;
+        assert_eq!(timestamps.timestamp.as_microsecond(), 1_700_000_000_000_001);
+        assert_eq!(
+            timestamps.optional.map(jiff::Timestamp::as_microsecond),
+            Some(1_699_999_999_999_999)
+        );
+        assert_eq!(
+            serde_json::to_string(&timestamps)?,
+            r#"{"timestamp":1700000000.000001,"optional":1699999999.999999}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_jiff() -> Result<(), Box<dyn Error>> {
+        let timestamp = time::OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_250_000_000)?;
+        assert_eq!(
+            Some(timestamp).to_jiff(),
+            Some(jiff::Timestamp::new(1_700_000_000, 250_000_000)?)
+        );
+        assert_eq!(
+            time::Duration::milliseconds(-1500).to_jiff(),
+            jiff::SignedDuration::from_millis(-1500)
+        );
+        Ok(())
+    }
+}
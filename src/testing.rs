@@ -0,0 +1,495 @@
+//! In-process fake Icinga API server for offline testing
+//!
+//! [FakeIcinga] serves `/v1/objects/*` from an in-memory object store,
+//! evaluates a useful subset of the Icinga filter language, performs the
+//! state changing parts of `/v1/actions/*` (downtimes, acknowledgements,
+//! comments, passive check results) and streams scripted events on
+//! `/v1/events`.
+//!
+//! The server runs on its own thread with its own tokio runtime so it can be
+//! used from both the blocking and the async client. It is shut down when the
+//! [FakeIcinga] value is dropped.
+//!
+//! Credentials are accepted but not verified.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{broadcast, oneshot};
+
+use crate::config::Icinga2Instance;
+use crate::types::enums::object_type::IcingaObjectType;
+use crate::types::event_stream::IcingaEvent;
+
+use self::server::{FakeEventMessage, FakeIcingaShared, FakeIcingaState};
+
+pub mod filter;
+pub mod fixtures;
+mod server;
+
+/// a request received by the fake server
+#[derive(Debug, Clone)]
+pub struct FakeRequest {
+    /// the HTTP method after applying X-HTTP-Method-Override
+    pub method: String,
+    /// the path of the URL
+    pub path: String,
+    /// the request headers
+    pub headers: http::HeaderMap,
+    /// the JSON request body, an empty object if there was none
+    pub body: serde_json::Value,
+}
+
+/// an in-process fake Icinga API server
+#[derive(Debug)]
+pub struct FakeIcinga {
+    /// the base URL the server listens on
+    url: url::Url,
+    /// state shared with the request handlers
+    shared: FakeIcingaShared,
+    /// tells the server thread to shut down
+    shutdown: Option<oneshot::Sender<()>>,
+    /// the server thread
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl FakeIcinga {
+    /// start a new fake server with an empty object store on a random local port
+    ///
+    /// # Errors
+    ///
+    /// this fails if the listening socket, the runtime or the server thread
+    /// can not be created
+    pub fn start() -> Result<Self, crate::error::Error> {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+            .map_err(crate::error::Error::CouldNotStartFakeIcinga)?;
+        listener
+            .set_nonblocking(true)
+            .map_err(crate::error::Error::CouldNotStartFakeIcinga)?;
+        let address = listener
+            .local_addr()
+            .map_err(crate::error::Error::CouldNotStartFakeIcinga)?;
+        let url = url::Url::parse(&format!("http://{address}/"))
+            .map_err(crate::error::Error::CouldNotParseUrlFragment)?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(crate::error::Error::CouldNotStartFakeIcinga)?;
+        let (events, _) = broadcast::channel(1024);
+        let shared = FakeIcingaShared {
+            state: Arc::new(Mutex::new(FakeIcingaState::default())),
+            events,
+        };
+        let (shutdown, shutdown_receiver) = oneshot::channel();
+        let thread_shared = shared.clone();
+        let thread = std::thread::Builder::new()
+            .name("fake-icinga".to_owned())
+            .spawn(move || runtime.block_on(serve(listener, thread_shared, shutdown_receiver)))
+            .map_err(crate::error::Error::CouldNotStartFakeIcinga)?;
+        Ok(Self {
+            url,
+            shared,
+            shutdown: Some(shutdown),
+            thread: Some(thread),
+        })
+    }
+
+    /// the base URL of the fake server
+    #[must_use]
+    pub const fn url(&self) -> &url::Url {
+        &self.url
+    }
+
+    /// an instance configuration pointing at the fake server, suitable for
+    /// [crate::api::blocking::Icinga2::from_instance_config] and
+    /// [crate::api::async_client::Icinga2Async::from_instance_config]
+    #[must_use]
+    pub fn instance_config(&self) -> Icinga2Instance {
+        Icinga2Instance::new(
+            self.url.to_string(),
+            None,
+            "root".to_owned(),
+            "icinga".to_owned(),
+        )
+    }
+
+    /// insert or replace an object in the store
+    ///
+    /// `name` is the full object name (e.g. `host!service` for services),
+    /// `attrs` is what the API returns in the attrs field of a query result,
+    /// see [fixtures] for suitable starting points
+    pub fn insert_object(
+        &self,
+        object_type: IcingaObjectType,
+        name: &str,
+        attrs: serde_json::Value,
+    ) {
+        self.shared
+            .lock()
+            .objects
+            .entry(object_type)
+            .or_default()
+            .insert(name.to_owned(), attrs);
+    }
+
+    /// insert a host in state UP
+    pub fn add_host(&self, name: &str) {
+        self.insert_object(IcingaObjectType::Host, name, fixtures::host(name));
+    }
+
+    /// insert a service in state OK
+    pub fn add_service(&self, host_name: &str, name: &str) {
+        self.insert_object(
+            IcingaObjectType::Service,
+            &format!("{host_name}!{name}"),
+            fixtures::service(host_name, name),
+        );
+    }
+
+    /// change a single attribute of a stored object
+    ///
+    /// returns false if the object does not exist
+    pub fn set_attribute(
+        &self,
+        object_type: IcingaObjectType,
+        name: &str,
+        attribute: &str,
+        value: serde_json::Value,
+    ) -> bool {
+        self.shared
+            .lock()
+            .objects
+            .get_mut(&object_type)
+            .and_then(|objects| objects.get_mut(name))
+            .and_then(serde_json::Value::as_object_mut)
+            .map(|attrs| attrs.insert(attribute.to_owned(), value))
+            .is_some()
+    }
+
+    /// remove an object from the store, returning its attributes
+    #[expect(
+        clippy::must_use_candidate,
+        reason = "removing an object is useful without looking at the removed attributes"
+    )]
+    pub fn remove_object(
+        &self,
+        object_type: IcingaObjectType,
+        name: &str,
+    ) -> Option<serde_json::Value> {
+        self.shared
+            .lock()
+            .objects
+            .get_mut(&object_type)?
+            .remove(name)
+    }
+
+    /// the attributes of a stored object
+    #[must_use]
+    pub fn object(&self, object_type: IcingaObjectType, name: &str) -> Option<serde_json::Value> {
+        self.shared
+            .lock()
+            .objects
+            .get(&object_type)?
+            .get(name)
+            .cloned()
+    }
+
+    /// all stored objects of the given type by full name
+    #[must_use]
+    pub fn objects(&self, object_type: IcingaObjectType) -> BTreeMap<String, serde_json::Value> {
+        self.shared
+            .lock()
+            .objects
+            .get(&object_type)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// all requests received so far in the order they were received
+    #[must_use]
+    pub fn requests(&self) -> Vec<FakeRequest> {
+        self.shared.lock().requests.clone()
+    }
+
+    /// answer every following request with the given error status, e.g. to
+    /// simulate rejected credentials, or answer requests normally again for
+    /// None
+    pub fn fail_requests_with(&self, status: Option<http::StatusCode>) {
+        self.shared.lock().fail_requests_with = status;
+    }
+
+    /// set the events every new event stream client receives right after connecting
+    ///
+    /// # Errors
+    ///
+    /// this fails if one of the events can not be serialized
+    pub fn script_events(&self, events: &[IcingaEvent]) -> Result<(), crate::error::Error> {
+        let events = events
+            .iter()
+            .map(serialize_event)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.shared.lock().scripted_events = events;
+        Ok(())
+    }
+
+    /// send an event to all currently connected event stream clients
+    ///
+    /// # Errors
+    ///
+    /// this fails if the event can not be serialized
+    pub fn push_event(&self, event: &IcingaEvent) -> Result<(), crate::error::Error> {
+        self.push_raw_event(serialize_event(event)?);
+        Ok(())
+    }
+
+    /// send an arbitrary JSON event to all currently connected event stream
+    /// clients, e.g. to test handling of malformed events
+    pub fn push_raw_event(&self, event: serde_json::Value) {
+        if self
+            .shared
+            .events
+            .send(FakeEventMessage::Event(event))
+            .is_err()
+        {
+            tracing::trace!("fake Icinga event pushed without any connected event stream clients");
+        }
+    }
+
+    /// end all currently open event streams
+    pub fn end_event_streams(&self) {
+        if self.shared.events.send(FakeEventMessage::End).is_err() {
+            tracing::trace!("fake Icinga event streams ended without any connected clients");
+        }
+    }
+}
+
+impl Drop for FakeIcinga {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take()
+            && shutdown.send(()).is_err()
+        {
+            tracing::trace!("fake Icinga server thread already stopped");
+        }
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            tracing::error!("fake Icinga server thread panicked");
+        }
+    }
+}
+
+/// serialize an event the way Icinga sends it
+fn serialize_event(event: &IcingaEvent) -> Result<serde_json::Value, crate::error::Error> {
+    Ok(serde_path_to_error::serialize(
+        event,
+        serde_json::value::Serializer,
+    )?)
+}
+
+/// accept connections until told to shut down
+async fn serve(
+    listener: std::net::TcpListener,
+    shared: FakeIcingaShared,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    let listener = match tokio::net::TcpListener::from_std(listener) {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!("fake Icinga could not listen: {}", e);
+            return;
+        }
+    };
+    loop {
+        let stream = tokio::select! {
+            _ = &mut shutdown => return,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    tracing::error!("fake Icinga could not accept connection: {}", e);
+                    continue;
+                }
+            },
+        };
+        let shared = shared.clone();
+        tokio::spawn(async move {
+            let service =
+                hyper::service::service_fn(move |req| server::handle(shared.clone(), req));
+            if let Err(e) = hyper::server::conn::http1::Builder::new()
+                .serve_connection(hyper_util::rt::TokioIo::new(stream), service)
+                .await
+            {
+                tracing::trace!("fake Icinga connection error: {}", e);
+            }
+        });
+    }
+}
+
+/// the instance the crate's own tests run against
+///
+/// this is the live instance configured in `ICINGA_TEST_INSTANCE_CONFIG`
+/// if that is set (directly or via `.env`) and a seeded [FakeIcinga]
+/// otherwise, which has to be kept alive for the duration of the test
+#[cfg(test)]
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub(crate) fn test_instance()
+-> Result<(Icinga2Instance, Option<FakeIcinga>), Box<dyn std::error::Error>> {
+    if let Err(e) = dotenvy::dotenv() {
+        tracing::trace!("no .env file loaded: {}", e);
+    }
+    if let Ok(path) = std::env::var("ICINGA_TEST_INSTANCE_CONFIG") {
+        let config = Icinga2Instance::from_config_file(std::path::Path::new(&path))?;
+        return Ok((config, None));
+    }
+    let fake = FakeIcinga::start()?;
+    fake.add_host("fake-host-up");
+    fake.add_host("fake-host-down");
+    fake.set_attribute(IcingaObjectType::Host, "fake-host-down", "state", 1.into());
+    fake.add_service("fake-host-up", "fake-service-ok");
+    fake.add_service("fake-host-down", "fake-service-unknown");
+    fake.set_attribute(
+        IcingaObjectType::Service,
+        "fake-host-down!fake-service-unknown",
+        "state",
+        3.into(),
+    );
+    fake.set_attribute(
+        IcingaObjectType::Service,
+        "fake-host-down!fake-service-unknown",
+        "vars",
+        serde_json::json!({ "serviceSeverity": "imminent" }),
+    );
+    fake.insert_object(
+        IcingaObjectType::Downtime,
+        "fake-host-down!fake-downtime",
+        fixtures::downtime(
+            "fake-host-down!fake-downtime",
+            "fake-downtime",
+            "fake-host-down",
+            None,
+            serde_json::json!({ "author": "test", "comment": "seeded downtime" }),
+        ),
+    );
+    fake.insert_object(
+        IcingaObjectType::Comment,
+        "fake-host-down!fake-comment",
+        fixtures::comment(
+            "fake-host-down!fake-comment",
+            "fake-comment",
+            "fake-host-down",
+            None,
+            1,
+            serde_json::json!({ "author": "test", "text": "seeded comment" }),
+        ),
+    );
+    Ok((fake.instance_config(), Some(fake)))
+}
+
+#[cfg(test)]
+#[cfg(feature = "blocking")]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+    use tracing_test::traced_test;
+
+    use crate::api::action::{
+        acknowledge_problem::AcknowledgeProblem, remove_downtime::RemoveDowntime,
+        schedule_downtime::ScheduleDowntime,
+    };
+    use crate::api::blocking::Icinga2;
+    use crate::api::query::monitoring_objects::host::ListHosts;
+    use crate::types::action::StatusResponse;
+    use crate::types::enums::acknowledgement_type::IcingaAcknowledgementType;
+    use crate::types::filter::IcingaFilter;
+    use crate::types::monitoring_objects::host::IcingaHost;
+    use crate::types::query::{QueryResultObject, ResultsWrapper};
+
+    /// a filter for a single host by name
+    fn host_filter(name: &str) -> IcingaFilter {
+        IcingaFilter {
+            object_type: IcingaObjectType::Host,
+            filter: "host.name == name".to_string(),
+            filter_vars: BTreeMap::from([("name".to_string(), serde_json::json!(name))]),
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_fake_actions_mutate_state() -> Result<(), Box<dyn Error>> {
+        let fake = FakeIcinga::start()?;
+        fake.add_host("web01");
+        fake.add_host("web02");
+        fake.add_service("web01", "http");
+        fake.set_attribute(IcingaObjectType::Host, "web01", "state", 1.into());
+        let icinga2 = Icinga2::from_instance_config(&fake.instance_config())?;
+
+        let response: ResultsWrapper<QueryResultObject<IcingaHost>> =
+            icinga2.rest(ListHosts::builder().filter(host_filter("web01")).build()?)?;
+        assert_eq!(response.results.len(), 1, "filter selects a single host");
+
+        let now = time::OffsetDateTime::now_utc();
+        let _response: ResultsWrapper<StatusResponse> = icinga2.rest(
+            ScheduleDowntime::builder()
+                .author("test".to_string())
+                .comment("maintenance".to_string())
+                .start_time(now)
+                .end_time(now.saturating_add(time::Duration::hours(1)))
+                .fixed(Some(true))
+                .duration(None)
+                .all_services(Some(true))
+                .trigger_name(None)
+                .child_options(None)
+                .filter(host_filter("web01"))
+                .build()?,
+        )?;
+        assert_eq!(
+            fake.objects(IcingaObjectType::Downtime).len(),
+            2,
+            "downtimes for the host and its service"
+        );
+        let host: IcingaHost = serde_json::from_value(
+            fake.object(IcingaObjectType::Host, "web01")
+                .ok_or("host missing")?,
+        )?;
+        assert_eq!(host.checkable.downtime_depth, 1, "downtime depth increased");
+
+        let _response: ResultsWrapper<StatusResponse> = icinga2.rest(
+            AcknowledgeProblem::builder()
+                .author("test".to_string())
+                .comment("on it".to_string())
+                .expiry(None)
+                .sticky(None)
+                .notify(None)
+                .persistent(None)
+                .filter(host_filter("web01"))
+                .build()?,
+        )?;
+        let host: IcingaHost = serde_json::from_value(
+            fake.object(IcingaObjectType::Host, "web01")
+                .ok_or("host missing")?,
+        )?;
+        assert_eq!(
+            host.checkable.acknowledgement,
+            IcingaAcknowledgementType::Normal,
+            "problem acknowledged"
+        );
+        assert_eq!(
+            fake.objects(IcingaObjectType::Comment).len(),
+            1,
+            "acknowledgement comment added"
+        );
+
+        let _response: ResultsWrapper<StatusResponse> = icinga2.rest(
+            RemoveDowntime::builder()
+                .author("test".to_string())
+                .filter(host_filter("web01"))
+                .build()?,
+        )?;
+        assert_eq!(
+            fake.objects(IcingaObjectType::Downtime).len(),
+            1,
+            "host downtime removed, service downtime kept"
+        );
+        Ok(())
+    }
+}
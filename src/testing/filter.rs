@@ -0,0 +1,549 @@
+//! A small evaluator for the subset of the Icinga filter language supported
+//! by the fake server
+//!
+//! Supported are literals (strings, numbers, booleans, null and arrays),
+//! attribute paths like `host.vars.os`, variables from `filter_vars`, the
+//! usual state constants (e.g. `ServiceCritical`), the operators
+//! `! && || == != < <= > >= in`, parentheses and the functions `match`
+//! (glob matching) and `len`.
+//!
+//! [Official Documentation](https://icinga.com/docs/icinga-2/latest/doc/12-icinga2-api/#filters)
+
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+/// error returned when a filter expression can not be parsed
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid filter expression: {0}")]
+pub struct FilterParseError(String);
+
+/// a single token of a filter expression
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// an identifier or attribute path
+    Ident(String),
+    /// a string literal
+    Str(String),
+    /// a number literal
+    Num(f64),
+    /// (
+    LParen,
+    /// )
+    RParen,
+    /// [
+    LBracket,
+    /// ]
+    RBracket,
+    /// ,
+    Comma,
+    /// !
+    Not,
+    /// &&
+    And,
+    /// ||
+    Or,
+    /// a comparison operator
+    Cmp(CmpOp),
+    /// the in operator
+    In,
+}
+
+/// comparison operators
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CmpOp {
+    /// ==
+    Eq,
+    /// !=
+    Ne,
+    /// <
+    Lt,
+    /// <=
+    Le,
+    /// >
+    Gt,
+    /// >=
+    Ge,
+}
+
+/// a parsed filter expression
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Expr {
+    /// a literal value
+    Literal(serde_json::Value),
+    /// an array literal
+    Array(Vec<Self>),
+    /// a variable or attribute path
+    Path(Vec<String>),
+    /// logical negation
+    Not(Box<Self>),
+    /// logical and
+    And(Box<Self>, Box<Self>),
+    /// logical or
+    Or(Box<Self>, Box<Self>),
+    /// a comparison
+    Cmp(CmpOp, Box<Self>, Box<Self>),
+    /// membership test in an array
+    In(Box<Self>, Box<Self>),
+    /// a function call
+    Call(String, Vec<Self>),
+}
+
+/// split a filter expression into tokens
+fn tokenize(input: &str) -> Result<Vec<Token>, FilterParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '(' => tokens.push(Token::LParen),
+            ')' => tokens.push(Token::RParen),
+            '[' => tokens.push(Token::LBracket),
+            ']' => tokens.push(Token::RBracket),
+            ',' => tokens.push(Token::Comma),
+            '!' => {
+                if chars.next_if_eq(&'=').is_some() {
+                    tokens.push(Token::Cmp(CmpOp::Ne));
+                } else {
+                    tokens.push(Token::Not);
+                }
+            }
+            '=' => {
+                if chars.next_if_eq(&'=').is_none() {
+                    return Err(FilterParseError("assignment is not allowed".to_string()));
+                }
+                tokens.push(Token::Cmp(CmpOp::Eq));
+            }
+            '<' => {
+                if chars.next_if_eq(&'=').is_some() {
+                    tokens.push(Token::Cmp(CmpOp::Le));
+                } else {
+                    tokens.push(Token::Cmp(CmpOp::Lt));
+                }
+            }
+            '>' => {
+                if chars.next_if_eq(&'=').is_some() {
+                    tokens.push(Token::Cmp(CmpOp::Ge));
+                } else {
+                    tokens.push(Token::Cmp(CmpOp::Gt));
+                }
+            }
+            '&' => {
+                if chars.next_if_eq(&'&').is_none() {
+                    return Err(FilterParseError("expected &&".to_string()));
+                }
+                tokens.push(Token::And);
+            }
+            '|' => {
+                if chars.next_if_eq(&'|').is_none() {
+                    return Err(FilterParseError("expected ||".to_string()));
+                }
+                tokens.push(Token::Or);
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(e) => s.push(e),
+                            None => {
+                                return Err(FilterParseError(
+                                    "unterminated string literal".to_string(),
+                                ));
+                            }
+                        },
+                        Some(e) if e == quote => break,
+                        Some(e) => s.push(e),
+                        None => {
+                            return Err(FilterParseError(
+                                "unterminated string literal".to_string(),
+                            ));
+                        }
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut s = String::from(c);
+                while let Some(d) = chars.next_if(|d| d.is_ascii_digit() || *d == '.') {
+                    s.push(d);
+                }
+                let n = s
+                    .parse()
+                    .map_err(|_err| FilterParseError(format!("invalid number {s}")))?;
+                tokens.push(Token::Num(n));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut s = String::from(c);
+                while let Some(d) = chars.next_if(|d| d.is_alphanumeric() || *d == '_' || *d == '.')
+                {
+                    s.push(d);
+                }
+                if s == "in" {
+                    tokens.push(Token::In);
+                } else {
+                    tokens.push(Token::Ident(s));
+                }
+            }
+            c => {
+                return Err(FilterParseError(format!("unexpected character {c}")));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// recursive descent parser over the token list
+struct Parser {
+    /// the remaining tokens
+    tokens: std::iter::Peekable<std::vec::IntoIter<Token>>,
+}
+
+impl Parser {
+    /// consume the next token if it is equal to the expected one
+    fn eat(&mut self, expected: &Token) -> bool {
+        self.tokens.next_if_eq(expected).is_some()
+    }
+
+    /// consume the next token and fail if it is not the expected one
+    fn expect(&mut self, expected: &Token) -> Result<(), FilterParseError> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(FilterParseError(format!(
+                "expected {expected:?} but found {:?}",
+                self.tokens.peek()
+            )))
+        }
+    }
+
+    /// `or := and ('||' and)*`
+    fn parse_or(&mut self) -> Result<Expr, FilterParseError> {
+        let mut lhs = self.parse_and()?;
+        while self.eat(&Token::Or) {
+            let rhs = self.parse_and()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    /// `and := not ('&&' not)*`
+    fn parse_and(&mut self) -> Result<Expr, FilterParseError> {
+        let mut lhs = self.parse_not()?;
+        while self.eat(&Token::And) {
+            let rhs = self.parse_not()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    /// `not := '!' not | comparison`
+    fn parse_not(&mut self) -> Result<Expr, FilterParseError> {
+        if self.eat(&Token::Not) {
+            Ok(Expr::Not(Box::new(self.parse_not()?)))
+        } else {
+            self.parse_comparison()
+        }
+    }
+
+    /// `comparison := primary (op primary)?`
+    fn parse_comparison(&mut self) -> Result<Expr, FilterParseError> {
+        let lhs = self.parse_primary()?;
+        match self.tokens.peek() {
+            Some(Token::Cmp(op)) => {
+                let op = *op;
+                self.tokens.next();
+                let rhs = self.parse_primary()?;
+                Ok(Expr::Cmp(op, Box::new(lhs), Box::new(rhs)))
+            }
+            Some(Token::In) => {
+                self.tokens.next();
+                let rhs = self.parse_primary()?;
+                Ok(Expr::In(Box::new(lhs), Box::new(rhs)))
+            }
+            _ => Ok(lhs),
+        }
+    }
+
+    /// a literal, path, function call, array or parenthesized expression
+    fn parse_primary(&mut self) -> Result<Expr, FilterParseError> {
+        match self.tokens.next() {
+            Some(Token::LParen) => {
+                let e = self.parse_or()?;
+                self.expect(&Token::RParen)?;
+                Ok(e)
+            }
+            Some(Token::LBracket) => {
+                let mut elements = Vec::new();
+                if !self.eat(&Token::RBracket) {
+                    loop {
+                        elements.push(self.parse_or()?);
+                        if self.eat(&Token::RBracket) {
+                            break;
+                        }
+                        self.expect(&Token::Comma)?;
+                    }
+                }
+                Ok(Expr::Array(elements))
+            }
+            Some(Token::Str(s)) => Ok(Expr::Literal(serde_json::Value::String(s))),
+            Some(Token::Num(n)) => Ok(Expr::Literal(serde_json::json!(n))),
+            Some(Token::Ident(s)) => match s.as_str() {
+                "true" => Ok(Expr::Literal(serde_json::Value::Bool(true))),
+                "false" => Ok(Expr::Literal(serde_json::Value::Bool(false))),
+                "null" => Ok(Expr::Literal(serde_json::Value::Null)),
+                _ if self.eat(&Token::LParen) => {
+                    let mut args = Vec::new();
+                    if !self.eat(&Token::RParen) {
+                        loop {
+                            args.push(self.parse_or()?);
+                            if self.eat(&Token::RParen) {
+                                break;
+                            }
+                            self.expect(&Token::Comma)?;
+                        }
+                    }
+                    Ok(Expr::Call(s, args))
+                }
+                _ => Ok(Expr::Path(s.split('.').map(ToOwned::to_owned).collect())),
+            },
+            t => Err(FilterParseError(format!("unexpected token {t:?}"))),
+        }
+    }
+}
+
+/// parse a filter expression
+///
+/// # Errors
+///
+/// returns an error if the expression uses syntax outside of the supported subset
+pub(crate) fn parse(input: &str) -> Result<Expr, FilterParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?.into_iter().peekable(),
+    };
+    let expr = parser.parse_or()?;
+    if let Some(t) = parser.tokens.next() {
+        return Err(FilterParseError(format!("unexpected trailing token {t:?}")));
+    }
+    Ok(expr)
+}
+
+/// the values a filter expression is evaluated against
+#[derive(Debug)]
+pub(crate) struct FilterContext<'a> {
+    /// the variables passed in as filter_vars
+    pub(crate) vars: &'a BTreeMap<String, serde_json::Value>,
+    /// the objects available under a name, e.g. host or service
+    pub(crate) bindings: BTreeMap<String, &'a serde_json::Value>,
+}
+
+/// numeric values of the global constants Icinga defines for states
+fn constant(name: &str) -> Option<serde_json::Value> {
+    let v = match name {
+        "HostUp" | "ServiceOK" | "AcknowledgementNone" => 0,
+        "HostDown" | "ServiceWarning" | "AcknowledgementNormal" => 1,
+        "HostUnreachable" | "ServiceCritical" | "AcknowledgementSticky" => 2,
+        "ServiceUnknown" => 3,
+        _ => return None,
+    };
+    Some(serde_json::json!(v))
+}
+
+/// Icinga's notion of truthiness
+fn truthy(v: &serde_json::Value) -> bool {
+    match v {
+        serde_json::Value::Null => false,
+        serde_json::Value::Bool(b) => *b,
+        serde_json::Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        serde_json::Value::String(s) => !s.is_empty(),
+        serde_json::Value::Array(a) => !a.is_empty(),
+        serde_json::Value::Object(o) => !o.is_empty(),
+    }
+}
+
+/// compare two values, numbers are compared numerically regardless of representation
+fn compare(a: &serde_json::Value, b: &serde_json::Value) -> Option<std::cmp::Ordering> {
+    match (a, b) {
+        (serde_json::Value::Number(a), serde_json::Value::Number(b)) => {
+            a.as_f64()?.partial_cmp(&b.as_f64()?)
+        }
+        (serde_json::Value::String(a), serde_json::Value::String(b)) => Some(a.cmp(b)),
+        (serde_json::Value::Bool(a), serde_json::Value::Bool(b)) => Some(a.cmp(b)),
+        (serde_json::Value::Null, serde_json::Value::Null) => Some(std::cmp::Ordering::Equal),
+        (a, b) if a == b => Some(std::cmp::Ordering::Equal),
+        _ => None,
+    }
+}
+
+/// glob style matching with `*` and `?` wildcards as used by Icinga's match()
+pub(crate) fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match (pattern.split_first(), text.split_first()) {
+        (None, None) => true,
+        (Some(('*', rest)), _) => {
+            glob_match(rest, text)
+                || text
+                    .split_first()
+                    .is_some_and(|(_, text_rest)| glob_match(pattern, text_rest))
+        }
+        (Some(('?', rest)), Some((_, text_rest))) => glob_match(rest, text_rest),
+        (Some((p, rest)), Some((t, text_rest))) => p == t && glob_match(rest, text_rest),
+        _ => false,
+    }
+}
+
+impl FilterContext<'_> {
+    /// look up a path in the bindings, the filter variables or the constants
+    fn resolve(&self, path: &[String]) -> serde_json::Value {
+        let Some((first, rest)) = path.split_first() else {
+            return serde_json::Value::Null;
+        };
+        let root = if let Some(v) = self.bindings.get(first) {
+            (*v).to_owned()
+        } else if let Some(v) = self.vars.get(first) {
+            v.to_owned()
+        } else if let Some(v) = constant(first) {
+            v
+        } else {
+            return serde_json::Value::Null;
+        };
+        let mut current = &root;
+        for segment in rest {
+            match current.get(segment) {
+                Some(v) => current = v,
+                None => return serde_json::Value::Null,
+            }
+        }
+        current.to_owned()
+    }
+
+    /// evaluate an expression to a value
+    pub(crate) fn eval(&self, expr: &Expr) -> serde_json::Value {
+        match expr {
+            Expr::Literal(v) => v.to_owned(),
+            Expr::Array(elements) => {
+                serde_json::Value::Array(elements.iter().map(|e| self.eval(e)).collect())
+            }
+            Expr::Path(path) => self.resolve(path),
+            Expr::Not(e) => serde_json::Value::Bool(!truthy(&self.eval(e))),
+            Expr::And(a, b) => {
+                serde_json::Value::Bool(truthy(&self.eval(a)) && truthy(&self.eval(b)))
+            }
+            Expr::Or(a, b) => {
+                serde_json::Value::Bool(truthy(&self.eval(a)) || truthy(&self.eval(b)))
+            }
+            Expr::Cmp(op, a, b) => {
+                let ordering = compare(&self.eval(a), &self.eval(b));
+                let result = match op {
+                    CmpOp::Eq => ordering == Some(std::cmp::Ordering::Equal),
+                    CmpOp::Ne => ordering != Some(std::cmp::Ordering::Equal),
+                    CmpOp::Lt => ordering == Some(std::cmp::Ordering::Less),
+                    CmpOp::Le => matches!(
+                        ordering,
+                        Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal)
+                    ),
+                    CmpOp::Gt => ordering == Some(std::cmp::Ordering::Greater),
+                    CmpOp::Ge => matches!(
+                        ordering,
+                        Some(std::cmp::Ordering::Greater | std::cmp::Ordering::Equal)
+                    ),
+                };
+                serde_json::Value::Bool(result)
+            }
+            Expr::In(needle, haystack) => {
+                let needle = self.eval(needle);
+                let result = match self.eval(haystack) {
+                    serde_json::Value::Array(a) => a
+                        .iter()
+                        .any(|v| compare(v, &needle) == Some(std::cmp::Ordering::Equal)),
+                    serde_json::Value::Object(o) => {
+                        needle.as_str().is_some_and(|k| o.contains_key(k))
+                    }
+                    _ => false,
+                };
+                serde_json::Value::Bool(result)
+            }
+            Expr::Call(name, args) => {
+                let args: Vec<serde_json::Value> = args.iter().map(|a| self.eval(a)).collect();
+                match (name.as_str(), args.as_slice()) {
+                    ("match", [pattern, value]) => {
+                        let (Some(pattern), Some(value)) = (pattern.as_str(), value.as_str())
+                        else {
+                            return serde_json::Value::Bool(false);
+                        };
+                        let pattern: Vec<char> = pattern.chars().collect();
+                        let value: Vec<char> = value.chars().collect();
+                        serde_json::Value::Bool(glob_match(&pattern, &value))
+                    }
+                    ("len", [serde_json::Value::Array(a)]) => serde_json::json!(a.len()),
+                    ("len", [serde_json::Value::String(s)]) => {
+                        serde_json::json!(s.chars().count())
+                    }
+                    ("len", [serde_json::Value::Object(o)]) => serde_json::json!(o.len()),
+                    _ => serde_json::Value::Null,
+                }
+            }
+        }
+    }
+
+    /// evaluate an expression and interpret the result as a boolean
+    pub(crate) fn matches(&self, expr: &Expr) -> bool {
+        truthy(&self.eval(expr))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// evaluate a filter against a single host object
+    fn eval_host(filter: &str, host: &serde_json::Value) -> Result<bool, FilterParseError> {
+        let vars = BTreeMap::from([("os".to_string(), serde_json::json!("Linux"))]);
+        let ctx = FilterContext {
+            vars: &vars,
+            bindings: BTreeMap::from([("host".to_string(), host)]),
+        };
+        Ok(ctx.matches(&parse(filter)?))
+    }
+
+    #[test]
+    fn test_filter_evaluation() -> Result<(), FilterParseError> {
+        let host = serde_json::json!({
+            "name": "web01",
+            "state": 1,
+            "groups": ["linux", "web"],
+            "vars": { "os": "Linux" },
+        });
+        assert!(eval_host("host.name == \"web01\"", &host)?, "equality");
+        assert!(eval_host("host.state == HostDown", &host)?, "constants");
+        assert!(eval_host("host.vars.os == os", &host)?, "filter_vars");
+        assert!(eval_host("\"web\" in host.groups", &host)?, "in");
+        assert!(eval_host("match(\"web*\", host.name)", &host)?, "match");
+        assert!(
+            eval_host("!(host.state < 1) && host.state >= 1", &host)?,
+            "comparison and negation"
+        );
+        assert!(
+            eval_host("host.vars.missing || host.name != 'db01'", &host)?,
+            "missing attributes are null"
+        );
+        assert!(
+            !eval_host("host.name in [\"db01\", \"db02\"]", &host)?,
+            "arrays"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_parse_errors() {
+        assert_eq!(
+            parse("host.name = \"x\""),
+            Err(FilterParseError("assignment is not allowed".to_string()))
+        );
+        assert!(parse("(host.name").is_err(), "unbalanced parentheses");
+        assert!(parse("host.name == \"x").is_err(), "unterminated string");
+    }
+}
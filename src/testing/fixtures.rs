@@ -0,0 +1,320 @@
+//! Attribute sets for objects stored in the fake server
+//!
+//! These produce JSON in the shape the Icinga API returns in the `attrs`
+//! field of query results so that they deserialize into the types in
+//! [crate::types]. Callers can modify the returned values before inserting
+//! them to model specific situations.
+//!
+//! [service_check_result] builds a passive check result to submit to it.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    api::action::process_check_result::{ProcessCheckResult, ProcessCheckResultExitStatus},
+    types::{enums::object_type::IcingaObjectType, filter::IcingaFilter},
+};
+
+/// the current time as a unix timestamp the way Icinga returns it
+#[must_use]
+pub fn now() -> serde_json::Value {
+    serde_json::json!(time::OffsetDateTime::now_utc().unix_timestamp())
+}
+
+/// the fields shared by all config objects
+fn config_object(full_name: &str, name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "__name": full_name,
+        "name": name,
+        "active": true,
+        "ha_mode": 0,
+        "original_attributes": null,
+        "package": "_etc",
+        "paused": false,
+        "source_location": {
+            "path": "/etc/icinga2/conf.d/fake.conf",
+            "first_line": 1,
+            "first_column": 1,
+            "last_line": 1,
+            "last_column": 1,
+        },
+        "templates": [name],
+        "version": 0,
+        "zone": "",
+        "vars": {},
+    })
+}
+
+/// merge the fields of `extra` into `base`
+fn merge(mut base: serde_json::Value, extra: serde_json::Value) -> serde_json::Value {
+    if let (Some(base_map), serde_json::Value::Object(extra_map)) = (base.as_object_mut(), extra) {
+        base_map.extend(extra_map);
+    }
+    base
+}
+
+/// a check result with the given state and output
+#[must_use]
+pub fn check_result(state: u8, output: &str) -> serde_json::Value {
+    let now = now();
+    serde_json::json!({
+        "active": true,
+        "check_source": "fake-icinga",
+        "command": null,
+        "execution_start": now,
+        "execution_end": now,
+        "exit_status": state,
+        "output": output,
+        "performance_data": [],
+        "previous_hard_state": 0,
+        "schedule_start": now,
+        "schedule_end": now,
+        "scheduling_source": "fake-icinga",
+        "state": state,
+        "ttl": 0,
+        "type": "CheckResult",
+        "vars_before": null,
+        "vars_after": null,
+    })
+}
+
+/// the fields shared by hosts and services
+fn checkable(full_name: &str, name: &str, check_command: &str) -> serde_json::Value {
+    let now = now();
+    let attrs = merge(
+        config_object(full_name, name),
+        serde_json::json!({
+            "acknowledgement": 0,
+            "acknowledgement_expiry": 0,
+            "acknowledgement_last_change": 0,
+            "action_url": "",
+            "check_attempt": 1,
+            "check_command": check_command,
+            "check_interval": 300,
+            "check_period": "",
+            "check_timeout": null,
+            "command_endpoint": "",
+            "downtime_depth": 0,
+            "enable_active_checks": true,
+            "enable_event_handler": true,
+            "enable_flapping": false,
+            "enable_notifications": true,
+            "enable_passive_checks": true,
+            "enable_perfdata": true,
+            "event_command": "",
+            "executions": null,
+            "flapping": false,
+            "flapping_current": 0.0,
+            "flapping_ignore_states": null,
+            "flapping_last_change": 0,
+        }),
+    );
+    merge(
+        attrs,
+        serde_json::json!({
+            "flapping_threshold": 0.0,
+            "flapping_threshold_low": 25.0,
+            "flapping_threshold_high": 30.0,
+            "force_next_check": false,
+            "force_next_notification": false,
+            "handled": false,
+            "icon_image": "",
+            "icon_image_alt": "",
+            "last_check": now,
+            "last_check_result": check_result(0, "OK - fake check result"),
+            "last_hard_state_change": now,
+            "last_reachable": true,
+            "last_state_change": now,
+            "last_state_type": 1,
+            "last_state_unreachable": 0,
+            "max_check_attempts": 3,
+            "next_check": now,
+            "next_update": now,
+            "notes": "",
+            "notes_url": "",
+            "previous_state_change": now,
+            "problem": false,
+            "retry_interval": 60,
+            "severity": 0,
+            "state_type": 1,
+            "volatile": false,
+        }),
+    )
+}
+
+/// the attributes of a host in state UP
+#[must_use]
+pub fn host(name: &str) -> serde_json::Value {
+    merge(
+        checkable(name, name, "hostalive"),
+        serde_json::json!({
+            "type": "Host",
+            "address": "127.0.0.1",
+            "address6": "",
+            "display_name": name,
+            "groups": [],
+            "last_hard_state": 0,
+            "last_state": 0,
+            "last_state_down": 0,
+            "last_state_up": now(),
+            "state": 0,
+        }),
+    )
+}
+
+/// the attributes of a service in state OK
+#[must_use]
+pub fn service(host_name: &str, name: &str) -> serde_json::Value {
+    merge(
+        checkable(&format!("{host_name}!{name}"), name, "dummy"),
+        serde_json::json!({
+            "type": "Service",
+            "display_name": name,
+            "groups": [],
+            "host_name": host_name,
+            "last_hard_state": 0,
+            "last_state": 0,
+            "last_state_critical": 0,
+            "last_state_ok": now(),
+            "last_state_unknown": 0,
+            "last_state_warning": 0,
+            "state": 0,
+        }),
+    )
+}
+
+/// the attributes of a downtime, host downtimes have an empty service name
+/// like in Icinga
+///
+/// `extra` should contain the fields passed to the schedule-downtime action
+/// (author, comment, start_time, end_time, fixed, duration,...)
+#[must_use]
+pub fn downtime(
+    full_name: &str,
+    name: &str,
+    host_name: &str,
+    service_name: Option<&str>,
+    extra: serde_json::Value,
+) -> serde_json::Value {
+    let attrs = merge(
+        config_object(full_name, name),
+        serde_json::json!({
+            "type": "Downtime",
+            "host_name": host_name,
+            "service_name": service_name.unwrap_or_default(),
+            "entry_time": now(),
+            "author": "",
+            "comment": "",
+            "start_time": 0,
+            "end_time": 0,
+            "trigger_time": 0,
+            "fixed": true,
+            "duration": 0,
+            "triggered_by": null,
+            "scheduled_by": null,
+            "parent": null,
+            "triggers": [],
+            "legacy_id": 0,
+            "remove_time": 0,
+            "was_canceled": false,
+            "config_owner": null,
+            "config_owner_hash": null,
+            "authoritative_zone": null,
+        }),
+    );
+    merge(attrs, extra)
+}
+
+/// the attributes of a comment, host comments have an empty service name
+/// like in Icinga
+///
+/// `entry_type` is 1 for user comments and 4 for acknowledgement comments
+#[must_use]
+pub fn comment(
+    full_name: &str,
+    name: &str,
+    host_name: &str,
+    service_name: Option<&str>,
+    entry_type: u8,
+    extra: serde_json::Value,
+) -> serde_json::Value {
+    let attrs = merge(
+        config_object(full_name, name),
+        serde_json::json!({
+            "type": "Comment",
+            "host_name": host_name,
+            "service_name": service_name.unwrap_or_default(),
+            "entry_time": now(),
+            "entry_type": entry_type,
+            "author": "",
+            "text": "",
+            "persistent": false,
+            "expire_time": 0,
+            "legacy_id": 0,
+        }),
+    );
+    merge(attrs, extra)
+}
+
+/// the attributes of a fixed scheduled downtime, scheduled downtimes of
+/// hosts have an empty service name like in Icinga
+///
+/// `ranges` maps days to times of day in the syntax of time periods
+#[must_use]
+pub fn scheduled_downtime(
+    host_name: &str,
+    service_name: Option<&str>,
+    name: &str,
+    ranges: serde_json::Value,
+    comment: &str,
+) -> serde_json::Value {
+    let full_name = match service_name {
+        Some(service_name) => format!("{host_name}!{service_name}!{name}"),
+        None => format!("{host_name}!{name}"),
+    };
+    merge(
+        config_object(&full_name, name),
+        serde_json::json!({
+            "type": "ScheduledDowntime",
+            "host_name": host_name,
+            "service_name": service_name.unwrap_or_default(),
+            "author": "",
+            "comment": comment,
+            "duration": 0,
+            "fixed": true,
+            "child_options": "DowntimeNoChildren",
+            "ranges": ranges,
+        }),
+    )
+}
+
+/// a passive check result for a service
+///
+/// if `execution_end` is set the check is recorded as having started one
+/// second before it
+///
+/// # Errors
+///
+/// this fails if the check result can not be built
+pub fn service_check_result(
+    host_name: &str,
+    service_name: &str,
+    exit_status: ProcessCheckResultExitStatus,
+    output: &str,
+    execution_end: Option<time::OffsetDateTime>,
+) -> Result<ProcessCheckResult, crate::error::Error> {
+    ProcessCheckResult::builder()
+        .exit_status(exit_status)
+        .plugin_output(output.to_owned())
+        .execution_start(execution_end.map(|end| end.saturating_sub(time::Duration::SECOND)))
+        .execution_end(execution_end)
+        .ttl(None)
+        .filter(IcingaFilter {
+            object_type: IcingaObjectType::Service,
+            filter: "host.name == host_name && service.name == service_name".to_owned(),
+            filter_vars: BTreeMap::from([
+                ("host_name".to_owned(), serde_json::json!(host_name)),
+                ("service_name".to_owned(), serde_json::json!(service_name)),
+            ]),
+        })
+        .build()
+}
@@ -0,0 +1,1179 @@
+//! HTTP request handling for the fake Icinga API server
+
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use bytes::Bytes;
+use futures::StreamExt as _;
+use http_body_util::{BodyExt as _, Full, StreamBody, combinators::UnsyncBoxBody};
+use hyper::body::Frame;
+use hyper::{Method, Request, Response};
+use tokio::sync::broadcast;
+
+use crate::types::enums::object_type::IcingaObjectType;
+
+use super::filter::{Expr, FilterContext};
+use super::fixtures;
+
+/// the body type of all responses produced by the fake server
+pub(crate) type FakeBody = UnsyncBoxBody<Bytes, Infallible>;
+
+/// messages sent to all connected event stream clients
+#[derive(Debug, Clone)]
+pub(crate) enum FakeEventMessage {
+    /// an event to deliver, already serialized
+    Event(serde_json::Value),
+    /// close all event streams
+    End,
+}
+
+/// the mutable state of the fake server
+#[derive(Debug, Default)]
+pub(crate) struct FakeIcingaState {
+    /// the object attributes by type and full object name
+    pub(crate) objects: BTreeMap<IcingaObjectType, BTreeMap<String, serde_json::Value>>,
+    /// events delivered to every event stream client right after connecting
+    pub(crate) scripted_events: Vec<serde_json::Value>,
+    /// counter used to generate unique names for runtime objects
+    pub(crate) counter: u64,
+    /// all requests received so far
+    pub(crate) requests: Vec<super::FakeRequest>,
+    /// if set every request is answered with this status
+    pub(crate) fail_requests_with: Option<http::StatusCode>,
+}
+
+/// shared handles passed to every connection
+#[derive(Debug, Clone)]
+pub(crate) struct FakeIcingaShared {
+    /// the object store
+    pub(crate) state: Arc<Mutex<FakeIcingaState>>,
+    /// the channel used to push events to event stream clients
+    pub(crate) events: broadcast::Sender<FakeEventMessage>,
+}
+
+impl FakeIcingaShared {
+    /// lock the state, ignoring poisoning since a panicking handler can not
+    /// leave the plain data in the store in an inconsistent state
+    pub(crate) fn lock(&self) -> std::sync::MutexGuard<'_, FakeIcingaState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// the type name Icinga uses in URLs for an object type
+pub(crate) fn url_type_name(object_type: IcingaObjectType) -> String {
+    match object_type {
+        IcingaObjectType::Dependency => "dependencies".to_string(),
+        t => format!("{}s", t.to_string().to_lowercase()),
+    }
+}
+
+/// the object type for a type name as used in URLs
+fn object_type_from_url(name: &str) -> Option<IcingaObjectType> {
+    [
+        IcingaObjectType::Host,
+        IcingaObjectType::Service,
+        IcingaObjectType::Comment,
+        IcingaObjectType::Dependency,
+        IcingaObjectType::Notification,
+        IcingaObjectType::CheckCommand,
+        IcingaObjectType::NotificationCommand,
+        IcingaObjectType::EventCommand,
+        IcingaObjectType::HostGroup,
+        IcingaObjectType::ServiceGroup,
+        IcingaObjectType::UserGroup,
+        IcingaObjectType::Endpoint,
+        IcingaObjectType::TimePeriod,
+        IcingaObjectType::Zone,
+        IcingaObjectType::User,
+        IcingaObjectType::ScheduledDowntime,
+        IcingaObjectType::Downtime,
+        IcingaObjectType::ApiUser,
+    ]
+    .into_iter()
+    .find(|t| url_type_name(*t) == name)
+}
+
+/// joins supported by the fake server
+///
+/// source type, join name, attributes combined with ! to form the target name, target type
+const JOINS: &[(IcingaObjectType, &str, &[&str], IcingaObjectType)] = &[
+    (
+        IcingaObjectType::Host,
+        "check_command",
+        &["check_command"],
+        IcingaObjectType::CheckCommand,
+    ),
+    (
+        IcingaObjectType::Host,
+        "check_period",
+        &["check_period"],
+        IcingaObjectType::TimePeriod,
+    ),
+    (
+        IcingaObjectType::Host,
+        "event_command",
+        &["event_command"],
+        IcingaObjectType::EventCommand,
+    ),
+    (
+        IcingaObjectType::Host,
+        "command_endpoint",
+        &["command_endpoint"],
+        IcingaObjectType::Endpoint,
+    ),
+    (
+        IcingaObjectType::Service,
+        "host",
+        &["host_name"],
+        IcingaObjectType::Host,
+    ),
+    (
+        IcingaObjectType::Service,
+        "check_command",
+        &["check_command"],
+        IcingaObjectType::CheckCommand,
+    ),
+    (
+        IcingaObjectType::Service,
+        "check_period",
+        &["check_period"],
+        IcingaObjectType::TimePeriod,
+    ),
+    (
+        IcingaObjectType::Service,
+        "event_command",
+        &["event_command"],
+        IcingaObjectType::EventCommand,
+    ),
+    (
+        IcingaObjectType::Service,
+        "command_endpoint",
+        &["command_endpoint"],
+        IcingaObjectType::Endpoint,
+    ),
+    (
+        IcingaObjectType::Notification,
+        "host",
+        &["host_name"],
+        IcingaObjectType::Host,
+    ),
+    (
+        IcingaObjectType::Notification,
+        "service",
+        &["host_name", "service_name"],
+        IcingaObjectType::Service,
+    ),
+    (
+        IcingaObjectType::Notification,
+        "command",
+        &["command"],
+        IcingaObjectType::NotificationCommand,
+    ),
+    (
+        IcingaObjectType::Notification,
+        "period",
+        &["period"],
+        IcingaObjectType::TimePeriod,
+    ),
+    (
+        IcingaObjectType::Dependency,
+        "child_host",
+        &["child_host_name"],
+        IcingaObjectType::Host,
+    ),
+    (
+        IcingaObjectType::Dependency,
+        "child_service",
+        &["child_host_name", "child_service_name"],
+        IcingaObjectType::Service,
+    ),
+    (
+        IcingaObjectType::Dependency,
+        "parent_host",
+        &["parent_host_name"],
+        IcingaObjectType::Host,
+    ),
+    (
+        IcingaObjectType::Dependency,
+        "parent_service",
+        &["parent_host_name", "parent_service_name"],
+        IcingaObjectType::Service,
+    ),
+    (
+        IcingaObjectType::Dependency,
+        "period",
+        &["period"],
+        IcingaObjectType::TimePeriod,
+    ),
+    (
+        IcingaObjectType::User,
+        "period",
+        &["period"],
+        IcingaObjectType::TimePeriod,
+    ),
+    (
+        IcingaObjectType::Zone,
+        "parent",
+        &["parent"],
+        IcingaObjectType::Zone,
+    ),
+];
+
+/// the objects joined to the given object, by join name
+fn joined_objects<'a>(
+    state: &'a FakeIcingaState,
+    object_type: IcingaObjectType,
+    attrs: &serde_json::Value,
+) -> BTreeMap<String, &'a serde_json::Value> {
+    let mut result = BTreeMap::new();
+    for (source_type, join_name, name_attrs, target_type) in JOINS {
+        if *source_type != object_type {
+            continue;
+        }
+        let name_parts: Option<Vec<&str>> = name_attrs
+            .iter()
+            .map(|a| attrs.get(a).and_then(serde_json::Value::as_str))
+            .collect();
+        let Some(name_parts) = name_parts else {
+            continue;
+        };
+        let target_name = name_parts.join("!");
+        if let Some(target) = state
+            .objects
+            .get(target_type)
+            .and_then(|objects| objects.get(&target_name))
+        {
+            result.insert((*join_name).to_string(), target);
+        }
+    }
+    result
+}
+
+/// the variable name an object is bound to in filters, e.g. host
+fn filter_binding_name(object_type: IcingaObjectType) -> String {
+    object_type.to_string().to_lowercase()
+}
+
+/// a parsed filter along with its variables
+#[derive(Debug)]
+struct ParsedFilter {
+    /// the parsed expression, None matches everything
+    expr: Option<Expr>,
+    /// the filter_vars
+    vars: BTreeMap<String, serde_json::Value>,
+}
+
+/// extract the filter from a request body
+fn parse_filter(body: &serde_json::Value) -> Result<ParsedFilter, String> {
+    let expr = match body.get("filter").and_then(serde_json::Value::as_str) {
+        Some(filter) if !filter.trim().is_empty() => {
+            Some(super::filter::parse(filter).map_err(|e| e.to_string())?)
+        }
+        _ => None,
+    };
+    let vars = match body.get("filter_vars") {
+        Some(serde_json::Value::Object(vars)) => vars
+            .iter()
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect(),
+        _ => BTreeMap::new(),
+    };
+    Ok(ParsedFilter { expr, vars })
+}
+
+/// names of all objects of the given type matching the filter
+fn matching_objects(
+    state: &FakeIcingaState,
+    object_type: IcingaObjectType,
+    filter: &ParsedFilter,
+) -> Vec<String> {
+    let Some(objects) = state.objects.get(&object_type) else {
+        return Vec::new();
+    };
+    objects
+        .iter()
+        .filter(|(_, attrs)| {
+            let Some(expr) = &filter.expr else {
+                return true;
+            };
+            let mut bindings = joined_objects(state, object_type, attrs);
+            bindings.insert(filter_binding_name(object_type), *attrs);
+            let ctx = FilterContext {
+                vars: &filter.vars,
+                bindings,
+            };
+            ctx.matches(expr)
+        })
+        .map(|(name, _)| name.to_owned())
+        .collect()
+}
+
+/// a JSON response with the given status
+fn json_response(status: http::StatusCode, body: &serde_json::Value) -> Response<FakeBody> {
+    let mut response = Response::new(Full::new(Bytes::from(body.to_string())).boxed_unsync());
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+/// an error response in the format Icinga uses
+fn error_response(status: http::StatusCode, message: &str) -> Response<FakeBody> {
+    json_response(
+        status,
+        &serde_json::json!({ "error": status.as_u16(), "status": message }),
+    )
+}
+
+/// handle a single HTTP request
+///
+/// # Errors
+///
+/// this never fails, errors are reported to the client as HTTP status codes
+pub(crate) async fn handle(
+    shared: FakeIcingaShared,
+    req: Request<hyper::body::Incoming>,
+) -> Result<Response<FakeBody>, Infallible> {
+    let method = req
+        .headers()
+        .get("X-HTTP-Method-Override")
+        .and_then(|m| m.to_str().ok())
+        .and_then(|m| m.parse::<Method>().ok())
+        .unwrap_or_else(|| req.method().to_owned());
+    let path = req.uri().path().to_owned();
+    let headers = req.headers().to_owned();
+    let query: Vec<(String, String)> = req
+        .uri()
+        .query()
+        .map(|q| {
+            url::form_urlencoded::parse(q.as_bytes())
+                .into_owned()
+                .collect()
+        })
+        .unwrap_or_default();
+    let body = match req.into_body().collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(e) => {
+            return Ok(error_response(
+                http::StatusCode::BAD_REQUEST,
+                &format!("could not read request body: {e}"),
+            ));
+        }
+    };
+    let body: serde_json::Value = if body.is_empty() {
+        serde_json::Value::Object(serde_json::Map::new())
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(body) => body,
+            Err(e) => {
+                return Ok(error_response(
+                    http::StatusCode::BAD_REQUEST,
+                    &format!("invalid request body: {e}"),
+                ));
+            }
+        }
+    };
+    tracing::trace!(%method, %path, "fake Icinga received request:\n{:#}", body);
+    let fail_requests_with = {
+        let mut state = shared.lock();
+        state.requests.push(super::FakeRequest {
+            method: method.to_string(),
+            path: path.to_owned(),
+            headers,
+            body: body.to_owned(),
+        });
+        state.fail_requests_with
+    };
+    if let Some(status) = fail_requests_with {
+        return Ok(error_response(
+            status,
+            "Request failed as configured in the fake Icinga API.",
+        ));
+    }
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let response = match (&method, segments.as_slice()) {
+        (&Method::GET, ["v1", "objects", type_name]) => {
+            query_objects(&shared, type_name, None, &query, &body)
+        }
+        (&Method::GET, ["v1", "objects", type_name, name]) => {
+            query_objects(&shared, type_name, Some(name), &query, &body)
+        }
+        (&Method::POST, ["v1", "actions", action]) => perform_action(&shared, action, &body),
+        (&Method::POST, ["v1", "events"]) => event_stream(&shared, &query, &body),
+        _ => error_response(
+            http::StatusCode::NOT_FOUND,
+            "Request is not supported by the fake Icinga API.",
+        ),
+    };
+    Ok(response)
+}
+
+/// handle a query on /v1/objects
+fn query_objects(
+    shared: &FakeIcingaShared,
+    type_name: &str,
+    name: Option<&str>,
+    query: &[(String, String)],
+    body: &serde_json::Value,
+) -> Response<FakeBody> {
+    let Some(object_type) = object_type_from_url(type_name) else {
+        return error_response(
+            http::StatusCode::NOT_FOUND,
+            &format!("Invalid type '{type_name}'."),
+        );
+    };
+    let filter = match parse_filter(body) {
+        Ok(filter) => filter,
+        Err(e) => return error_response(http::StatusCode::BAD_REQUEST, &e),
+    };
+    let all_joins = query.iter().any(|(k, v)| k == "all_joins" && v == "1");
+    let joins: Vec<&str> = query
+        .iter()
+        .filter(|(k, _)| k == "joins")
+        .map(|(_, v)| v.as_str())
+        .collect();
+    let requested_attrs: Vec<&str> = query
+        .iter()
+        .filter(|(k, _)| k == "attrs")
+        .map(|(_, v)| v.as_str())
+        .collect();
+    let state = shared.lock();
+    let mut names = matching_objects(&state, object_type, &filter);
+    if let Some(name) = name {
+        names.retain(|n| n == name);
+        if names.is_empty() {
+            return error_response(http::StatusCode::NOT_FOUND, "No objects found.");
+        }
+    }
+    let results: Vec<serde_json::Value> = names
+        .iter()
+        .filter_map(|name| {
+            let attrs = state.objects.get(&object_type)?.get(name)?;
+            let mut join_results = serde_json::Map::new();
+            for (join_name, joined) in joined_objects(&state, object_type, attrs) {
+                if all_joins || joins.contains(&join_name.as_str()) {
+                    join_results.insert(join_name, joined.to_owned());
+                    continue;
+                }
+                let prefix = format!("{join_name}.");
+                let fields: serde_json::Map<String, serde_json::Value> = joins
+                    .iter()
+                    .filter_map(|j| j.strip_prefix(&prefix))
+                    .filter_map(|field| Some((field.to_owned(), joined.get(field)?.to_owned())))
+                    .collect();
+                if !fields.is_empty() {
+                    join_results.insert(join_name, serde_json::Value::Object(fields));
+                }
+            }
+            let attrs = match attrs.as_object() {
+                Some(all_attrs) if !requested_attrs.is_empty() => serde_json::Value::Object(
+                    all_attrs
+                        .iter()
+                        .filter(|(k, _)| requested_attrs.contains(&k.as_str()))
+                        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                        .collect(),
+                ),
+                _ => attrs.to_owned(),
+            };
+            Some(serde_json::json!({
+                "attrs": attrs,
+                "joins": join_results,
+                "meta": {},
+                "name": name,
+                "type": object_type,
+            }))
+        })
+        .collect();
+    drop(state);
+    json_response(
+        http::StatusCode::OK,
+        &serde_json::json!({ "results": results }),
+    )
+}
+
+/// a single result of an action
+fn action_result(code: http::StatusCode, status: String) -> serde_json::Value {
+    serde_json::json!({ "code": f64::from(code.as_u16()), "status": status })
+}
+
+/// the host and service name of a checkable
+fn checkable_names(
+    object_type: IcingaObjectType,
+    attrs: &serde_json::Value,
+) -> Option<(String, Option<String>)> {
+    let name = attrs.get("name")?.as_str()?.to_owned();
+    match object_type {
+        IcingaObjectType::Host => Some((name, None)),
+        IcingaObjectType::Service => {
+            Some((attrs.get("host_name")?.as_str()?.to_owned(), Some(name)))
+        }
+        _ => None,
+    }
+}
+
+/// the full name of the checkable a downtime or comment belongs to
+fn owner_full_name(attrs: &serde_json::Value) -> Option<String> {
+    let host_name = attrs.get("host_name")?.as_str()?;
+    match attrs
+        .get("service_name")
+        .and_then(serde_json::Value::as_str)
+    {
+        Some(service_name) if !service_name.is_empty() => {
+            Some(format!("{host_name}!{service_name}"))
+        }
+        _ => Some(host_name.to_owned()),
+    }
+}
+
+/// the checkable type for a downtime or comment
+fn owner_type(attrs: &serde_json::Value) -> IcingaObjectType {
+    match attrs
+        .get("service_name")
+        .and_then(serde_json::Value::as_str)
+    {
+        Some(service_name) if !service_name.is_empty() => IcingaObjectType::Service,
+        _ => IcingaObjectType::Host,
+    }
+}
+
+/// copy the listed fields from the request body into a new attribute map
+fn copy_fields(body: &serde_json::Value, fields: &[&str]) -> serde_json::Value {
+    serde_json::Value::Object(
+        fields
+            .iter()
+            .filter_map(|f| Some(((*f).to_owned(), body.get(f)?.to_owned())))
+            .filter(|(_, v)| !v.is_null())
+            .collect(),
+    )
+}
+
+/// set an attribute on a stored object
+fn set_attr(
+    state: &mut FakeIcingaState,
+    object_type: IcingaObjectType,
+    name: &str,
+    attr: &str,
+    value: serde_json::Value,
+) {
+    if let Some(attrs) = state
+        .objects
+        .get_mut(&object_type)
+        .and_then(|objects| objects.get_mut(name))
+        .and_then(serde_json::Value::as_object_mut)
+    {
+        attrs.insert(attr.to_owned(), value);
+    }
+}
+
+/// get an attribute from a stored object
+fn get_attr(
+    state: &FakeIcingaState,
+    object_type: IcingaObjectType,
+    name: &str,
+    attr: &str,
+) -> Option<serde_json::Value> {
+    state
+        .objects
+        .get(&object_type)?
+        .get(name)?
+        .get(attr)
+        .cloned()
+}
+
+/// update downtime_depth (and handled) of a checkable by the given delta
+fn adjust_downtime_depth(
+    state: &mut FakeIcingaState,
+    object_type: IcingaObjectType,
+    name: &str,
+    increase: bool,
+) {
+    let depth = get_attr(state, object_type, name, "downtime_depth")
+        .and_then(|d| d.as_u64())
+        .unwrap_or(0);
+    let depth = if increase {
+        depth.saturating_add(1)
+    } else {
+        depth.saturating_sub(1)
+    };
+    set_attr(state, object_type, name, "downtime_depth", depth.into());
+    let acknowledged = get_attr(state, object_type, name, "acknowledgement")
+        .and_then(|a| a.as_u64())
+        .is_some_and(|a| a != 0);
+    set_attr(
+        state,
+        object_type,
+        name,
+        "handled",
+        (depth > 0 || acknowledged).into(),
+    );
+}
+
+/// generate a unique name for a runtime object
+fn next_name(state: &mut FakeIcingaState) -> String {
+    state.counter = state.counter.saturating_add(1);
+    format!("fake-{}", state.counter)
+}
+
+/// remove all runtime objects of the given type matching the predicate,
+/// returns the removed objects
+fn remove_where(
+    state: &mut FakeIcingaState,
+    object_type: IcingaObjectType,
+    predicate: impl Fn(&serde_json::Value) -> bool,
+) -> Vec<(String, serde_json::Value)> {
+    let Some(objects) = state.objects.get_mut(&object_type) else {
+        return Vec::new();
+    };
+    let names: Vec<String> = objects
+        .iter()
+        .filter(|(_, attrs)| predicate(attrs))
+        .map(|(name, _)| name.to_owned())
+        .collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let attrs = objects.remove(&name)?;
+            Some((name, attrs))
+        })
+        .collect()
+}
+
+/// handle an action on /v1/actions
+fn perform_action(
+    shared: &FakeIcingaShared,
+    action: &str,
+    body: &serde_json::Value,
+) -> Response<FakeBody> {
+    let filter = match parse_filter(body) {
+        Ok(filter) => filter,
+        Err(e) => return error_response(http::StatusCode::BAD_REQUEST, &e),
+    };
+    let object_type = body
+        .get("type")
+        .and_then(|t| serde_json::from_value::<IcingaObjectType>(t.to_owned()).ok());
+    let mut state = shared.lock();
+    let targets = match object_type {
+        Some(object_type) => matching_objects(&state, object_type, &filter),
+        None => Vec::new(),
+    };
+    let results: Vec<serde_json::Value> = match (action, object_type) {
+        ("restart-process" | "shutdown-process", _) => vec![action_result(
+            http::StatusCode::OK,
+            format!("Action '{action}' accepted by the fake Icinga API."),
+        )],
+        ("generate-ticket", _) => {
+            let cn = body.get("cn").and_then(serde_json::Value::as_str);
+            vec![serde_json::json!({
+                "code": 200.0,
+                "status": format!("Generated PKI ticket for common name '{}'.", cn.unwrap_or_default()),
+                "ticket": format!("fake-ticket-{}", cn.unwrap_or_default()),
+            })]
+        }
+        (_, None) => {
+            return error_response(
+                http::StatusCode::BAD_REQUEST,
+                "Parameter 'type' is required by the fake Icinga API.",
+            );
+        }
+        (_, Some(_)) if targets.is_empty() => {
+            return error_response(http::StatusCode::NOT_FOUND, "No objects found.");
+        }
+        ("schedule-downtime", Some(object_type)) => {
+            schedule_downtime(&mut state, object_type, &targets, body)
+        }
+        ("remove-downtime", Some(object_type)) => {
+            remove_downtime(&mut state, object_type, &targets)
+        }
+        ("acknowledge-problem", Some(object_type)) => {
+            acknowledge_problem(&mut state, object_type, &targets, body)
+        }
+        ("remove-acknowledgement", Some(object_type)) => {
+            remove_acknowledgement(&mut state, object_type, &targets)
+        }
+        ("add-comment", Some(object_type)) => add_comment(&mut state, object_type, &targets, body),
+        ("remove-comment", Some(object_type)) => remove_comment(&mut state, object_type, &targets),
+        ("process-check-result", Some(object_type)) => {
+            process_check_result(&mut state, object_type, &targets, body)
+        }
+        (_, Some(_)) => targets
+            .iter()
+            .map(|name| {
+                action_result(
+                    http::StatusCode::OK,
+                    format!(
+                        "Action '{action}' accepted by the fake Icinga API for object '{name}'."
+                    ),
+                )
+            })
+            .collect(),
+    };
+    drop(state);
+    json_response(
+        http::StatusCode::OK,
+        &serde_json::json!({ "results": results }),
+    )
+}
+
+/// the schedule-downtime action
+fn schedule_downtime(
+    state: &mut FakeIcingaState,
+    object_type: IcingaObjectType,
+    targets: &[String],
+    body: &serde_json::Value,
+) -> Vec<serde_json::Value> {
+    let extra = copy_fields(
+        body,
+        &[
+            "author",
+            "comment",
+            "start_time",
+            "end_time",
+            "fixed",
+            "duration",
+        ],
+    );
+    let all_services = body
+        .get("all_services")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
+    let mut checkables: Vec<(IcingaObjectType, String)> = targets
+        .iter()
+        .map(|t| (object_type, t.to_owned()))
+        .collect();
+    if object_type == IcingaObjectType::Host
+        && all_services
+        && let Some(services) = state.objects.get(&IcingaObjectType::Service)
+    {
+        for (service_name, attrs) in services {
+            if attrs
+                .get("host_name")
+                .and_then(serde_json::Value::as_str)
+                .is_some_and(|h| targets.iter().any(|t| t == h))
+            {
+                checkables.push((IcingaObjectType::Service, service_name.to_owned()));
+            }
+        }
+    }
+    let mut results = Vec::new();
+    for (checkable_type, checkable) in checkables {
+        let Some((host_name, service_name)) = state
+            .objects
+            .get(&checkable_type)
+            .and_then(|objects| objects.get(&checkable))
+            .and_then(|attrs| checkable_names(checkable_type, attrs))
+        else {
+            continue;
+        };
+        let name = next_name(state);
+        let full_name = format!("{checkable}!{name}");
+        let attrs = fixtures::downtime(
+            &full_name,
+            &name,
+            &host_name,
+            service_name.as_deref(),
+            extra.to_owned(),
+        );
+        state
+            .objects
+            .entry(IcingaObjectType::Downtime)
+            .or_default()
+            .insert(full_name.to_owned(), attrs);
+        adjust_downtime_depth(state, checkable_type, &checkable, true);
+        results.push(serde_json::json!({
+            "code": 200.0,
+            "status": format!("Successfully scheduled downtime '{full_name}' for object '{checkable}'."),
+            "name": full_name,
+            "legacy_id": state.counter,
+        }));
+    }
+    results
+}
+
+/// the remove-downtime action
+fn remove_downtime(
+    state: &mut FakeIcingaState,
+    object_type: IcingaObjectType,
+    targets: &[String],
+) -> Vec<serde_json::Value> {
+    let removed = if object_type == IcingaObjectType::Downtime {
+        remove_where(state, IcingaObjectType::Downtime, |attrs| {
+            attrs
+                .get("__name")
+                .and_then(serde_json::Value::as_str)
+                .is_some_and(|n| targets.iter().any(|t| t == n))
+        })
+    } else {
+        remove_where(state, IcingaObjectType::Downtime, |attrs| {
+            owner_type(attrs) == object_type
+                && owner_full_name(attrs).is_some_and(|n| targets.contains(&n))
+        })
+    };
+    removed
+        .into_iter()
+        .map(|(name, attrs)| {
+            if let Some(owner) = owner_full_name(&attrs) {
+                adjust_downtime_depth(state, owner_type(&attrs), &owner, false);
+            }
+            action_result(
+                http::StatusCode::OK,
+                format!("Successfully removed downtime '{name}'."),
+            )
+        })
+        .collect()
+}
+
+/// the acknowledge-problem action
+fn acknowledge_problem(
+    state: &mut FakeIcingaState,
+    object_type: IcingaObjectType,
+    targets: &[String],
+    body: &serde_json::Value,
+) -> Vec<serde_json::Value> {
+    let sticky = body
+        .get("sticky")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false);
+    let expiry = body
+        .get("expiry")
+        .filter(|e| !e.is_null())
+        .cloned()
+        .unwrap_or_else(|| 0.into());
+    let mut results = Vec::new();
+    for target in targets {
+        let in_problem_state = get_attr(state, object_type, target, "state")
+            .and_then(|s| s.as_u64())
+            .is_some_and(|s| s != 0);
+        if !in_problem_state {
+            results.push(action_result(
+                http::StatusCode::CONFLICT,
+                format!("Neither service nor host '{target}' is in a problem state."),
+            ));
+            continue;
+        }
+        let ack_type: u8 = if sticky { 2 } else { 1 };
+        set_attr(
+            state,
+            object_type,
+            target,
+            "acknowledgement",
+            ack_type.into(),
+        );
+        set_attr(
+            state,
+            object_type,
+            target,
+            "acknowledgement_expiry",
+            expiry.to_owned(),
+        );
+        set_attr(
+            state,
+            object_type,
+            target,
+            "acknowledgement_last_change",
+            fixtures::now(),
+        );
+        set_attr(state, object_type, target, "handled", true.into());
+        let Some((host_name, service_name)) = state
+            .objects
+            .get(&object_type)
+            .and_then(|objects| objects.get(target))
+            .and_then(|attrs| checkable_names(object_type, attrs))
+        else {
+            continue;
+        };
+        let name = next_name(state);
+        let full_name = format!("{target}!{name}");
+        let mut extra = copy_fields(body, &["author", "persistent"]);
+        if let (Some(extra), Some(text)) = (extra.as_object_mut(), body.get("comment")) {
+            extra.insert("text".to_owned(), text.to_owned());
+            extra.insert("expire_time".to_owned(), expiry.to_owned());
+        }
+        let attrs = fixtures::comment(
+            &full_name,
+            &name,
+            &host_name,
+            service_name.as_deref(),
+            4,
+            extra,
+        );
+        state
+            .objects
+            .entry(IcingaObjectType::Comment)
+            .or_default()
+            .insert(full_name, attrs);
+        results.push(action_result(
+            http::StatusCode::OK,
+            format!("Successfully acknowledged problem for object '{target}'."),
+        ));
+    }
+    results
+}
+
+/// the remove-acknowledgement action
+fn remove_acknowledgement(
+    state: &mut FakeIcingaState,
+    object_type: IcingaObjectType,
+    targets: &[String],
+) -> Vec<serde_json::Value> {
+    let mut results = Vec::new();
+    for target in targets {
+        set_attr(state, object_type, target, "acknowledgement", 0.into());
+        set_attr(
+            state,
+            object_type,
+            target,
+            "acknowledgement_expiry",
+            0.into(),
+        );
+        set_attr(
+            state,
+            object_type,
+            target,
+            "acknowledgement_last_change",
+            fixtures::now(),
+        );
+        let in_downtime = get_attr(state, object_type, target, "downtime_depth")
+            .and_then(|d| d.as_u64())
+            .is_some_and(|d| d > 0);
+        set_attr(state, object_type, target, "handled", in_downtime.into());
+        remove_where(state, IcingaObjectType::Comment, |attrs| {
+            attrs.get("entry_type").and_then(serde_json::Value::as_u64) == Some(4)
+                && !attrs
+                    .get("persistent")
+                    .and_then(serde_json::Value::as_bool)
+                    .unwrap_or(false)
+                && owner_type(attrs) == object_type
+                && owner_full_name(attrs).as_ref() == Some(target)
+        });
+        results.push(action_result(
+            http::StatusCode::OK,
+            format!("Successfully removed acknowledgement for object '{target}'."),
+        ));
+    }
+    results
+}
+
+/// the add-comment action
+fn add_comment(
+    state: &mut FakeIcingaState,
+    object_type: IcingaObjectType,
+    targets: &[String],
+    body: &serde_json::Value,
+) -> Vec<serde_json::Value> {
+    let mut extra = copy_fields(body, &["author"]);
+    if let Some(extra) = extra.as_object_mut() {
+        if let Some(text) = body.get("comment") {
+            extra.insert("text".to_owned(), text.to_owned());
+        }
+        if let Some(expiry) = body.get("expiry").filter(|e| !e.is_null()) {
+            extra.insert("expire_time".to_owned(), expiry.to_owned());
+        }
+    }
+    let mut results = Vec::new();
+    for target in targets {
+        let Some((host_name, service_name)) = state
+            .objects
+            .get(&object_type)
+            .and_then(|objects| objects.get(target))
+            .and_then(|attrs| checkable_names(object_type, attrs))
+        else {
+            continue;
+        };
+        let name = next_name(state);
+        let full_name = format!("{target}!{name}");
+        let attrs = fixtures::comment(
+            &full_name,
+            &name,
+            &host_name,
+            service_name.as_deref(),
+            1,
+            extra.to_owned(),
+        );
+        state
+            .objects
+            .entry(IcingaObjectType::Comment)
+            .or_default()
+            .insert(full_name.to_owned(), attrs);
+        results.push(serde_json::json!({
+            "code": 200.0,
+            "status": format!("Successfully added comment '{full_name}' for object '{target}'."),
+            "name": full_name,
+            "legacy_id": state.counter,
+        }));
+    }
+    results
+}
+
+/// the remove-comment action
+fn remove_comment(
+    state: &mut FakeIcingaState,
+    object_type: IcingaObjectType,
+    targets: &[String],
+) -> Vec<serde_json::Value> {
+    let removed = if object_type == IcingaObjectType::Comment {
+        remove_where(state, IcingaObjectType::Comment, |attrs| {
+            attrs
+                .get("__name")
+                .and_then(serde_json::Value::as_str)
+                .is_some_and(|n| targets.iter().any(|t| t == n))
+        })
+    } else {
+        remove_where(state, IcingaObjectType::Comment, |attrs| {
+            owner_type(attrs) == object_type
+                && owner_full_name(attrs).is_some_and(|n| targets.contains(&n))
+        })
+    };
+    removed
+        .into_iter()
+        .map(|(name, _)| {
+            action_result(
+                http::StatusCode::OK,
+                format!("Successfully removed comment '{name}'."),
+            )
+        })
+        .collect()
+}
+
+/// the process-check-result action
+fn process_check_result(
+    state: &mut FakeIcingaState,
+    object_type: IcingaObjectType,
+    targets: &[String],
+    body: &serde_json::Value,
+) -> Vec<serde_json::Value> {
+    let Some(exit_status) = body
+        .get("exit_status")
+        .and_then(serde_json::Value::as_u64)
+        .and_then(|s| u8::try_from(s).ok())
+    else {
+        return vec![action_result(
+            http::StatusCode::BAD_REQUEST,
+            "Parameter 'exit_status' is required.".to_owned(),
+        )];
+    };
+    let output = body
+        .get("plugin_output")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default();
+    let mut check_result = fixtures::check_result(exit_status, output);
+    if let Some(check_result) = check_result.as_object_mut() {
+        check_result.insert("active".to_owned(), false.into());
+        for field in [
+            "performance_data",
+            "check_source",
+            "execution_start",
+            "execution_end",
+            "ttl",
+        ] {
+            if let Some(v) = body.get(field).filter(|v| !v.is_null()) {
+                check_result.insert(field.to_owned(), v.to_owned());
+            }
+        }
+    }
+    let mut results = Vec::new();
+    for target in targets {
+        if let Some(previous) = get_attr(state, object_type, target, "state") {
+            set_attr(state, object_type, target, "last_state", previous);
+        }
+        set_attr(state, object_type, target, "state", exit_status.into());
+        set_attr(
+            state,
+            object_type,
+            target,
+            "problem",
+            (exit_status != 0).into(),
+        );
+        set_attr(state, object_type, target, "last_check", fixtures::now());
+        set_attr(
+            state,
+            object_type,
+            target,
+            "last_check_result",
+            check_result.to_owned(),
+        );
+        results.push(action_result(
+            http::StatusCode::OK,
+            format!("Successfully processed check result for object '{target}'."),
+        ));
+    }
+    results
+}
+
+/// handle a request to /v1/events
+fn event_stream(
+    shared: &FakeIcingaShared,
+    query: &[(String, String)],
+    body: &serde_json::Value,
+) -> Response<FakeBody> {
+    let types: Vec<String> = query
+        .iter()
+        .filter(|(k, _)| k == "types")
+        .map(|(_, v)| v.to_owned())
+        .collect();
+    if types.is_empty() {
+        return error_response(
+            http::StatusCode::BAD_REQUEST,
+            "'types' query parameter required.",
+        );
+    }
+    if !query.iter().any(|(k, _)| k == "queue") {
+        return error_response(
+            http::StatusCode::BAD_REQUEST,
+            "'queue' query parameter required.",
+        );
+    }
+    let filter = match parse_filter(body) {
+        Ok(filter) => filter,
+        Err(e) => return error_response(http::StatusCode::BAD_REQUEST, &e),
+    };
+    let receiver = shared.events.subscribe();
+    let scripted = shared.lock().scripted_events.to_owned();
+    let wanted = move |event: &serde_json::Value| {
+        let type_matches = event
+            .get("type")
+            .and_then(serde_json::Value::as_str)
+            .is_some_and(|t| types.iter().any(|wanted| wanted == t));
+        let filter_matches = filter.expr.as_ref().is_none_or(|expr| {
+            let ctx = FilterContext {
+                vars: &filter.vars,
+                bindings: BTreeMap::from([("event".to_owned(), event)]),
+            };
+            ctx.matches(expr)
+        });
+        type_matches && filter_matches
+    };
+    let scripted = futures::stream::iter(scripted.into_iter().map(FakeEventMessage::Event));
+    let live = futures::stream::unfold(receiver, async |mut receiver| {
+        loop {
+            match receiver.recv().await {
+                Ok(message) => return Some((message, receiver)),
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    tracing::warn!("fake Icinga event stream lagged by {} events", n);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    let frames = scripted
+        .chain(live)
+        .take_while(|message| std::future::ready(!matches!(message, FakeEventMessage::End)))
+        .filter_map(move |message| {
+            std::future::ready(match message {
+                FakeEventMessage::Event(event) if wanted(&event) => {
+                    let mut line = event.to_string();
+                    line.push('\n');
+                    Some(Ok::<_, Infallible>(Frame::data(Bytes::from(line))))
+                }
+                FakeEventMessage::Event(_) | FakeEventMessage::End => None,
+            })
+        });
+    let mut response = Response::new(StreamBody::new(frames).boxed_unsync());
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("application/json"),
+    );
+    response
+}
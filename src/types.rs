@@ -3,15 +3,21 @@
 //! [Official Object Types Documentation](https://icinga.com/docs/icinga-2/latest/doc/09-object-types/)
 
 pub mod action;
+pub mod attributes;
 pub mod common;
+pub mod downtime_calendar;
 pub mod enums;
 pub mod event_stream;
 pub mod features;
 pub mod filter;
 pub mod join_types;
+pub mod lenient;
 pub mod metadata;
 pub mod monitoring_objects;
 pub mod names;
 pub mod query;
 pub mod rest;
 pub mod runtime_objects;
+pub mod threshold_range;
+pub mod time_ranges;
+pub mod timestamp;
@@ -0,0 +1,21 @@
+//! The attrs parameter of queries
+//!
+//! Restricting the returned attributes to the ones actually needed can
+//! reduce the size of query results considerably, e.g. by not returning
+//! the last check result of every host or service.
+//!
+//! The attribute enums and the partial result types with all-optional
+//! fields are defined next to the full object types.
+
+/// a marker trait for all the various attribute enums for the different objects
+pub trait IcingaAttribute {}
+
+/// shared code for all the handlers that have an attrs parameter
+pub(crate) fn add_attrs_to_url<A: IcingaAttribute + std::fmt::Display>(
+    url: &mut url::Url,
+    attrs: &[A],
+) {
+    for a in attrs {
+        url.query_pairs_mut().append_pair("attrs", &a.to_string());
+    }
+}
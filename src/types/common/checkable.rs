@@ -7,10 +7,8 @@ use serde::Serialize;
 
 use crate::serde::{
     deserialize_empty_string_or_parse, deserialize_empty_string_or_string,
-    deserialize_icinga_timestamp, deserialize_optional_icinga_timestamp,
-    deserialize_optional_seconds_as_duration, serialize_icinga_timestamp,
-    serialize_none_as_empty_string, serialize_none_as_empty_string_or_to_string,
-    serialize_optional_duration_as_seconds, serialize_optional_icinga_timestamp,
+    deserialize_optional_seconds_as_duration, serialize_none_as_empty_string,
+    serialize_none_as_empty_string_or_to_string, serialize_optional_duration_as_seconds,
 };
 use crate::types::enums::acknowledgement_type::IcingaAcknowledgementType;
 use crate::types::enums::host_or_service_state::IcingaHostOrServiceState;
@@ -19,10 +17,12 @@ use crate::types::names::IcingaCheckCommandName;
 use crate::types::names::IcingaEndpointName;
 use crate::types::names::IcingaEventCommandName;
 use crate::types::names::IcingaTimePeriodName;
+use crate::types::timestamp::IcingaTimestamp;
 
 use super::check_result::IcingaCheckResult;
 use super::custom_var_object::CustomVarHolder;
 use super::custom_var_object::IcingaCustomVarObject;
+use super::custom_var_object::IcingaCustomVarObjectPartial;
 
 /// shared attributes on any checkable object (host and service)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -37,17 +37,9 @@ pub struct IcingaCheckable {
     /// the type of acknowledgement (includes None)
     pub acknowledgement: IcingaAcknowledgementType,
     /// when the acknowledgement expires
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub acknowledgement_expiry: Option<time::OffsetDateTime>,
+    pub acknowledgement_expiry: IcingaTimestamp,
     /// when the acknowledgement last changed
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub acknowledgement_last_change: Option<time::OffsetDateTime>,
+    pub acknowledgement_last_change: IcingaTimestamp,
     /// URL for actions for the checkable (host or service)
     #[serde(
         serialize_with = "serialize_none_as_empty_string",
@@ -114,11 +106,7 @@ pub struct IcingaCheckable {
     #[serde(default)]
     pub flapping_ignore_states: Option<Vec<IcingaHostOrServiceState>>,
     /// when the last flapping change occurred
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub flapping_last_change: Option<time::OffsetDateTime>,
+    pub flapping_last_change: IcingaTimestamp,
     /// deprecated and has no effect, replaced by flapping_threshold_low and flapping_threshold_high
     pub flapping_threshold: f64,
     /// the flapping lower bound in percent for a host/service to be considered flapping
@@ -144,49 +132,25 @@ pub struct IcingaCheckable {
     )]
     pub icon_image_alt: Option<String>,
     /// when the last check occurred
-    #[serde(
-        serialize_with = "serialize_icinga_timestamp",
-        deserialize_with = "deserialize_icinga_timestamp"
-    )]
-    pub last_check: time::OffsetDateTime,
+    pub last_check: IcingaTimestamp,
     /// the result of the last check
     pub last_check_result: IcingaCheckResult,
     /// when the last hard state change occurred
-    #[serde(
-        serialize_with = "serialize_icinga_timestamp",
-        deserialize_with = "deserialize_icinga_timestamp"
-    )]
-    pub last_hard_state_change: time::OffsetDateTime,
+    pub last_hard_state_change: IcingaTimestamp,
     /// whether the host/service was reachable when the last check occurred
     pub last_reachable: bool,
     /// when the last state change occurred
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub last_state_change: Option<time::OffsetDateTime>,
+    pub last_state_change: IcingaTimestamp,
     /// the previous state type (soft/hard)
     pub last_state_type: IcingaStateType,
     /// when the last UNREACHABLE state occurred
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub last_state_unreachable: Option<time::OffsetDateTime>,
+    pub last_state_unreachable: IcingaTimestamp,
     /// the number of times the host/service is checked before changing into a new hard state
     pub max_check_attempts: u64,
     /// when the next check occurs
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub next_check: Option<time::OffsetDateTime>,
+    pub next_check: IcingaTimestamp,
     /// when the next check update is to be expected
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub next_update: Option<time::OffsetDateTime>,
+    pub next_update: IcingaTimestamp,
     /// notes for the host/service
     #[serde(
         serialize_with = "serialize_none_as_empty_string",
@@ -200,11 +164,7 @@ pub struct IcingaCheckable {
     )]
     pub notes_url: Option<String>,
     /// when the previous state change occurred
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub previous_state_change: Option<time::OffsetDateTime>,
+    pub previous_state_change: IcingaTimestamp,
     /// whether the host/service is considered to be in a problem state type (not up)
     pub problem: bool,
     /// the interval used for checks when the host/service is in a SOFT state
@@ -220,6 +180,10 @@ pub struct IcingaCheckable {
     pub state_type: IcingaStateType,
     /// treat all state changes as HARD changes
     pub volatile: bool,
+    /// attributes not modelled by this crate, only filled in by lenient
+    /// deserialization (see [crate::types::lenient])
+    #[serde(flatten, skip_deserializing)]
+    pub extra: std::collections::BTreeMap<String, serde_json::Value>,
 }
 
 impl CustomVarHolder for IcingaCheckable {
@@ -227,3 +191,176 @@ impl CustomVarHolder for IcingaCheckable {
         self.custom_var.custom_var_value(name)
     }
 }
+
+/// the attributes of [IcingaCheckable] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct IcingaCheckablePartial {
+    /// shared config object and custom variable fields
+    #[serde(flatten)]
+    pub custom_var: IcingaCustomVarObjectPartial,
+    /// the type of acknowledgement (includes None)
+    pub acknowledgement: Option<IcingaAcknowledgementType>,
+    /// when the acknowledgement expires
+    #[serde(default)]
+    pub acknowledgement_expiry: IcingaTimestamp,
+    /// when the acknowledgement last changed
+    #[serde(default)]
+    pub acknowledgement_last_change: IcingaTimestamp,
+    /// URL for actions for the checkable (host or service)
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string",
+        deserialize_with = "deserialize_empty_string_or_string"
+    )]
+    pub action_url: Option<String>,
+    /// the current check attempt number
+    pub check_attempt: Option<u64>,
+    /// the name of the check command
+    pub check_command: Option<IcingaCheckCommandName>,
+    /// the interval used for checks when the host/service is in a HARD state
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_duration_as_seconds",
+        deserialize_with = "deserialize_optional_seconds_as_duration"
+    )]
+    pub check_interval: Option<time::Duration>,
+    /// name of a time period when this host/service is checked
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string_or_to_string",
+        deserialize_with = "deserialize_empty_string_or_parse"
+    )]
+    pub check_period: Option<IcingaTimePeriodName>,
+    /// check timeout
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_duration_as_seconds",
+        deserialize_with = "deserialize_optional_seconds_as_duration"
+    )]
+    pub check_timeout: Option<time::Duration>,
+    /// the endpoint the command is executed on
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string_or_to_string",
+        deserialize_with = "deserialize_empty_string_or_parse"
+    )]
+    pub command_endpoint: Option<IcingaEndpointName>,
+    /// number of active downtimes on the host/service
+    pub downtime_depth: Option<u64>,
+    /// whether active checks are enabled
+    pub enable_active_checks: Option<bool>,
+    /// enabled event handlers for this host/service
+    pub enable_event_handler: Option<bool>,
+    /// whether flap detection is enabled
+    pub enable_flapping: Option<bool>,
+    /// whether notifications are enabled
+    pub enable_notifications: Option<bool>,
+    /// whether passive checks are enabled
+    pub enable_passive_checks: Option<bool>,
+    /// whether performance data processing is enabled
+    pub enable_perfdata: Option<bool>,
+    /// the name of an event command that should be executed every time the host/service state changes or the host/service is in a SOFT state
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string_or_to_string",
+        deserialize_with = "deserialize_empty_string_or_parse"
+    )]
+    pub event_command: Option<IcingaEventCommandName>,
+    /// contains the state of execute-command executions
+    pub executions: Option<()>,
+    /// whether the host/service is flapping between states
+    pub flapping: Option<bool>,
+    /// current flapping value in percent
+    pub flapping_current: Option<f64>,
+    /// a list of states that should be ignored during flapping calculations
+    #[serde(default)]
+    pub flapping_ignore_states: Option<Vec<IcingaHostOrServiceState>>,
+    /// when the last flapping change occurred
+    #[serde(default)]
+    pub flapping_last_change: IcingaTimestamp,
+    /// deprecated and has no effect, replaced by flapping_threshold_low and flapping_threshold_high
+    pub flapping_threshold: Option<f64>,
+    /// the flapping lower bound in percent for a host/service to be considered flapping
+    pub flapping_threshold_low: Option<f64>,
+    /// the flapping upper bound in percent for a host/service to be considered flapping
+    pub flapping_threshold_high: Option<f64>,
+    /// force the next check (execute it now)
+    pub force_next_check: Option<bool>,
+    /// force next notification (send it now)
+    pub force_next_notification: Option<bool>,
+    /// whether the host/service problem is handled (downtime or acknowledgement)
+    pub handled: Option<bool>,
+    /// icon image for the host/service
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string",
+        deserialize_with = "deserialize_empty_string_or_string"
+    )]
+    pub icon_image: Option<String>,
+    /// icon image alt text for the host/service
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string",
+        deserialize_with = "deserialize_empty_string_or_string"
+    )]
+    pub icon_image_alt: Option<String>,
+    /// when the last check occurred
+    #[serde(default)]
+    pub last_check: IcingaTimestamp,
+    /// the result of the last check
+    pub last_check_result: Option<IcingaCheckResult>,
+    /// when the last hard state change occurred
+    #[serde(default)]
+    pub last_hard_state_change: IcingaTimestamp,
+    /// whether the host/service was reachable when the last check occurred
+    pub last_reachable: Option<bool>,
+    /// when the last state change occurred
+    #[serde(default)]
+    pub last_state_change: IcingaTimestamp,
+    /// the previous state type (soft/hard)
+    pub last_state_type: Option<IcingaStateType>,
+    /// when the last UNREACHABLE state occurred
+    #[serde(default)]
+    pub last_state_unreachable: IcingaTimestamp,
+    /// the number of times the host/service is checked before changing into a new hard state
+    pub max_check_attempts: Option<u64>,
+    /// when the next check occurs
+    #[serde(default)]
+    pub next_check: IcingaTimestamp,
+    /// when the next check update is to be expected
+    #[serde(default)]
+    pub next_update: IcingaTimestamp,
+    /// notes for the host/service
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string",
+        deserialize_with = "deserialize_empty_string_or_string"
+    )]
+    pub notes: Option<String>,
+    /// URL for notes for the host/service
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string",
+        deserialize_with = "deserialize_empty_string_or_string"
+    )]
+    pub notes_url: Option<String>,
+    /// when the previous state change occurred
+    #[serde(default)]
+    pub previous_state_change: IcingaTimestamp,
+    /// whether the host/service is considered to be in a problem state type (not up)
+    pub problem: Option<bool>,
+    /// the interval used for checks when the host/service is in a SOFT state
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_duration_as_seconds",
+        deserialize_with = "deserialize_optional_seconds_as_duration"
+    )]
+    pub retry_interval: Option<time::Duration>,
+    /// pre-calculated value, higher means more severe
+    pub severity: Option<u64>,
+    /// the current state type (soft/hard)
+    pub state_type: Option<IcingaStateType>,
+    /// treat all state changes as HARD changes
+    pub volatile: Option<bool>,
+}
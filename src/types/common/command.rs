@@ -10,6 +10,7 @@ use crate::serde::{
     deserialize_optional_seconds_as_duration, serialize_optional_duration_as_seconds,
 };
 
+use super::custom_var_object::IcingaCustomVarObjectPartial;
 use super::{
     custom_var_object::{CustomVarHolder, IcingaCustomVarObject},
     function::IcingaFunction,
@@ -109,3 +110,27 @@ pub enum IcingaCommandArgumentDescription {
         separator: Option<String>,
     },
 }
+
+/// the attributes of [IcingaCommand] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IcingaCommandPartial {
+    /// shared config object and custom variable fields
+    #[serde(flatten)]
+    pub custom_var: IcingaCustomVarObjectPartial,
+    /// the descriptions of the command arguments
+    pub arguments: Option<BTreeMap<String, IcingaCommandArgumentDescription>>,
+    /// the actual command
+    pub command: Option<IcingaCommandLine>,
+    /// environment variables
+    pub env: Option<BTreeMap<String, String>>,
+    /// function for execution
+    pub execute: Option<IcingaFunction>,
+    /// command timeout
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_duration_as_seconds",
+        deserialize_with = "deserialize_optional_seconds_as_duration"
+    )]
+    pub timeout: Option<time::Duration>,
+}
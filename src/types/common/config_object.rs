@@ -54,3 +54,43 @@ pub struct IcingaConfigObject {
     )]
     pub zone: Option<IcingaZoneName>,
 }
+
+/// the attributes of [IcingaConfigObject] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IcingaConfigObjectPartial {
+    /// full object name
+    #[serde(rename = "__name")]
+    pub full_name: Option<String>,
+    /// short object name
+    pub name: Option<String>,
+    /// object is active (being checked)
+    pub active: Option<bool>,
+    /// whether to run a check once or everywhere
+    pub ha_mode: Option<HAMode>,
+    /// original values of object attributes modified at runtime
+    pub original_attributes: Option<BTreeMap<String, serde_json::Value>>,
+    /// configuration package name this object belongs to, _etc for local configuration
+    /// _api for runtime created objects
+    pub package: Option<IcingaPackageName>,
+    /// object has been paused at runtime
+    pub paused: Option<bool>,
+    /// location information whether the configuration files are stored
+    pub source_location: Option<IcingaSourceLocation>,
+    /// templates imported on object compilation
+    pub templates: Option<Vec<IcingaTemplateName>>,
+    /// timestamp when the object was created or modified. syncred throughout cluster nodes
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_icinga_timestamp",
+        deserialize_with = "deserialize_optional_icinga_timestamp"
+    )]
+    pub version: Option<time::OffsetDateTime>,
+    /// the zone this object is a member of
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string_or_to_string",
+        deserialize_with = "deserialize_empty_string_or_parse"
+    )]
+    pub zone: Option<IcingaZoneName>,
+}
@@ -7,6 +7,7 @@ use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 use super::config_object::IcingaConfigObject;
+use super::config_object::IcingaConfigObjectPartial;
 
 /// allows easier retrieval of custom variables from all objects which store them somewhere
 /// (possibly deep in some nested field)
@@ -40,3 +41,14 @@ impl CustomVarHolder for IcingaCustomVarObject {
         self.vars.as_ref().and_then(|vars| vars.get(name))
     }
 }
+
+/// the attributes of [IcingaCustomVarObject] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IcingaCustomVarObjectPartial {
+    /// custom variables specific to this object
+    pub vars: Option<BTreeMap<String, serde_json::Value>>,
+    /// shared config object fields
+    #[serde(flatten)]
+    pub config_object: IcingaConfigObjectPartial,
+}
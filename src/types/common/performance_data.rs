@@ -1,9 +1,11 @@
 //! Icinga2 performance data as it appears in check results
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::serde::{deserialize_empty_string_or_string, serialize_none_as_empty_string};
 use crate::types::enums::object_type::IcingaObjectType;
+use crate::types::threshold_range::IcingaThresholdRange;
 
 /// represents performance data
 ///
@@ -22,10 +24,10 @@ pub enum IcingaPerformanceData {
         counter: bool,
         /// the current value
         value: f64,
-        /// the critical value
-        crit: Option<f64>,
-        /// the warning value
-        warn: Option<f64>,
+        /// the critical threshold
+        crit: Option<IcingaThresholdRange>,
+        /// the warning threshold
+        warn: Option<IcingaThresholdRange>,
         /// the label for the type of values
         #[serde(
             serialize_with = "serialize_none_as_empty_string",
@@ -44,3 +46,562 @@ pub enum IcingaPerformanceData {
         unit: Option<String>,
     },
 }
+
+/// error parsing plain-text performance data
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PerfDataParseError {
+    /// a label is not followed by `=` and a value
+    #[error("performance data label without a value: {0}")]
+    MissingValue(String),
+    /// a quoted label is missing its closing quote
+    #[error("unterminated quote in performance data label: {0}")]
+    UnterminatedQuote(String),
+    /// a label is empty
+    #[error("empty performance data label")]
+    EmptyLabel,
+    /// the value is not a number followed by an optional unit
+    #[error("invalid value for performance data label {label}: {value}")]
+    InvalidValue {
+        /// the label of the value
+        label: String,
+        /// the value that could not be parsed
+        value: String,
+    },
+    /// the warn or crit threshold is not a valid range
+    #[error("invalid threshold for performance data label {label}: {value}")]
+    InvalidThreshold {
+        /// the label of the value
+        label: String,
+        /// the threshold that could not be parsed
+        value: String,
+    },
+    /// the minimum or maximum is not a number
+    #[error("invalid minimum or maximum for performance data label {label}: {value}")]
+    InvalidMinMax {
+        /// the label of the value
+        label: String,
+        /// the minimum or maximum that could not be parsed
+        value: String,
+    },
+    /// there are more than the five fields value, warn, crit, min and max
+    #[error("more than five fields for performance data label {0}")]
+    TooManyFields(String),
+    /// a single value was expected but the string contained a different number of values
+    #[error("expected exactly one performance data value but found {0}")]
+    NotExactlyOne(usize),
+}
+
+impl IcingaPerformanceData {
+    /// parse plain-text performance data in the Nagios/Icinga format
+    /// (`'label'=value[UOM];[warn];[crit];[min];[max]`, multiple values are
+    /// separated by whitespace) into the structured representation
+    ///
+    /// like Icinga itself this keeps the unit as given and sets `counter`
+    /// for the unit `c`, warn and crit are parsed as [IcingaThresholdRange]
+    ///
+    /// # Errors
+    ///
+    /// returns an error if the performance data does not follow the format
+    pub fn parse_all(perfdata: &str) -> Result<Vec<Self>, PerfDataParseError> {
+        let mut rest = perfdata.trim_start();
+        let mut values = Vec::new();
+        while !rest.is_empty() {
+            let (label, after_label) = parse_label(rest)?;
+            let (value, after_value) = after_label
+                .split_once(char::is_whitespace)
+                .unwrap_or((after_label, ""));
+            values.push(parse_value(label, value)?);
+            rest = after_value.trim_start();
+        }
+        Ok(values)
+    }
+
+    /// parse whitespace separated performance data like Icinga does
+    ///
+    /// each value is parsed on its own, values that can not be parsed are
+    /// kept in string format instead of failing the whole string
+    #[must_use]
+    pub fn parse_all_lossy(perfdata: &str) -> Vec<Self> {
+        split_values(perfdata)
+            .into_iter()
+            .map(|value| {
+                value
+                    .parse()
+                    .unwrap_or_else(|_err| Self::String(value.to_owned()))
+            })
+            .collect()
+    }
+
+    /// convert performance data in string format into the structured
+    /// representation, structured values are returned unchanged
+    ///
+    /// a string can contain multiple values
+    ///
+    /// # Errors
+    ///
+    /// returns an error if the string does not follow the performance data format
+    pub fn into_structured(self) -> Result<Vec<Self>, PerfDataParseError> {
+        match self {
+            Self::String(perfdata) => Self::parse_all(&perfdata),
+            Self::PerfDataValue { .. } => Ok(vec![self]),
+        }
+    }
+
+    /// format multiple values as a plain-text performance data string
+    #[must_use]
+    pub fn format_all(values: &[Self]) -> String {
+        values
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl std::str::FromStr for IcingaPerformanceData {
+    type Err = PerfDataParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut values = Self::parse_all(s)?;
+        if values.len() == 1
+            && let Some(value) = values.pop()
+        {
+            Ok(value)
+        } else {
+            Err(PerfDataParseError::NotExactlyOne(values.len()))
+        }
+    }
+}
+
+impl std::fmt::Display for IcingaPerformanceData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::String(perfdata) => write!(f, "{perfdata}"),
+            Self::PerfDataValue {
+                counter,
+                value,
+                crit,
+                warn,
+                label,
+                min,
+                max,
+                unit,
+                ..
+            } => {
+                write_label(f, label.as_deref().unwrap_or_default())?;
+                write!(f, "={value}")?;
+                if *counter {
+                    write!(f, "c")?;
+                } else if let Some(unit) = unit {
+                    write!(f, "{unit}")?;
+                }
+                let fields = [
+                    warn.as_ref().map(ToString::to_string),
+                    crit.as_ref().map(ToString::to_string),
+                    min.as_ref().map(ToString::to_string),
+                    max.as_ref().map(ToString::to_string),
+                ];
+                let used = fields
+                    .iter()
+                    .rposition(|field| field.is_some())
+                    .map_or(0, |last| last.saturating_add(1));
+                for field in fields.iter().take(used) {
+                    write!(f, ";")?;
+                    if let Some(field) = field {
+                        write!(f, "{field}")?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// split performance data into values at whitespace outside of quoted labels
+fn split_values(perfdata: &str) -> Vec<&str> {
+    let mut values = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+    for (index, c) in perfdata.char_indices() {
+        if c.is_whitespace() && !quoted {
+            if let Some(start) = start.take()
+                && let Some(value) = perfdata.get(start..index)
+            {
+                values.push(value);
+            }
+        } else {
+            if c == '\'' {
+                quoted = !quoted;
+            }
+            start.get_or_insert(index);
+        }
+    }
+    if let Some(start) = start
+        && let Some(value) = perfdata.get(start..)
+    {
+        values.push(value);
+    }
+    values
+}
+
+/// write a label, quoting it if necessary
+fn write_label(f: &mut std::fmt::Formatter<'_>, label: &str) -> std::fmt::Result {
+    if label.is_empty()
+        || label
+            .chars()
+            .any(|c| c.is_whitespace() || c == '=' || c == '\'')
+    {
+        write!(f, "'{}'", label.replace('\'', "''"))
+    } else {
+        write!(f, "{label}")
+    }
+}
+
+/// parse the label at the start of the string including the `=`
+///
+/// returns the label and the rest of the string after the `=`
+fn parse_label(s: &str) -> Result<(String, &str), PerfDataParseError> {
+    let (label, after_label) = if let Some(mut rest) = s.strip_prefix('\'') {
+        let mut label = String::new();
+        loop {
+            let Some((part, after)) = rest.split_once('\'') else {
+                return Err(PerfDataParseError::UnterminatedQuote(s.to_owned()));
+            };
+            label.push_str(part);
+            if let Some(after) = after.strip_prefix('\'') {
+                label.push('\'');
+                rest = after;
+            } else {
+                let Some(after) = after.strip_prefix('=') else {
+                    return Err(PerfDataParseError::MissingValue(label));
+                };
+                break (label, after);
+            }
+        }
+    } else {
+        let Some((label, after)) = s.split_once('=') else {
+            return Err(PerfDataParseError::MissingValue(s.to_owned()));
+        };
+        (label.to_owned(), after)
+    };
+    if label.trim().is_empty() {
+        return Err(PerfDataParseError::EmptyLabel);
+    }
+    Ok((label, after_label))
+}
+
+/// the length of the decimal number at the start of the string
+///
+/// an optional sign, digits with an optional decimal point and an optional
+/// exponent, the exponent is only included if it contains digits
+fn number_length(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let digits_from = |start: usize| {
+        bytes
+            .iter()
+            .skip(start)
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+            .saturating_add(start)
+    };
+    let sign_from = |start: usize| {
+        if matches!(bytes.get(start), Some(b'+' | b'-')) {
+            start.saturating_add(1)
+        } else {
+            start
+        }
+    };
+    let mut end = digits_from(sign_from(0));
+    if bytes.get(end) == Some(&b'.') {
+        end = digits_from(end.saturating_add(1));
+    }
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let exponent_digits = sign_from(end.saturating_add(1));
+        let exponent_end = digits_from(exponent_digits);
+        if exponent_end > exponent_digits {
+            end = exponent_end;
+        }
+    }
+    end
+}
+
+/// parse a finite number, infinity and NaN are not valid in performance data
+fn parse_finite(s: &str) -> Option<f64> {
+    s.parse::<f64>().ok().filter(|value| value.is_finite())
+}
+
+/// parse the `value[UOM];[warn];[crit];[min];[max]` part of a value
+fn parse_value(label: String, s: &str) -> Result<IcingaPerformanceData, PerfDataParseError> {
+    let mut fields = s.split(';');
+    let value_with_unit = fields.next().unwrap_or_default();
+    let Some((value, unit)) = value_with_unit
+        .split_at_checked(number_length(value_with_unit))
+        .and_then(|(value, unit)| parse_finite(value).map(|value| (value, unit)))
+    else {
+        return Err(PerfDataParseError::InvalidValue {
+            label,
+            value: value_with_unit.to_owned(),
+        });
+    };
+    let warn = fields
+        .next()
+        .map(|field| parse_threshold(&label, field))
+        .transpose()?
+        .flatten();
+    let crit = fields
+        .next()
+        .map(|field| parse_threshold(&label, field))
+        .transpose()?
+        .flatten();
+    let min = fields
+        .next()
+        .map(|field| parse_min_max(&label, field))
+        .transpose()?
+        .flatten();
+    let max = fields
+        .next()
+        .map(|field| parse_min_max(&label, field))
+        .transpose()?
+        .flatten();
+    if fields.next().is_some() {
+        return Err(PerfDataParseError::TooManyFields(label));
+    }
+    let counter = unit == "c";
+    Ok(IcingaPerformanceData::PerfDataValue {
+        object_type: IcingaObjectType::PerfdataValue,
+        counter,
+        value,
+        crit,
+        warn,
+        label: Some(label),
+        min,
+        max,
+        unit: if counter || unit.is_empty() {
+            None
+        } else {
+            Some(unit.to_owned())
+        },
+    })
+}
+
+/// parse a warn or crit field
+///
+/// # Errors
+///
+/// returns an error if the field is neither empty nor a threshold range
+fn parse_threshold(
+    label: &str,
+    s: &str,
+) -> Result<Option<IcingaThresholdRange>, PerfDataParseError> {
+    let s = s.trim();
+    if s.is_empty() {
+        Ok(None)
+    } else {
+        s.parse()
+            .map(Some)
+            .map_err(|_err| PerfDataParseError::InvalidThreshold {
+                label: label.to_owned(),
+                value: s.to_owned(),
+            })
+    }
+}
+
+/// parse a min or max field
+///
+/// # Errors
+///
+/// returns an error if the field is neither empty nor a finite number
+fn parse_min_max(label: &str, s: &str) -> Result<Option<f64>, PerfDataParseError> {
+    let s = s.trim();
+    if s.is_empty() {
+        Ok(None)
+    } else {
+        parse_finite(s)
+            .map(Some)
+            .ok_or_else(|| PerfDataParseError::InvalidMinMax {
+                label: label.to_owned(),
+                value: s.to_owned(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+
+    /// a structured value with only label, value and unit set
+    fn perfdata(label: &str, value: f64, unit: Option<&str>) -> IcingaPerformanceData {
+        IcingaPerformanceData::PerfDataValue {
+            object_type: IcingaObjectType::PerfdataValue,
+            counter: false,
+            value,
+            crit: None,
+            warn: None,
+            label: Some(label.to_owned()),
+            min: None,
+            max: None,
+            unit: unit.map(ToOwned::to_owned),
+        }
+    }
+
+    #[test]
+    fn test_parse_perfdata() -> Result<(), Box<dyn Error>> {
+        let values = IcingaPerformanceData::parse_all(
+            "'time taken'=12.3ms;10;20;0;100 rta=0.5 'it''s'=3c  load1=-1.5e1;;;0 size=10B;5:20;@1:2",
+        )?;
+        assert_eq!(
+            values,
+            vec![
+                IcingaPerformanceData::PerfDataValue {
+                    object_type: IcingaObjectType::PerfdataValue,
+                    counter: false,
+                    value: 12.3,
+                    crit: Some(IcingaThresholdRange::from_zero_to(20.0)),
+                    warn: Some(IcingaThresholdRange::from_zero_to(10.0)),
+                    label: Some("time taken".to_owned()),
+                    min: Some(0.0),
+                    max: Some(100.0),
+                    unit: Some("ms".to_owned()),
+                },
+                perfdata("rta", 0.5, None),
+                IcingaPerformanceData::PerfDataValue {
+                    object_type: IcingaObjectType::PerfdataValue,
+                    counter: true,
+                    value: 3.0,
+                    crit: None,
+                    warn: None,
+                    label: Some("it's".to_owned()),
+                    min: None,
+                    max: None,
+                    unit: None,
+                },
+                IcingaPerformanceData::PerfDataValue {
+                    object_type: IcingaObjectType::PerfdataValue,
+                    counter: false,
+                    value: -15.0,
+                    crit: None,
+                    warn: None,
+                    label: Some("load1".to_owned()),
+                    min: Some(0.0),
+                    max: None,
+                    unit: None,
+                },
+                IcingaPerformanceData::PerfDataValue {
+                    object_type: IcingaObjectType::PerfdataValue,
+                    counter: false,
+                    value: 10.0,
+                    crit: Some(IcingaThresholdRange {
+                        start: Some(1.0),
+                        end: Some(2.0),
+                        inside: true,
+                    }),
+                    warn: Some(IcingaThresholdRange {
+                        start: Some(5.0),
+                        end: Some(20.0),
+                        inside: false,
+                    }),
+                    label: Some("size".to_owned()),
+                    min: None,
+                    max: None,
+                    unit: Some("B".to_owned()),
+                },
+            ]
+        );
+        assert_eq!(
+            IcingaPerformanceData::String("a=1 b=2%".to_owned()).into_structured()?,
+            vec![perfdata("a", 1.0, None), perfdata("b", 2.0, Some("%"))]
+        );
+        assert_eq!(
+            IcingaPerformanceData::parse_all("x=10EB y=1.5e3s z=2.e")?,
+            vec![
+                perfdata("x", 10.0, Some("EB")),
+                perfdata("y", 1500.0, Some("s")),
+                perfdata("z", 2.0, Some("e"))
+            ]
+        );
+        assert_eq!(IcingaPerformanceData::parse_all("  ")?, vec![]);
+        assert_eq!(
+            IcingaPerformanceData::parse_all_lossy(" 'a b'=1  broken c=x d=2% "),
+            vec![
+                perfdata("a b", 1.0, None),
+                IcingaPerformanceData::String("broken".to_owned()),
+                IcingaPerformanceData::String("c=x".to_owned()),
+                perfdata("d", 2.0, Some("%")),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_perfdata_errors() {
+        assert_eq!(
+            IcingaPerformanceData::parse_all("novalue"),
+            Err(PerfDataParseError::MissingValue("novalue".to_owned()))
+        );
+        assert_eq!(
+            IcingaPerformanceData::parse_all("'open=1"),
+            Err(PerfDataParseError::UnterminatedQuote("'open=1".to_owned()))
+        );
+        assert_eq!(
+            IcingaPerformanceData::parse_all("=1"),
+            Err(PerfDataParseError::EmptyLabel)
+        );
+        assert_eq!(
+            IcingaPerformanceData::parse_all("a=U"),
+            Err(PerfDataParseError::InvalidValue {
+                label: "a".to_owned(),
+                value: "U".to_owned()
+            })
+        );
+        for value in ["NaNs", "inf", "infinity", "-Infinity", "1e999", ".", "e5"] {
+            assert_eq!(
+                IcingaPerformanceData::parse_all(&format!("a={value}")),
+                Err(PerfDataParseError::InvalidValue {
+                    label: "a".to_owned(),
+                    value: value.to_owned()
+                })
+            );
+        }
+        assert_eq!(
+            IcingaPerformanceData::parse_all("a=1;;;NaN"),
+            Err(PerfDataParseError::InvalidMinMax {
+                label: "a".to_owned(),
+                value: "NaN".to_owned()
+            })
+        );
+        assert_eq!(
+            IcingaPerformanceData::parse_all("a=1;10:5"),
+            Err(PerfDataParseError::InvalidThreshold {
+                label: "a".to_owned(),
+                value: "10:5".to_owned()
+            })
+        );
+        assert_eq!(
+            IcingaPerformanceData::parse_all("a=1;;;x"),
+            Err(PerfDataParseError::InvalidMinMax {
+                label: "a".to_owned(),
+                value: "x".to_owned()
+            })
+        );
+        assert_eq!(
+            IcingaPerformanceData::parse_all("a=1;;;;;"),
+            Err(PerfDataParseError::TooManyFields("a".to_owned()))
+        );
+        assert_eq!(
+            "a=1 b=2".parse::<IcingaPerformanceData>(),
+            Err(PerfDataParseError::NotExactlyOne(2))
+        );
+    }
+
+    #[test]
+    fn test_format_perfdata() -> Result<(), Box<dyn Error>> {
+        let perfdata =
+            "'time taken'=12.3ms;10;20;0;100 rta=0.5 'it''s'=3c load1=-15;;;0 size=10B;5:20;@~:2";
+        let values = IcingaPerformanceData::parse_all(perfdata)?;
+        assert_eq!(IcingaPerformanceData::format_all(&values), perfdata);
+        assert_eq!("a=1".parse::<IcingaPerformanceData>()?.to_string(), "a=1");
+        Ok(())
+    }
+}
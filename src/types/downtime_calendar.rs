@@ -0,0 +1,433 @@
+//! Upcoming maintenance windows from scheduled downtimes
+//!
+//! Icinga creates downtimes from the ranges of scheduled downtimes shortly
+//! before they start. [IcingaDowntimeCalendar] expands the ranges into the
+//! windows the downtimes will cover (see [crate::types::time_ranges] for the
+//! syntax and its limitations) so they can be reviewed further in advance,
+//! e.g. by exporting them as an iCalendar file.
+//!
+//! For flexible scheduled downtimes the window is the time during which the
+//! downtime can start, the actual downtime may extend beyond it by the
+//! duration.
+
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::types::{
+    monitoring_objects::scheduled_downtime::IcingaScheduledDowntime,
+    names::{IcingaHostName, IcingaServiceFullName, IcingaServiceName},
+    time_ranges::{IcingaTimeRange, IcingaTimeZone, TimeRangeParseError, merge_segments},
+};
+
+/// the product identifier of exported calendars
+const ICALENDAR_PRODUCT_ID: &str = "-//icinga2-api//scheduled downtimes//EN";
+
+/// the maximum length of a line in an iCalendar file in bytes, excluding the
+/// line break
+const ICALENDAR_MAX_LINE_LENGTH: usize = 75;
+
+/// a range of a scheduled downtime can not be parsed
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid range in scheduled downtime {name}: {error}")]
+pub struct ScheduledDowntimeRangeError {
+    /// the full name of the scheduled downtime
+    pub name: String,
+    /// the parse error
+    pub error: TimeRangeParseError,
+}
+
+/// a time span during which a host or service is in a scheduled downtime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcingaDowntimeWindow {
+    /// the host
+    pub host_name: IcingaHostName,
+    /// the service, None for host downtimes
+    pub service_name: Option<IcingaServiceName>,
+    /// the start of the window
+    pub begin: OffsetDateTime,
+    /// the end of the window
+    pub end: OffsetDateTime,
+    /// the full names of the scheduled downtimes covering the window
+    pub scheduled_downtimes: Vec<String>,
+    /// the distinct comments of those scheduled downtimes
+    pub comments: Vec<String>,
+}
+
+impl IcingaDowntimeWindow {
+    /// the host or service in the `host!service` format Icinga uses for
+    /// full names
+    #[must_use]
+    pub fn checkable_name(&self) -> String {
+        IcingaServiceFullName::from_host_and_service(&self.host_name, self.service_name.as_ref())
+            .map_or_else(
+                || self.host_name.to_string(),
+                |full_name| full_name.to_string(),
+            )
+    }
+
+    /// add the scheduled downtimes and comments of another window for the
+    /// same host or service overlapping this one
+    fn merge(&mut self, other: Self) {
+        self.end = self.end.max(other.end);
+        for scheduled_downtime in other.scheduled_downtimes {
+            if !self.scheduled_downtimes.contains(&scheduled_downtime) {
+                self.scheduled_downtimes.push(scheduled_downtime);
+            }
+        }
+        for comment in other.comments {
+            if !self.comments.contains(&comment) {
+                self.comments.push(comment);
+            }
+        }
+    }
+}
+
+/// the windows of scheduled downtimes between two points in time
+///
+/// overlapping and adjacent windows of the same host or service are merged,
+/// the windows are sorted by their start
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IcingaDowntimeCalendar {
+    /// the merged windows
+    windows: Vec<IcingaDowntimeWindow>,
+}
+
+impl IcingaDowntimeCalendar {
+    /// expand the ranges of the scheduled downtimes into the windows between
+    /// `from` and `to`, evaluated in the time zone of the Icinga instance
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if a range of any of the scheduled downtimes can
+    /// not be parsed
+    pub fn from_scheduled_downtimes(
+        scheduled_downtimes: impl IntoIterator<Item = impl Borrow<IcingaScheduledDowntime>>,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+        time_zone: &IcingaTimeZone,
+    ) -> Result<Self, ScheduledDowntimeRangeError> {
+        let mut by_checkable: BTreeMap<
+            (IcingaHostName, Option<IcingaServiceName>),
+            Vec<IcingaDowntimeWindow>,
+        > = BTreeMap::new();
+        for scheduled_downtime in scheduled_downtimes {
+            let scheduled_downtime = scheduled_downtime.borrow();
+            let name = &scheduled_downtime.custom_var.config_object.full_name;
+            let ranges = IcingaTimeRange::parse_all(&scheduled_downtime.ranges).map_err(|e| {
+                ScheduledDowntimeRangeError {
+                    name: name.to_owned(),
+                    error: e,
+                }
+            })?;
+            let segments = merge_segments(
+                ranges
+                    .iter()
+                    .flat_map(|range| range.segments(from, to, time_zone))
+                    .collect(),
+            );
+            // Icinga uses an empty service name for host scheduled downtimes
+            let service_name = scheduled_downtime
+                .service_name
+                .clone()
+                .filter(|service_name| !service_name.0.is_empty());
+            by_checkable
+                .entry((scheduled_downtime.host_name.clone(), service_name.clone()))
+                .or_default()
+                .extend(segments.into_iter().filter_map(|segment| {
+                    Some(IcingaDowntimeWindow {
+                        host_name: scheduled_downtime.host_name.clone(),
+                        service_name: service_name.clone(),
+                        begin: segment.begin.to_offset_date_time()?,
+                        end: segment.end.to_offset_date_time()?,
+                        scheduled_downtimes: vec![name.to_owned()],
+                        comments: vec![scheduled_downtime.comment.clone()],
+                    })
+                }));
+        }
+        let mut windows = Vec::new();
+        for mut checkable_windows in by_checkable.into_values() {
+            checkable_windows.sort_by_key(|window| window.begin);
+            let mut merged: Vec<IcingaDowntimeWindow> = Vec::new();
+            for window in checkable_windows {
+                if let Some(last) = merged.last_mut()
+                    && window.begin <= last.end
+                {
+                    last.merge(window);
+                } else {
+                    merged.push(window);
+                }
+            }
+            windows.extend(merged);
+        }
+        windows.sort_by(|a, b| {
+            (a.begin, &a.host_name, &a.service_name).cmp(&(b.begin, &b.host_name, &b.service_name))
+        });
+        Ok(Self { windows })
+    }
+
+    /// all windows sorted by their start
+    #[must_use]
+    pub fn windows(&self) -> &[IcingaDowntimeWindow] {
+        &self.windows
+    }
+
+    /// the windows of a single host (without its services) or service
+    pub fn windows_for<'a>(
+        &'a self,
+        host_name: &'a IcingaHostName,
+        service_name: Option<&'a IcingaServiceName>,
+    ) -> impl Iterator<Item = &'a IcingaDowntimeWindow> {
+        self.windows.iter().filter(move |window| {
+            window.host_name == *host_name && window.service_name.as_ref() == service_name
+        })
+    }
+
+    /// keep only the windows matching the predicate, e.g. to export the
+    /// windows of some hosts
+    pub fn retain(&mut self, predicate: impl FnMut(&IcingaDowntimeWindow) -> bool) {
+        self.windows.retain(predicate);
+    }
+
+    /// the windows as an iCalendar (RFC 5545) file with one event per window,
+    /// stamped with the current time
+    #[must_use]
+    pub fn to_icalendar(&self) -> String {
+        self.to_icalendar_at(OffsetDateTime::now_utc())
+    }
+
+    /// the windows as an iCalendar (RFC 5545) file with one event per window,
+    /// `dtstamp` is the creation time of the events (`DTSTAMP`)
+    #[must_use]
+    pub fn to_icalendar_at(&self, dtstamp: OffsetDateTime) -> String {
+        let timestamp = format_icalendar_timestamp(dtstamp);
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_owned(),
+            "VERSION:2.0".to_owned(),
+            format!("PRODID:{ICALENDAR_PRODUCT_ID}"),
+            "CALSCALE:GREGORIAN".to_owned(),
+        ];
+        for window in &self.windows {
+            let checkable_name = window.checkable_name();
+            let begin = format_icalendar_timestamp(window.begin);
+            lines.extend([
+                "BEGIN:VEVENT".to_owned(),
+                format!(
+                    "UID:{}",
+                    escape_icalendar_text(&format!("{begin}-{checkable_name}@icinga2-api"))
+                ),
+                format!("DTSTAMP:{timestamp}"),
+                format!("DTSTART:{begin}"),
+                format!("DTEND:{}", format_icalendar_timestamp(window.end)),
+                format!(
+                    "SUMMARY:{}",
+                    escape_icalendar_text(&format!("Downtime {checkable_name}"))
+                ),
+            ]);
+            let description = window
+                .comments
+                .iter()
+                .filter(|comment| !comment.is_empty())
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join("\n");
+            if !description.is_empty() {
+                lines.push(format!(
+                    "DESCRIPTION:{}",
+                    escape_icalendar_text(&description)
+                ));
+            }
+            lines.push("END:VEVENT".to_owned());
+        }
+        lines.push("END:VCALENDAR".to_owned());
+        let mut calendar = String::new();
+        for line in lines {
+            fold_icalendar_line(&mut calendar, &line);
+        }
+        calendar
+    }
+}
+
+/// format a point in time as an iCalendar UTC date-time
+fn format_icalendar_timestamp(timestamp: OffsetDateTime) -> String {
+    let timestamp = timestamp.to_offset(UtcOffset::UTC);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        timestamp.year(),
+        u8::from(timestamp.month()),
+        timestamp.day(),
+        timestamp.hour(),
+        timestamp.minute(),
+        timestamp.second()
+    )
+}
+
+/// escape a text value for an iCalendar property
+fn escape_icalendar_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// append a content line to the calendar, folding it into several lines of
+/// at most [ICALENDAR_MAX_LINE_LENGTH] bytes without splitting characters
+fn fold_icalendar_line(calendar: &mut String, line: &str) {
+    let mut length = 0usize;
+    for c in line.chars() {
+        if length.saturating_add(c.len_utf8()) > ICALENDAR_MAX_LINE_LENGTH {
+            calendar.push_str("\r\n ");
+            // the leading space of a continuation line counts towards its length
+            length = 1;
+        }
+        calendar.push(c);
+        length = length.saturating_add(c.len_utf8());
+    }
+    calendar.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+    use time::macros::datetime;
+
+    use crate::testing::fixtures;
+
+    #[test]
+    fn test_downtime_calendar() -> Result<(), Box<dyn Error>> {
+        let scheduled_downtimes: Vec<IcingaScheduledDowntime> = [
+            fixtures::scheduled_downtime(
+                "db1",
+                None,
+                "os-updates",
+                serde_json::json!({ "sunday": "02:00-04:00" }),
+                "OS updates",
+            ),
+            fixtures::scheduled_downtime(
+                "db1",
+                None,
+                "backup",
+                serde_json::json!({ "sunday": "03:00-05:00", "saturday": "23:00-02:00" }),
+                "nightly backup; disks are slow",
+            ),
+            fixtures::scheduled_downtime(
+                "db1",
+                Some("mysql"),
+                "restart",
+                serde_json::json!({ "day 1": "22:00-23:00" }),
+                "",
+            ),
+        ]
+        .into_iter()
+        .map(serde_json::from_value)
+        .collect::<Result<_, _>>()?;
+        let calendar = IcingaDowntimeCalendar::from_scheduled_downtimes(
+            &scheduled_downtimes,
+            datetime!(2024-11-29 00:00 UTC),
+            datetime!(2024-12-09 00:00 UTC),
+            &IcingaTimeZone::fixed(UtcOffset::UTC),
+        )?;
+        assert_eq!(
+            calendar
+                .windows()
+                .iter()
+                .map(|window| (window.checkable_name(), window.begin, window.end))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "db1".to_owned(),
+                    datetime!(2024-11-30 23:00 UTC),
+                    datetime!(2024-12-01 05:00 UTC)
+                ),
+                (
+                    "db1!mysql".to_owned(),
+                    datetime!(2024-12-01 22:00 UTC),
+                    datetime!(2024-12-01 23:00 UTC)
+                ),
+                (
+                    "db1".to_owned(),
+                    datetime!(2024-12-07 23:00 UTC),
+                    datetime!(2024-12-08 05:00 UTC)
+                ),
+            ]
+        );
+        let host_name = IcingaHostName("db1".to_owned());
+        let first = calendar
+            .windows_for(&host_name, None)
+            .next()
+            .ok_or("missing host window")?;
+        assert_eq!(
+            first.scheduled_downtimes,
+            vec!["db1!backup".to_owned(), "db1!os-updates".to_owned()]
+        );
+
+        let mut calendar = calendar;
+        calendar.retain(|window| window.service_name.is_none());
+        assert_eq!(
+            calendar.to_icalendar_at(datetime!(2024-11-28 12:00 UTC)),
+            [
+                "BEGIN:VCALENDAR",
+                "VERSION:2.0",
+                "PRODID:-//icinga2-api//scheduled downtimes//EN",
+                "CALSCALE:GREGORIAN",
+                "BEGIN:VEVENT",
+                "UID:20241130T230000Z-db1@icinga2-api",
+                "DTSTAMP:20241128T120000Z",
+                "DTSTART:20241130T230000Z",
+                "DTEND:20241201T050000Z",
+                "SUMMARY:Downtime db1",
+                "DESCRIPTION:nightly backup\\; disks are slow\\nOS updates",
+                "END:VEVENT",
+                "BEGIN:VEVENT",
+                "UID:20241207T230000Z-db1@icinga2-api",
+                "DTSTAMP:20241128T120000Z",
+                "DTSTART:20241207T230000Z",
+                "DTEND:20241208T050000Z",
+                "SUMMARY:Downtime db1",
+                "DESCRIPTION:nightly backup\\; disks are slow\\nOS updates",
+                "END:VEVENT",
+                "END:VCALENDAR",
+                "",
+            ]
+            .join("\r\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_fold_icalendar_line() {
+        let mut calendar = String::new();
+        let line = format!("DESCRIPTION:{}", "ä".repeat(40));
+        fold_icalendar_line(&mut calendar, &line);
+        let lines = calendar.split("\r\n").collect::<Vec<_>>();
+        assert!(
+            lines
+                .iter()
+                .all(|line| line.len() <= ICALENDAR_MAX_LINE_LENGTH)
+        );
+        assert_eq!(
+            lines
+                .iter()
+                .enumerate()
+                .map(|(index, line)| if index == 0 {
+                    *line
+                } else {
+                    line.strip_prefix(' ').unwrap_or(line)
+                })
+                .collect::<String>(),
+            line
+        );
+    }
+}
@@ -6,21 +6,17 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::serde::{deserialize_icinga_timestamp, serialize_icinga_timestamp};
 use crate::types::enums::acknowledgement_type::IcingaAcknowledgementType;
 use crate::types::enums::host_or_service_state::IcingaHostOrServiceState;
 use crate::types::enums::state_type::IcingaStateType;
 use crate::types::names::{IcingaHostName, IcingaServiceName};
+use crate::types::timestamp::IcingaTimestamp;
 
 /// the AcknowledgementCleared event type
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct IcingaEventAcknowledgementCleared {
     /// when the event happened
-    #[serde(
-        serialize_with = "serialize_icinga_timestamp",
-        deserialize_with = "deserialize_icinga_timestamp"
-    )]
-    pub timestamp: time::OffsetDateTime,
+    pub timestamp: IcingaTimestamp,
     /// host on which  the event happened
     pub host: IcingaHostName,
     /// service for which the event happened, if not specified this is a host event
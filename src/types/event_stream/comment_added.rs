@@ -6,18 +6,14 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::serde::{deserialize_icinga_timestamp, serialize_icinga_timestamp};
 use crate::types::runtime_objects::comment::IcingaComment;
+use crate::types::timestamp::IcingaTimestamp;
 
 /// the CommentAdded event type
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct IcingaEventCommentAdded {
     /// when the event happened
-    #[serde(
-        serialize_with = "serialize_icinga_timestamp",
-        deserialize_with = "deserialize_icinga_timestamp"
-    )]
-    pub timestamp: time::OffsetDateTime,
+    pub timestamp: IcingaTimestamp,
     /// the comment this notification is about
     pub comment: IcingaComment,
 }
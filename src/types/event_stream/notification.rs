@@ -6,22 +6,18 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::serde::{deserialize_icinga_timestamp, serialize_icinga_timestamp};
 use crate::types::common::check_result::IcingaCheckResult;
 use crate::types::enums::notification_type::IcingaNotificationType;
 use crate::types::names::{
     IcingaHostName, IcingaNotificationCommandName, IcingaServiceName, IcingaUserName,
 };
+use crate::types::timestamp::IcingaTimestamp;
 
 /// the Notification event type
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct IcingaEventNotification {
     /// when the event happened
-    #[serde(
-        serialize_with = "serialize_icinga_timestamp",
-        deserialize_with = "deserialize_icinga_timestamp"
-    )]
-    pub timestamp: time::OffsetDateTime,
+    pub timestamp: IcingaTimestamp,
     /// host on which  the event happened
     pub host: IcingaHostName,
     /// service for which the event happened, if not specified this is a host event
@@ -4,18 +4,14 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::serde::{deserialize_icinga_timestamp, serialize_icinga_timestamp};
 use crate::types::enums::object_type::IcingaObjectType;
+use crate::types::timestamp::IcingaTimestamp;
 
 /// the ObjectCreated event type
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct IcingaEventObjectCreated {
     /// when the event happened
-    #[serde(
-        serialize_with = "serialize_icinga_timestamp",
-        deserialize_with = "deserialize_icinga_timestamp"
-    )]
-    pub timestamp: time::OffsetDateTime,
+    pub timestamp: IcingaTimestamp,
     /// the object type of the object that changed
     pub object_type: IcingaObjectType,
     /// the object name of the object that changed
@@ -8,13 +8,13 @@ use serde::{Deserialize, Serialize};
 
 use thiserror::Error;
 
-use crate::serde::{deserialize_icinga_timestamp, serialize_icinga_timestamp};
 use crate::types::common::check_result::IcingaCheckResult;
 use crate::types::enums::host_or_service_state::IcingaHostOrServiceState;
 use crate::types::enums::host_state::IcingaHostState;
 use crate::types::enums::service_state::IcingaServiceState;
 use crate::types::enums::state_type::IcingaStateType;
 use crate::types::names::{IcingaHostName, IcingaServiceName};
+use crate::types::timestamp::IcingaTimestamp;
 
 /// the StateChange event type
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -24,11 +24,7 @@ use crate::types::names::{IcingaHostName, IcingaServiceName};
 )]
 pub struct IcingaEventStateChange {
     /// when the event happened
-    #[serde(
-        serialize_with = "serialize_icinga_timestamp",
-        deserialize_with = "deserialize_icinga_timestamp"
-    )]
-    pub timestamp: time::OffsetDateTime,
+    pub timestamp: IcingaTimestamp,
     /// host on which  the event happened
     pub host: IcingaHostName,
     /// service for which the event happened, if not specified this is a host event
@@ -95,11 +91,7 @@ impl TryFrom<IcingaEventStateChangeNumericState> for IcingaEventStateChange {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct IcingaEventStateChangeNumericState {
     /// when the event happened
-    #[serde(
-        serialize_with = "serialize_icinga_timestamp",
-        deserialize_with = "deserialize_icinga_timestamp"
-    )]
-    pub timestamp: time::OffsetDateTime,
+    pub timestamp: IcingaTimestamp,
     /// host on which  the event happened
     pub host: IcingaHostName,
     /// service for which the event happened, if not specified this is a host event
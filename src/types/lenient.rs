@@ -0,0 +1,413 @@
+//! Forward compatible (lenient) deserialization
+//!
+//! By default attributes newer Icinga versions add are silently dropped and
+//! attributes missing from a response are a hard parse failure. Lenient
+//! deserialization is opt-in, either via the `rest_lenient` methods of the
+//! clients or by calling [LenientDeserialize::deserialize_lenient] directly.
+//! It
+//!
+//! - fills in defaults for missing non-essential attributes
+//! - captures unknown attributes in the `extra` field of [IcingaHost],
+//!   [IcingaService] and [IcingaCheckable]
+//! - records both in a [SchemaDriftReport]
+//!
+//! Attributes identifying an object (like its name or type) or describing
+//! its current state are essential and still required.
+
+use std::collections::BTreeMap;
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::types::{
+    common::checkable::IcingaCheckable,
+    monitoring_objects::{host::IcingaHost, service::IcingaService},
+    query::{QueryResultObject, QueryResultObjectWithJoins, ResultsWrapper},
+};
+
+/// the differences between the attributes in responses and the ones modelled
+/// by this crate found during lenient deserialization
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaDriftReport {
+    /// attributes seen but not modelled, with the number of objects they were seen on
+    pub unknown: BTreeMap<String, usize>,
+    /// attributes modelled but missing and filled in with defaults, with the
+    /// number of objects they were missing on
+    pub missing: BTreeMap<String, usize>,
+}
+
+impl SchemaDriftReport {
+    /// true if responses matched the modelled attributes exactly
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.unknown.is_empty() && self.missing.is_empty()
+    }
+
+    /// add the counts from another report to this one
+    pub fn merge(&mut self, other: &Self) {
+        for (attribute, count) in &other.unknown {
+            increment(&mut self.unknown, attribute, *count);
+        }
+        for (attribute, count) in &other.missing {
+            increment(&mut self.missing, attribute, *count);
+        }
+    }
+}
+
+/// add to the count of an attribute in a report
+fn increment(counts: &mut BTreeMap<String, usize>, attribute: &str, by: usize) {
+    let count = counts.entry(attribute.to_owned()).or_insert(0);
+    *count = count.saturating_add(by);
+}
+
+/// a type that can be deserialized leniently
+pub trait LenientDeserialize: Sized {
+    /// deserialize from a JSON value, filling in defaults for missing
+    /// non-essential attributes and capturing unknown ones
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the value can not be deserialized even with
+    /// the defaults filled in, e.g. because an essential attribute is missing
+    fn deserialize_lenient(
+        value: serde_json::Value,
+        report: &mut SchemaDriftReport,
+    ) -> Result<Self, crate::error::Error>;
+}
+
+/// deserialize an object from its attributes leniently
+///
+/// `defaults` are inserted for missing attributes, attributes that do not
+/// appear when the deserialized object is serialized again are unknown and
+/// stored in the map returned by `extra`
+///
+/// # Errors
+///
+/// this returns an error if the object can not be deserialized
+fn deserialize_object_lenient<T>(
+    mut value: serde_json::Value,
+    defaults: Vec<(&'static str, serde_json::Value)>,
+    extra: impl FnOnce(&mut T) -> &mut BTreeMap<String, serde_json::Value>,
+    report: &mut SchemaDriftReport,
+) -> Result<T, crate::error::Error>
+where
+    T: Serialize + DeserializeOwned,
+{
+    if let Some(attributes) = value.as_object_mut() {
+        for (attribute, default) in defaults {
+            if !attributes.contains_key(attribute) {
+                tracing::debug!("attribute {} missing, using default {}", attribute, default);
+                attributes.insert(attribute.to_owned(), default);
+                increment(&mut report.missing, attribute, 1);
+            }
+        }
+    }
+    let mut object: T = serde_path_to_error::deserialize(&value)?;
+    let known = serde_path_to_error::serialize(&object, serde_json::value::Serializer)?;
+    let unknown: BTreeMap<String, serde_json::Value> = match value {
+        serde_json::Value::Object(attributes) => attributes
+            .into_iter()
+            .filter(|(attribute, _)| known.get(attribute).is_none())
+            .collect(),
+        _ => BTreeMap::new(),
+    };
+    for attribute in unknown.keys() {
+        tracing::debug!("attribute {} is not modelled", attribute);
+        increment(&mut report.unknown, attribute, 1);
+    }
+    *extra(&mut object) = unknown;
+    Ok(object)
+}
+
+/// defaults for the non-essential attributes of checkable objects
+/// including those of config objects and custom var objects
+///
+/// missing timestamps default to `null` so they are unset instead of
+/// pretending something happened at the start of the epoch
+fn checkable_defaults() -> Vec<(&'static str, serde_json::Value)> {
+    vec![
+        ("vars", serde_json::Value::Null),
+        ("active", serde_json::json!(true)),
+        ("ha_mode", serde_json::json!(0)),
+        ("original_attributes", serde_json::Value::Null),
+        ("package", serde_json::json!("_etc")),
+        ("paused", serde_json::json!(false)),
+        (
+            "source_location",
+            serde_json::json!({
+                "path": "",
+                "first_line": 0,
+                "first_column": 0,
+                "last_line": 0,
+                "last_column": 0,
+            }),
+        ),
+        ("templates", serde_json::json!([])),
+        ("version", serde_json::json!(0)),
+        ("zone", serde_json::json!("")),
+        ("acknowledgement", serde_json::json!(0)),
+        ("acknowledgement_expiry", serde_json::Value::Null),
+        ("acknowledgement_last_change", serde_json::Value::Null),
+        ("action_url", serde_json::json!("")),
+        ("check_attempt", serde_json::json!(1)),
+        ("check_period", serde_json::json!("")),
+        ("command_endpoint", serde_json::json!("")),
+        ("downtime_depth", serde_json::json!(0)),
+        ("enable_active_checks", serde_json::json!(true)),
+        ("enable_event_handler", serde_json::json!(true)),
+        ("enable_flapping", serde_json::json!(false)),
+        ("enable_notifications", serde_json::json!(true)),
+        ("enable_passive_checks", serde_json::json!(true)),
+        ("enable_perfdata", serde_json::json!(true)),
+        ("event_command", serde_json::json!("")),
+        ("flapping", serde_json::json!(false)),
+        ("flapping_current", serde_json::json!(0)),
+        ("flapping_last_change", serde_json::Value::Null),
+        ("flapping_threshold", serde_json::json!(0)),
+        ("flapping_threshold_low", serde_json::json!(25)),
+        ("flapping_threshold_high", serde_json::json!(30)),
+        ("force_next_check", serde_json::json!(false)),
+        ("force_next_notification", serde_json::json!(false)),
+        ("handled", serde_json::json!(false)),
+        ("icon_image", serde_json::json!("")),
+        ("icon_image_alt", serde_json::json!("")),
+        ("last_check", serde_json::Value::Null),
+        ("last_hard_state_change", serde_json::Value::Null),
+        ("last_reachable", serde_json::json!(true)),
+        ("last_state_change", serde_json::Value::Null),
+        ("last_state_type", serde_json::json!(1)),
+        ("last_state_unreachable", serde_json::Value::Null),
+        ("max_check_attempts", serde_json::json!(3)),
+        ("next_check", serde_json::Value::Null),
+        ("next_update", serde_json::Value::Null),
+        ("notes", serde_json::json!("")),
+        ("notes_url", serde_json::json!("")),
+        ("previous_state_change", serde_json::Value::Null),
+        ("problem", serde_json::json!(false)),
+        ("severity", serde_json::json!(0)),
+        ("state_type", serde_json::json!(1)),
+        ("volatile", serde_json::json!(false)),
+    ]
+}
+
+impl LenientDeserialize for IcingaCheckable {
+    fn deserialize_lenient(
+        value: serde_json::Value,
+        report: &mut SchemaDriftReport,
+    ) -> Result<Self, crate::error::Error> {
+        deserialize_object_lenient(
+            value,
+            checkable_defaults(),
+            |checkable: &mut Self| &mut checkable.extra,
+            report,
+        )
+    }
+}
+
+impl LenientDeserialize for IcingaHost {
+    fn deserialize_lenient(
+        value: serde_json::Value,
+        report: &mut SchemaDriftReport,
+    ) -> Result<Self, crate::error::Error> {
+        let mut defaults = checkable_defaults();
+        defaults.extend([
+            ("address6", serde_json::json!("")),
+            ("display_name", serde_json::json!("")),
+            ("groups", serde_json::json!([])),
+            ("last_hard_state", serde_json::json!(0)),
+            ("last_state", serde_json::json!(0)),
+            ("last_state_down", serde_json::Value::Null),
+            ("last_state_up", serde_json::Value::Null),
+        ]);
+        deserialize_object_lenient(value, defaults, |host: &mut Self| &mut host.extra, report)
+    }
+}
+
+impl LenientDeserialize for IcingaService {
+    fn deserialize_lenient(
+        value: serde_json::Value,
+        report: &mut SchemaDriftReport,
+    ) -> Result<Self, crate::error::Error> {
+        let mut defaults = checkable_defaults();
+        defaults.extend([
+            ("display_name", serde_json::json!("")),
+            ("groups", serde_json::json!([])),
+            ("last_hard_state", serde_json::json!(0)),
+            ("last_state", serde_json::json!(0)),
+            ("last_state_critical", serde_json::Value::Null),
+            ("last_state_ok", serde_json::Value::Null),
+            ("last_state_unknown", serde_json::Value::Null),
+            ("last_state_warning", serde_json::Value::Null),
+        ]);
+        deserialize_object_lenient(
+            value,
+            defaults,
+            |service: &mut Self| &mut service.extra,
+            report,
+        )
+    }
+}
+
+/// remove the attrs from a query result so the rest of it can be deserialized
+/// strictly
+fn take_attrs(value: &mut serde_json::Value) -> serde_json::Value {
+    value
+        .as_object_mut()
+        .and_then(|result| result.insert("attrs".to_owned(), serde_json::Value::Null))
+        .unwrap_or_default()
+}
+
+impl<Obj> LenientDeserialize for QueryResultObject<Obj>
+where
+    Obj: LenientDeserialize,
+{
+    fn deserialize_lenient(
+        mut value: serde_json::Value,
+        report: &mut SchemaDriftReport,
+    ) -> Result<Self, crate::error::Error> {
+        let attrs = take_attrs(&mut value);
+        let result: QueryResultObject<()> = serde_path_to_error::deserialize(value)?;
+        Ok(Self {
+            attrs: Obj::deserialize_lenient(attrs, report)?,
+            meta: result.meta,
+            name: result.name,
+            object_type: result.object_type,
+        })
+    }
+}
+
+impl<Obj, ObjJoins> LenientDeserialize for QueryResultObjectWithJoins<Obj, ObjJoins>
+where
+    Obj: LenientDeserialize,
+    ObjJoins: DeserializeOwned,
+{
+    fn deserialize_lenient(
+        mut value: serde_json::Value,
+        report: &mut SchemaDriftReport,
+    ) -> Result<Self, crate::error::Error> {
+        let attrs = take_attrs(&mut value);
+        let result: QueryResultObjectWithJoins<(), ObjJoins> =
+            serde_path_to_error::deserialize(value)?;
+        Ok(Self {
+            attrs: Obj::deserialize_lenient(attrs, report)?,
+            joins: result.joins,
+            meta: result.meta,
+            name: result.name,
+            object_type: result.object_type,
+        })
+    }
+}
+
+impl<T> LenientDeserialize for ResultsWrapper<T>
+where
+    T: LenientDeserialize,
+{
+    fn deserialize_lenient(
+        value: serde_json::Value,
+        report: &mut SchemaDriftReport,
+    ) -> Result<Self, crate::error::Error> {
+        let wrapper: ResultsWrapper<serde_json::Value> = serde_path_to_error::deserialize(value)?;
+        Ok(Self {
+            results: wrapper
+                .results
+                .into_iter()
+                .map(|result| T::deserialize_lenient(result, report))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl<T> LenientDeserialize for Option<T>
+where
+    T: LenientDeserialize,
+{
+    fn deserialize_lenient(
+        value: serde_json::Value,
+        report: &mut SchemaDriftReport,
+    ) -> Result<Self, crate::error::Error> {
+        if value.is_null() {
+            Ok(None)
+        } else {
+            T::deserialize_lenient(value, report).map(Some)
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "blocking")]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+    use tracing_test::traced_test;
+
+    use crate::{
+        api::{blocking::Icinga2, query::monitoring_objects::host::ListHosts},
+        types::{
+            enums::object_type::IcingaObjectType, join_types::host::IcingaHostJoins,
+            timestamp::IcingaTimestamp,
+        },
+    };
+
+    #[traced_test]
+    #[test]
+    fn test_rest_lenient() -> Result<(), Box<dyn Error>> {
+        let fake = crate::testing::FakeIcinga::start()?;
+        let mut attrs = crate::testing::fixtures::host("drifted-host");
+        let attributes = attrs
+            .as_object_mut()
+            .ok_or("host fixture is not an object")?;
+        attributes.remove("notes");
+        attributes.remove("groups");
+        attributes.remove("last_state_up");
+        attributes.insert("new_attribute".to_owned(), serde_json::json!({"a": 1}));
+        fake.insert_object(IcingaObjectType::Host, "drifted-host", attrs);
+        let icinga2 = Icinga2::from_instance_config(&fake.instance_config())?;
+        let strict: Result<
+            ResultsWrapper<QueryResultObjectWithJoins<IcingaHost, IcingaHostJoins>>,
+            _,
+        > = icinga2.rest(ListHosts::builder().build()?);
+        assert!(matches!(
+            strict,
+            Err(crate::error::Error::SerdeJsonError(_))
+        ));
+        let (response, report): (
+            ResultsWrapper<QueryResultObjectWithJoins<IcingaHost, IcingaHostJoins>>,
+            _,
+        ) = icinga2.rest_lenient(ListHosts::builder().build()?)?;
+        let host = response.results.first().ok_or("no host returned")?;
+        assert_eq!(host.attrs.checkable.notes, None);
+        assert!(host.attrs.groups.is_empty());
+        assert_eq!(host.attrs.last_state_up, IcingaTimestamp::UNSET);
+        assert_eq!(
+            host.attrs.extra.get("new_attribute"),
+            Some(&serde_json::json!({"a": 1}))
+        );
+        assert_eq!(
+            report,
+            SchemaDriftReport {
+                unknown: BTreeMap::from([("new_attribute".to_owned(), 1)]),
+                missing: BTreeMap::from([
+                    ("groups".to_owned(), 1),
+                    ("last_state_up".to_owned(), 1),
+                    ("notes".to_owned(), 1)
+                ]),
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lenient_essential_attribute() -> Result<(), Box<dyn Error>> {
+        let mut attrs = crate::testing::fixtures::host("no-state");
+        attrs
+            .as_object_mut()
+            .ok_or("host fixture is not an object")?
+            .remove("state");
+        let mut report = SchemaDriftReport::default();
+        assert!(matches!(
+            IcingaHost::deserialize_lenient(attrs, &mut report),
+            Err(crate::error::Error::SerdeJsonError(_))
+        ));
+        Ok(())
+    }
+}
@@ -32,14 +32,10 @@ pub struct IcingaMetadata {
 }
 
 /// shared code for all handlers that have a meta parameter
-pub(crate) fn add_meta_to_url(
-    url: &mut url::Url,
-    meta: &[IcingaMetadataType],
-) -> Result<(), crate::error::Error> {
+pub(crate) fn add_meta_to_url(url: &mut url::Url, meta: &[IcingaMetadataType]) {
     if !meta.is_empty() {
         for v in meta {
             url.query_pairs_mut().append_pair("meta", &v.to_string());
         }
     }
-    Ok(())
 }
@@ -6,6 +6,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::types::attributes::IcingaAttribute;
+use crate::types::common::config_object::IcingaConfigObjectPartial;
 use crate::types::{
     common::{config_object::IcingaConfigObject, function::IcingaFunction},
     enums::object_type::IcingaObjectType,
@@ -40,3 +42,76 @@ pub struct IcingaApiUser {
     /// permissions
     pub permissions: Option<Vec<IcingaPermissionEntry>>,
 }
+
+/// the attributes of [IcingaApiUser] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IcingaApiUserPartial {
+    /// type of icinga object, should always be ApiUser for this
+    #[serde(rename = "type")]
+    pub object_type: Option<IcingaObjectType>,
+    /// shared config object fields
+    #[serde(flatten)]
+    pub config_object: IcingaConfigObjectPartial,
+    /// client CN
+    pub client_cn: Option<String>,
+    /// permissions
+    pub permissions: Option<Vec<IcingaPermissionEntry>>,
+}
+
+/// the attributes of [IcingaApiUser] that can be requested with the attrs parameter of a query
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IcingaApiUserAttribute {
+    /// type of icinga object, should always be ApiUser for this
+    ObjectType,
+    /// full object name
+    FullName,
+    /// short object name
+    Name,
+    /// object is active (being checked)
+    Active,
+    /// whether to run a check once or everywhere
+    HaMode,
+    /// original values of object attributes modified at runtime
+    OriginalAttributes,
+    /// configuration package name this object belongs to, _etc for local configuration
+    /// _api for runtime created objects
+    Package,
+    /// object has been paused at runtime
+    Paused,
+    /// location information whether the configuration files are stored
+    SourceLocation,
+    /// templates imported on object compilation
+    Templates,
+    /// timestamp when the object was created or modified. syncred throughout cluster nodes
+    Version,
+    /// the zone this object is a member of
+    Zone,
+    /// client CN
+    ClientCn,
+    /// permissions
+    Permissions,
+}
+
+impl IcingaAttribute for IcingaApiUserAttribute {}
+
+impl std::fmt::Display for IcingaApiUserAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ObjectType => write!(f, "type"),
+            Self::FullName => write!(f, "__name"),
+            Self::Name => write!(f, "name"),
+            Self::Active => write!(f, "active"),
+            Self::HaMode => write!(f, "ha_mode"),
+            Self::OriginalAttributes => write!(f, "original_attributes"),
+            Self::Package => write!(f, "package"),
+            Self::Paused => write!(f, "paused"),
+            Self::SourceLocation => write!(f, "source_location"),
+            Self::Templates => write!(f, "templates"),
+            Self::Version => write!(f, "version"),
+            Self::Zone => write!(f, "zone"),
+            Self::ClientCn => write!(f, "client_cn"),
+            Self::Permissions => write!(f, "permissions"),
+        }
+    }
+}
@@ -9,7 +9,9 @@ use serde::{Deserialize, Serialize};
 use crate::serde::{
     deserialize_empty_string_or_parse, serialize_none_as_empty_string_or_to_string,
 };
+use crate::types::attributes::IcingaAttribute;
 use crate::types::common::custom_var_object::CustomVarHolder;
+use crate::types::common::custom_var_object::IcingaCustomVarObjectPartial;
 use crate::types::names::IcingaTimePeriodName;
 use crate::types::{
     common::custom_var_object::IcingaCustomVarObject,
@@ -59,3 +61,124 @@ impl CustomVarHolder for IcingaDependency {
         self.custom_var.custom_var_value(name)
     }
 }
+
+/// the attributes of [IcingaDependency] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IcingaDependencyPartial {
+    /// type of icinga object, should always be Dependency for this
+    #[serde(rename = "type")]
+    pub object_type: Option<IcingaObjectType>,
+    /// shared config object and custom variable fields
+    #[serde(flatten)]
+    pub custom_var: IcingaCustomVarObjectPartial,
+    /// the child host name
+    pub child_host_name: Option<IcingaHostName>,
+    /// the child service name
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string_or_to_string",
+        deserialize_with = "deserialize_empty_string_or_parse"
+    )]
+    pub child_service_name: Option<IcingaServiceName>,
+    /// the parent host name
+    pub parent_host_name: Option<IcingaHostName>,
+    /// the parent service name
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string_or_to_string",
+        deserialize_with = "deserialize_empty_string_or_parse"
+    )]
+    pub parent_service_name: Option<IcingaServiceName>,
+    /// whether checks are disabled by this dependency
+    pub disable_checks: Option<bool>,
+    /// whether notifications are disabled by this dependency
+    pub disable_notifications: Option<bool>,
+    /// whether this dependency ignores soft states
+    pub ignore_soft_states: Option<bool>,
+    /// the name of the time period when this dependency is active
+    pub period: Option<IcingaTimePeriodName>,
+    /// states when this dependency is enabled
+    pub states: Option<Vec<IcingaHostOrServiceState>>,
+}
+
+/// the attributes of [IcingaDependency] that can be requested with the attrs parameter of a query
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IcingaDependencyAttribute {
+    /// type of icinga object, should always be Dependency for this
+    ObjectType,
+    /// custom variables specific to this object
+    Vars,
+    /// full object name
+    FullName,
+    /// short object name
+    Name,
+    /// object is active (being checked)
+    Active,
+    /// whether to run a check once or everywhere
+    HaMode,
+    /// original values of object attributes modified at runtime
+    OriginalAttributes,
+    /// configuration package name this object belongs to, _etc for local configuration
+    /// _api for runtime created objects
+    Package,
+    /// object has been paused at runtime
+    Paused,
+    /// location information whether the configuration files are stored
+    SourceLocation,
+    /// templates imported on object compilation
+    Templates,
+    /// timestamp when the object was created or modified. syncred throughout cluster nodes
+    Version,
+    /// the zone this object is a member of
+    Zone,
+    /// the child host name
+    ChildHostName,
+    /// the child service name
+    ChildServiceName,
+    /// the parent host name
+    ParentHostName,
+    /// the parent service name
+    ParentServiceName,
+    /// whether checks are disabled by this dependency
+    DisableChecks,
+    /// whether notifications are disabled by this dependency
+    DisableNotifications,
+    /// whether this dependency ignores soft states
+    IgnoreSoftStates,
+    /// the name of the time period when this dependency is active
+    Period,
+    /// states when this dependency is enabled
+    States,
+}
+
+impl IcingaAttribute for IcingaDependencyAttribute {}
+
+impl std::fmt::Display for IcingaDependencyAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ObjectType => write!(f, "type"),
+            Self::Vars => write!(f, "vars"),
+            Self::FullName => write!(f, "__name"),
+            Self::Name => write!(f, "name"),
+            Self::Active => write!(f, "active"),
+            Self::HaMode => write!(f, "ha_mode"),
+            Self::OriginalAttributes => write!(f, "original_attributes"),
+            Self::Package => write!(f, "package"),
+            Self::Paused => write!(f, "paused"),
+            Self::SourceLocation => write!(f, "source_location"),
+            Self::Templates => write!(f, "templates"),
+            Self::Version => write!(f, "version"),
+            Self::Zone => write!(f, "zone"),
+            Self::ChildHostName => write!(f, "child_host_name"),
+            Self::ChildServiceName => write!(f, "child_service_name"),
+            Self::ParentHostName => write!(f, "parent_host_name"),
+            Self::ParentServiceName => write!(f, "parent_service_name"),
+            Self::DisableChecks => write!(f, "disable_checks"),
+            Self::DisableNotifications => write!(f, "disable_notifications"),
+            Self::IgnoreSoftStates => write!(f, "ignore_soft_states"),
+            Self::Period => write!(f, "period"),
+            Self::States => write!(f, "states"),
+        }
+    }
+}
@@ -11,6 +11,8 @@ use crate::serde::{
     deserialize_optional_seconds_as_duration, serialize_none_as_empty_string,
     serialize_optional_duration_as_seconds, serialize_optional_icinga_timestamp,
 };
+use crate::types::attributes::IcingaAttribute;
+use crate::types::common::config_object::IcingaConfigObjectPartial;
 use crate::types::{
     common::config_object::IcingaConfigObject, enums::object_type::IcingaObjectType,
 };
@@ -81,3 +83,176 @@ pub struct IcingaEndpoint {
     /// bandwidth used receiving on this endpoint
     pub bytes_received_per_second: Option<f64>,
 }
+
+/// the attributes of [IcingaEndpoint] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct IcingaEndpointPartial {
+    /// type of icinga object, should always be Endpoint for this
+    #[serde(rename = "type")]
+    pub object_type: Option<IcingaObjectType>,
+    /// shared config object fields
+    #[serde(flatten)]
+    pub config_object: IcingaConfigObjectPartial,
+    /// the host to connect to
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string",
+        deserialize_with = "deserialize_empty_string_or_string"
+    )]
+    pub host: Option<String>,
+    /// the port to connect to
+    pub port: Option<String>,
+    /// how long to keep the replay logs on connection loss, disabled if set to 0
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_duration_as_seconds",
+        deserialize_with = "deserialize_optional_seconds_as_duration"
+    )]
+    pub log_duration: Option<time::Duration>,
+    /// log position on the local side
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_icinga_timestamp",
+        deserialize_with = "deserialize_optional_icinga_timestamp"
+    )]
+    pub local_log_position: Option<time::OffsetDateTime>,
+    /// log position on the remote side
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_icinga_timestamp",
+        deserialize_with = "deserialize_optional_icinga_timestamp"
+    )]
+    pub remote_log_position: Option<time::OffsetDateTime>,
+    /// remote icinga version
+    pub icinga_version: Option<u64>,
+    /// remote icinga capabilities
+    pub capabilities: Option<u64>,
+    /// is this endpoint currently connecting
+    pub connecting: Option<bool>,
+    /// is this endpoint currently syncing
+    pub syncing: Option<bool>,
+    /// is this endpoint currently connected
+    pub connected: Option<bool>,
+    /// time when the last message was sent to this endpoint
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_icinga_timestamp",
+        deserialize_with = "deserialize_optional_icinga_timestamp"
+    )]
+    pub last_message_sent: Option<time::OffsetDateTime>,
+    /// time when the last message was received from this endpoint
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_icinga_timestamp",
+        deserialize_with = "deserialize_optional_icinga_timestamp"
+    )]
+    pub last_message_received: Option<time::OffsetDateTime>,
+    /// frequency of message sending on this endpoint
+    pub messages_send_per_second: Option<f64>,
+    /// frequency of message reception on this endpoint
+    pub messages_received_per_second: Option<f64>,
+    /// bandwidth used sending on this endpoint
+    pub bytes_sent_per_second: Option<f64>,
+    /// bandwidth used receiving on this endpoint
+    pub bytes_received_per_second: Option<f64>,
+}
+
+/// the attributes of [IcingaEndpoint] that can be requested with the attrs parameter of a query
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IcingaEndpointAttribute {
+    /// type of icinga object, should always be Endpoint for this
+    ObjectType,
+    /// full object name
+    FullName,
+    /// short object name
+    Name,
+    /// object is active (being checked)
+    Active,
+    /// whether to run a check once or everywhere
+    HaMode,
+    /// original values of object attributes modified at runtime
+    OriginalAttributes,
+    /// configuration package name this object belongs to, _etc for local configuration
+    /// _api for runtime created objects
+    Package,
+    /// object has been paused at runtime
+    Paused,
+    /// location information whether the configuration files are stored
+    SourceLocation,
+    /// templates imported on object compilation
+    Templates,
+    /// timestamp when the object was created or modified. syncred throughout cluster nodes
+    Version,
+    /// the zone this object is a member of
+    Zone,
+    /// the host to connect to
+    Host,
+    /// the port to connect to
+    Port,
+    /// how long to keep the replay logs on connection loss, disabled if set to 0
+    LogDuration,
+    /// log position on the local side
+    LocalLogPosition,
+    /// log position on the remote side
+    RemoteLogPosition,
+    /// remote icinga version
+    IcingaVersion,
+    /// remote icinga capabilities
+    Capabilities,
+    /// is this endpoint currently connecting
+    Connecting,
+    /// is this endpoint currently syncing
+    Syncing,
+    /// is this endpoint currently connected
+    Connected,
+    /// time when the last message was sent to this endpoint
+    LastMessageSent,
+    /// time when the last message was received from this endpoint
+    LastMessageReceived,
+    /// frequency of message sending on this endpoint
+    MessagesSendPerSecond,
+    /// frequency of message reception on this endpoint
+    MessagesReceivedPerSecond,
+    /// bandwidth used sending on this endpoint
+    BytesSentPerSecond,
+    /// bandwidth used receiving on this endpoint
+    BytesReceivedPerSecond,
+}
+
+impl IcingaAttribute for IcingaEndpointAttribute {}
+
+impl std::fmt::Display for IcingaEndpointAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ObjectType => write!(f, "type"),
+            Self::FullName => write!(f, "__name"),
+            Self::Name => write!(f, "name"),
+            Self::Active => write!(f, "active"),
+            Self::HaMode => write!(f, "ha_mode"),
+            Self::OriginalAttributes => write!(f, "original_attributes"),
+            Self::Package => write!(f, "package"),
+            Self::Paused => write!(f, "paused"),
+            Self::SourceLocation => write!(f, "source_location"),
+            Self::Templates => write!(f, "templates"),
+            Self::Version => write!(f, "version"),
+            Self::Zone => write!(f, "zone"),
+            Self::Host => write!(f, "host"),
+            Self::Port => write!(f, "port"),
+            Self::LogDuration => write!(f, "log_duration"),
+            Self::LocalLogPosition => write!(f, "local_log_position"),
+            Self::RemoteLogPosition => write!(f, "remote_log_position"),
+            Self::IcingaVersion => write!(f, "icinga_version"),
+            Self::Capabilities => write!(f, "capabilities"),
+            Self::Connecting => write!(f, "connecting"),
+            Self::Syncing => write!(f, "syncing"),
+            Self::Connected => write!(f, "connected"),
+            Self::LastMessageSent => write!(f, "last_message_sent"),
+            Self::LastMessageReceived => write!(f, "last_message_received"),
+            Self::MessagesSendPerSecond => write!(f, "messages_send_per_second"),
+            Self::MessagesReceivedPerSecond => write!(f, "messages_received_per_second"),
+            Self::BytesSentPerSecond => write!(f, "bytes_sent_per_second"),
+            Self::BytesReceivedPerSecond => write!(f, "bytes_received_per_second"),
+        }
+    }
+}
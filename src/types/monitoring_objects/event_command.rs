@@ -6,6 +6,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::types::attributes::IcingaAttribute;
+use crate::types::common::command::IcingaCommandPartial;
 use crate::types::{
     common::{command::IcingaCommand, custom_var_object::CustomVarHolder},
     enums::object_type::IcingaObjectType,
@@ -27,3 +29,84 @@ impl CustomVarHolder for IcingaEventCommand {
         self.command.custom_var_value(name)
     }
 }
+
+/// the attributes of [IcingaEventCommand] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IcingaEventCommandPartial {
+    /// type of object
+    #[serde(rename = "type")]
+    pub object_type: Option<IcingaObjectType>,
+    /// shared fields in all command types
+    #[serde(flatten)]
+    pub command: IcingaCommandPartial,
+}
+
+/// the attributes of [IcingaEventCommand] that can be requested with the attrs parameter of a query
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IcingaEventCommandAttribute {
+    /// type of object
+    ObjectType,
+    /// custom variables specific to this object
+    Vars,
+    /// full object name
+    FullName,
+    /// short object name
+    Name,
+    /// object is active (being checked)
+    Active,
+    /// whether to run a check once or everywhere
+    HaMode,
+    /// original values of object attributes modified at runtime
+    OriginalAttributes,
+    /// configuration package name this object belongs to, _etc for local configuration
+    /// _api for runtime created objects
+    Package,
+    /// object has been paused at runtime
+    Paused,
+    /// location information whether the configuration files are stored
+    SourceLocation,
+    /// templates imported on object compilation
+    Templates,
+    /// timestamp when the object was created or modified. syncred throughout cluster nodes
+    Version,
+    /// the zone this object is a member of
+    Zone,
+    /// the descriptions of the command arguments
+    Arguments,
+    /// the actual command
+    Command,
+    /// environment variables
+    Env,
+    /// function for execution
+    Execute,
+    /// command timeout
+    Timeout,
+}
+
+impl IcingaAttribute for IcingaEventCommandAttribute {}
+
+impl std::fmt::Display for IcingaEventCommandAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ObjectType => write!(f, "type"),
+            Self::Vars => write!(f, "vars"),
+            Self::FullName => write!(f, "__name"),
+            Self::Name => write!(f, "name"),
+            Self::Active => write!(f, "active"),
+            Self::HaMode => write!(f, "ha_mode"),
+            Self::OriginalAttributes => write!(f, "original_attributes"),
+            Self::Package => write!(f, "package"),
+            Self::Paused => write!(f, "paused"),
+            Self::SourceLocation => write!(f, "source_location"),
+            Self::Templates => write!(f, "templates"),
+            Self::Version => write!(f, "version"),
+            Self::Zone => write!(f, "zone"),
+            Self::Arguments => write!(f, "arguments"),
+            Self::Command => write!(f, "command"),
+            Self::Env => write!(f, "env"),
+            Self::Execute => write!(f, "execute"),
+            Self::Timeout => write!(f, "timeout"),
+        }
+    }
+}
@@ -12,7 +12,9 @@ use crate::serde::{
     deserialize_optional_icinga_timestamp, deserialize_optional_seconds_as_duration,
     serialize_optional_duration_as_seconds, serialize_optional_icinga_timestamp,
 };
+use crate::types::attributes::IcingaAttribute;
 use crate::types::common::custom_var_object::CustomVarHolder;
+use crate::types::common::custom_var_object::IcingaCustomVarObjectPartial;
 use crate::types::enums::notification_filter::IcingaNotificationFilter;
 use crate::types::enums::notification_type::IcingaNotificationType;
 use crate::types::{
@@ -90,3 +92,174 @@ impl CustomVarHolder for IcingaNotification {
         self.custom_var.custom_var_value(name)
     }
 }
+
+/// the attributes of [IcingaNotification] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IcingaNotificationPartial {
+    /// type of icinga object, should always be Notification for this
+    #[serde(rename = "type")]
+    pub object_type: Option<IcingaObjectType>,
+    /// shared config object and custom variable fields
+    #[serde(flatten)]
+    pub custom_var: IcingaCustomVarObjectPartial,
+    /// the notification command to call
+    pub command: Option<IcingaNotificationCommandName>,
+    /// the renotification interval
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_duration_as_seconds",
+        deserialize_with = "deserialize_optional_seconds_as_duration"
+    )]
+    pub interval: Option<time::Duration>,
+    /// the name of the time period when this notification is active
+    pub period: Option<IcingaTimePeriodName>,
+    /// the users to notify
+    pub users: Option<Vec<IcingaUserName>>,
+    /// the user groups to notify
+    pub user_groups: Option<Vec<IcingaUserGroupName>>,
+    /// A dictionary containing begin and end attributes for the notification.
+    pub times: Option<BTreeMap<String, serde_json::Value>>,
+    /// A list of type filters when this notification should be triggered. By default everything is matched.
+    pub types: Option<Vec<IcingaNotificationType>>,
+    /// A list of state filters when this notification should be triggered. By default everything is matched. Note that the states filter is ignored for notifications of type Acknowledgement!
+    pub states: Option<Vec<IcingaNotificationFilter>>,
+    /// The name of the host this notification belongs to.
+    pub host_name: Option<IcingaHostName>,
+    /// The short name of the service this notification belongs to. If omitted, this notification object is treated as host notification.
+    pub service_name: Option<IcingaServiceName>,
+    /// the users notified by this notification
+    pub notified_problem_users: Option<Vec<IcingaUserName>>,
+    /// do not send any more notifications for this issue
+    pub no_more_notifications: Option<bool>,
+    /// when was this notification last sent
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_icinga_timestamp",
+        deserialize_with = "deserialize_optional_icinga_timestamp"
+    )]
+    pub last_notification: Option<time::OffsetDateTime>,
+    /// when will this notification be sent next
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_icinga_timestamp",
+        deserialize_with = "deserialize_optional_icinga_timestamp"
+    )]
+    pub next_notification: Option<time::OffsetDateTime>,
+    /// the number of notifications sent out
+    pub notification_number: Option<u64>,
+    /// the last notification that was about a problem (as opposed to acknowledgement or end of problem)
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_icinga_timestamp",
+        deserialize_with = "deserialize_optional_icinga_timestamp"
+    )]
+    pub last_problem_notification: Option<time::OffsetDateTime>,
+    /// the command endpoint for the notification command
+    pub command_endpoint: Option<IcingaEndpointName>,
+}
+
+/// the attributes of [IcingaNotification] that can be requested with the attrs parameter of a query
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IcingaNotificationAttribute {
+    /// type of icinga object, should always be Notification for this
+    ObjectType,
+    /// custom variables specific to this object
+    Vars,
+    /// full object name
+    FullName,
+    /// short object name
+    Name,
+    /// object is active (being checked)
+    Active,
+    /// whether to run a check once or everywhere
+    HaMode,
+    /// original values of object attributes modified at runtime
+    OriginalAttributes,
+    /// configuration package name this object belongs to, _etc for local configuration
+    /// _api for runtime created objects
+    Package,
+    /// object has been paused at runtime
+    Paused,
+    /// location information whether the configuration files are stored
+    SourceLocation,
+    /// templates imported on object compilation
+    Templates,
+    /// timestamp when the object was created or modified. syncred throughout cluster nodes
+    Version,
+    /// the zone this object is a member of
+    Zone,
+    /// the notification command to call
+    Command,
+    /// the renotification interval
+    Interval,
+    /// the name of the time period when this notification is active
+    Period,
+    /// the users to notify
+    Users,
+    /// the user groups to notify
+    UserGroups,
+    /// A dictionary containing begin and end attributes for the notification.
+    Times,
+    /// A list of type filters when this notification should be triggered. By default everything is matched.
+    Types,
+    /// A list of state filters when this notification should be triggered. By default everything is matched. Note that the states filter is ignored for notifications of type Acknowledgement!
+    States,
+    /// The name of the host this notification belongs to.
+    HostName,
+    /// The short name of the service this notification belongs to. If omitted, this notification object is treated as host notification.
+    ServiceName,
+    /// the users notified by this notification
+    NotifiedProblemUsers,
+    /// do not send any more notifications for this issue
+    NoMoreNotifications,
+    /// when was this notification last sent
+    LastNotification,
+    /// when will this notification be sent next
+    NextNotification,
+    /// the number of notifications sent out
+    NotificationNumber,
+    /// the last notification that was about a problem (as opposed to acknowledgement or end of problem)
+    LastProblemNotification,
+    /// the command endpoint for the notification command
+    CommandEndpoint,
+}
+
+impl IcingaAttribute for IcingaNotificationAttribute {}
+
+impl std::fmt::Display for IcingaNotificationAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ObjectType => write!(f, "type"),
+            Self::Vars => write!(f, "vars"),
+            Self::FullName => write!(f, "__name"),
+            Self::Name => write!(f, "name"),
+            Self::Active => write!(f, "active"),
+            Self::HaMode => write!(f, "ha_mode"),
+            Self::OriginalAttributes => write!(f, "original_attributes"),
+            Self::Package => write!(f, "package"),
+            Self::Paused => write!(f, "paused"),
+            Self::SourceLocation => write!(f, "source_location"),
+            Self::Templates => write!(f, "templates"),
+            Self::Version => write!(f, "version"),
+            Self::Zone => write!(f, "zone"),
+            Self::Command => write!(f, "command"),
+            Self::Interval => write!(f, "interval"),
+            Self::Period => write!(f, "period"),
+            Self::Users => write!(f, "users"),
+            Self::UserGroups => write!(f, "user_groups"),
+            Self::Times => write!(f, "times"),
+            Self::Types => write!(f, "types"),
+            Self::States => write!(f, "states"),
+            Self::HostName => write!(f, "host_name"),
+            Self::ServiceName => write!(f, "service_name"),
+            Self::NotifiedProblemUsers => write!(f, "notified_problem_users"),
+            Self::NoMoreNotifications => write!(f, "no_more_notifications"),
+            Self::LastNotification => write!(f, "last_notification"),
+            Self::NextNotification => write!(f, "next_notification"),
+            Self::NotificationNumber => write!(f, "notification_number"),
+            Self::LastProblemNotification => write!(f, "last_problem_notification"),
+            Self::CommandEndpoint => write!(f, "command_endpoint"),
+        }
+    }
+}
@@ -11,7 +11,9 @@ use serde::{Deserialize, Serialize};
 use crate::serde::{
     deserialize_optional_seconds_as_duration, serialize_optional_duration_as_seconds,
 };
+use crate::types::attributes::IcingaAttribute;
 use crate::types::common::custom_var_object::CustomVarHolder;
+use crate::types::common::custom_var_object::IcingaCustomVarObjectPartial;
 use crate::types::{
     common::custom_var_object::IcingaCustomVarObject,
     enums::{downtime_child_options::IcingaDowntimeChildOptions, object_type::IcingaObjectType},
@@ -54,3 +56,114 @@ impl CustomVarHolder for IcingaScheduledDowntime {
         self.custom_var.custom_var_value(name)
     }
 }
+
+/// the attributes of [IcingaScheduledDowntime] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IcingaScheduledDowntimePartial {
+    /// type of icinga object, should always be ScheduledDowntime for this
+    #[serde(rename = "type")]
+    pub object_type: Option<IcingaObjectType>,
+    /// shared config object and custom variable fields
+    #[serde(flatten)]
+    pub custom_var: IcingaCustomVarObjectPartial,
+    /// the host for which the downtime will be scheduled
+    pub host_name: Option<IcingaHostName>,
+    /// the service for which the downtime will be scheduled, if not specified this is a host downtime
+    pub service_name: Option<IcingaServiceName>,
+    /// the author of the downtime
+    pub author: Option<String>,
+    /// the comment displayed for the downtime
+    pub comment: Option<String>,
+    /// the duration of the downtime
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_duration_as_seconds",
+        deserialize_with = "deserialize_optional_seconds_as_duration"
+    )]
+    pub duration: Option<time::Duration>,
+    /// is this a fixed downtime
+    pub fixed: Option<bool>,
+    /// how to apply this downtime to children
+    pub child_options: Option<IcingaDowntimeChildOptions>,
+    /// which days and durations apply to this timeperiod.
+    pub ranges: Option<BTreeMap<String, String>>,
+}
+
+/// the attributes of [IcingaScheduledDowntime] that can be requested with the attrs parameter of a query
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IcingaScheduledDowntimeAttribute {
+    /// type of icinga object, should always be ScheduledDowntime for this
+    ObjectType,
+    /// custom variables specific to this object
+    Vars,
+    /// full object name
+    FullName,
+    /// short object name
+    Name,
+    /// object is active (being checked)
+    Active,
+    /// whether to run a check once or everywhere
+    HaMode,
+    /// original values of object attributes modified at runtime
+    OriginalAttributes,
+    /// configuration package name this object belongs to, _etc for local configuration
+    /// _api for runtime created objects
+    Package,
+    /// object has been paused at runtime
+    Paused,
+    /// location information whether the configuration files are stored
+    SourceLocation,
+    /// templates imported on object compilation
+    Templates,
+    /// timestamp when the object was created or modified. syncred throughout cluster nodes
+    Version,
+    /// the zone this object is a member of
+    Zone,
+    /// the host for which the downtime will be scheduled
+    HostName,
+    /// the service for which the downtime will be scheduled, if not specified this is a host downtime
+    ServiceName,
+    /// the author of the downtime
+    Author,
+    /// the comment displayed for the downtime
+    Comment,
+    /// the duration of the downtime
+    Duration,
+    /// is this a fixed downtime
+    Fixed,
+    /// how to apply this downtime to children
+    ChildOptions,
+    /// which days and durations apply to this timeperiod.
+    Ranges,
+}
+
+impl IcingaAttribute for IcingaScheduledDowntimeAttribute {}
+
+impl std::fmt::Display for IcingaScheduledDowntimeAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ObjectType => write!(f, "type"),
+            Self::Vars => write!(f, "vars"),
+            Self::FullName => write!(f, "__name"),
+            Self::Name => write!(f, "name"),
+            Self::Active => write!(f, "active"),
+            Self::HaMode => write!(f, "ha_mode"),
+            Self::OriginalAttributes => write!(f, "original_attributes"),
+            Self::Package => write!(f, "package"),
+            Self::Paused => write!(f, "paused"),
+            Self::SourceLocation => write!(f, "source_location"),
+            Self::Templates => write!(f, "templates"),
+            Self::Version => write!(f, "version"),
+            Self::Zone => write!(f, "zone"),
+            Self::HostName => write!(f, "host_name"),
+            Self::ServiceName => write!(f, "service_name"),
+            Self::Author => write!(f, "author"),
+            Self::Comment => write!(f, "comment"),
+            Self::Duration => write!(f, "duration"),
+            Self::Fixed => write!(f, "fixed"),
+            Self::ChildOptions => write!(f, "child_options"),
+            Self::Ranges => write!(f, "ranges"),
+        }
+    }
+}
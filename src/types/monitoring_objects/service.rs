@@ -6,8 +6,10 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::serde::{deserialize_optional_icinga_timestamp, serialize_optional_icinga_timestamp};
+use crate::types::attributes::IcingaAttribute;
+use crate::types::common::checkable::IcingaCheckablePartial;
 use crate::types::common::custom_var_object::CustomVarHolder;
+use crate::types::timestamp::IcingaTimestamp;
 use crate::types::{
     common::checkable::IcingaCheckable,
     enums::{object_type::IcingaObjectType, service_state::IcingaServiceState},
@@ -34,31 +36,19 @@ pub struct IcingaService {
     /// the previous state
     pub last_state: IcingaServiceState,
     /// when the last CRITICAL state occurred
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub last_state_critical: Option<time::OffsetDateTime>,
+    pub last_state_critical: IcingaTimestamp,
     /// when the last OK state occurred
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub last_state_ok: Option<time::OffsetDateTime>,
+    pub last_state_ok: IcingaTimestamp,
     /// when the last UNKNOWN state occurred
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub last_state_unknown: Option<time::OffsetDateTime>,
+    pub last_state_unknown: IcingaTimestamp,
     /// when the last WARNINGE state occurred
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub last_state_warning: Option<time::OffsetDateTime>,
+    pub last_state_warning: IcingaTimestamp,
     /// the current state
     pub state: IcingaServiceState,
+    /// attributes not modelled by this crate, only filled in by lenient
+    /// deserialization (see [crate::types::lenient])
+    #[serde(flatten, skip_deserializing)]
+    pub extra: std::collections::BTreeMap<String, serde_json::Value>,
 }
 
 impl CustomVarHolder for IcingaService {
@@ -66,3 +56,270 @@ impl CustomVarHolder for IcingaService {
         self.checkable.custom_var_value(name)
     }
 }
+
+/// the attributes of [IcingaService] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct IcingaServicePartial {
+    /// type of icinga object, should always be Service for this
+    #[serde(rename = "type")]
+    pub object_type: Option<IcingaObjectType>,
+    /// all the attributes from the icinga checkable object (shared fields between host and service)
+    #[serde(flatten)]
+    pub checkable: IcingaCheckablePartial,
+    /// a short description of the service
+    pub display_name: Option<String>,
+    /// a list of groups the service belongs to
+    pub groups: Option<Vec<IcingaServiceGroupName>>,
+    /// the hostname for this service
+    pub host_name: Option<IcingaHostName>,
+    /// the previous hard state
+    pub last_hard_state: Option<IcingaServiceState>,
+    /// the previous state
+    pub last_state: Option<IcingaServiceState>,
+    /// when the last CRITICAL state occurred
+    #[serde(default)]
+    pub last_state_critical: IcingaTimestamp,
+    /// when the last OK state occurred
+    #[serde(default)]
+    pub last_state_ok: IcingaTimestamp,
+    /// when the last UNKNOWN state occurred
+    #[serde(default)]
+    pub last_state_unknown: IcingaTimestamp,
+    /// when the last WARNINGE state occurred
+    #[serde(default)]
+    pub last_state_warning: IcingaTimestamp,
+    /// the current state
+    pub state: Option<IcingaServiceState>,
+}
+
+/// the attributes of [IcingaService] that can be requested with the attrs parameter of a query
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IcingaServiceAttribute {
+    /// type of icinga object, should always be Service for this
+    ObjectType,
+    /// custom variables specific to this object
+    Vars,
+    /// full object name
+    FullName,
+    /// short object name
+    Name,
+    /// object is active (being checked)
+    Active,
+    /// whether to run a check once or everywhere
+    HaMode,
+    /// original values of object attributes modified at runtime
+    OriginalAttributes,
+    /// configuration package name this object belongs to, _etc for local configuration
+    /// _api for runtime created objects
+    Package,
+    /// object has been paused at runtime
+    Paused,
+    /// location information whether the configuration files are stored
+    SourceLocation,
+    /// templates imported on object compilation
+    Templates,
+    /// timestamp when the object was created or modified. syncred throughout cluster nodes
+    Version,
+    /// the zone this object is a member of
+    Zone,
+    /// the type of acknowledgement (includes None)
+    Acknowledgement,
+    /// when the acknowledgement expires
+    AcknowledgementExpiry,
+    /// when the acknowledgement last changed
+    AcknowledgementLastChange,
+    /// URL for actions for the checkable (host or service)
+    ActionUrl,
+    /// the current check attempt number
+    CheckAttempt,
+    /// the name of the check command
+    CheckCommand,
+    /// the interval used for checks when the host/service is in a HARD state
+    CheckInterval,
+    /// name of a time period when this host/service is checked
+    CheckPeriod,
+    /// check timeout
+    CheckTimeout,
+    /// the endpoint the command is executed on
+    CommandEndpoint,
+    /// number of active downtimes on the host/service
+    DowntimeDepth,
+    /// whether active checks are enabled
+    EnableActiveChecks,
+    /// enabled event handlers for this host/service
+    EnableEventHandler,
+    /// whether flap detection is enabled
+    EnableFlapping,
+    /// whether notifications are enabled
+    EnableNotifications,
+    /// whether passive checks are enabled
+    EnablePassiveChecks,
+    /// whether performance data processing is enabled
+    EnablePerfdata,
+    /// the name of an event command that should be executed every time the host/service state changes or the host/service is in a SOFT state
+    EventCommand,
+    /// contains the state of execute-command executions
+    Executions,
+    /// whether the host/service is flapping between states
+    Flapping,
+    /// current flapping value in percent
+    FlappingCurrent,
+    /// a list of states that should be ignored during flapping calculations
+    FlappingIgnoreStates,
+    /// when the last flapping change occurred
+    FlappingLastChange,
+    /// deprecated and has no effect, replaced by flapping_threshold_low and flapping_threshold_high
+    FlappingThreshold,
+    /// the flapping lower bound in percent for a host/service to be considered flapping
+    FlappingThresholdLow,
+    /// the flapping upper bound in percent for a host/service to be considered flapping
+    FlappingThresholdHigh,
+    /// force the next check (execute it now)
+    ForceNextCheck,
+    /// force next notification (send it now)
+    ForceNextNotification,
+    /// whether the host/service problem is handled (downtime or acknowledgement)
+    Handled,
+    /// icon image for the host/service
+    IconImage,
+    /// icon image alt text for the host/service
+    IconImageAlt,
+    /// when the last check occurred
+    LastCheck,
+    /// the result of the last check
+    LastCheckResult,
+    /// when the last hard state change occurred
+    LastHardStateChange,
+    /// whether the host/service was reachable when the last check occurred
+    LastReachable,
+    /// when the last state change occurred
+    LastStateChange,
+    /// the previous state type (soft/hard)
+    LastStateType,
+    /// when the last UNREACHABLE state occurred
+    LastStateUnreachable,
+    /// the number of times the host/service is checked before changing into a new hard state
+    MaxCheckAttempts,
+    /// when the next check occurs
+    NextCheck,
+    /// when the next check update is to be expected
+    NextUpdate,
+    /// notes for the host/service
+    Notes,
+    /// URL for notes for the host/service
+    NotesUrl,
+    /// when the previous state change occurred
+    PreviousStateChange,
+    /// whether the host/service is considered to be in a problem state type (not up)
+    Problem,
+    /// the interval used for checks when the host/service is in a SOFT state
+    RetryInterval,
+    /// pre-calculated value, higher means more severe
+    Severity,
+    /// the current state type (soft/hard)
+    StateType,
+    /// treat all state changes as HARD changes
+    Volatile,
+    /// a short description of the service
+    DisplayName,
+    /// a list of groups the service belongs to
+    Groups,
+    /// the hostname for this service
+    HostName,
+    /// the previous hard state
+    LastHardState,
+    /// the previous state
+    LastState,
+    /// when the last CRITICAL state occurred
+    LastStateCritical,
+    /// when the last OK state occurred
+    LastStateOk,
+    /// when the last UNKNOWN state occurred
+    LastStateUnknown,
+    /// when the last WARNINGE state occurred
+    LastStateWarning,
+    /// the current state
+    State,
+}
+
+impl IcingaAttribute for IcingaServiceAttribute {}
+
+impl std::fmt::Display for IcingaServiceAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ObjectType => write!(f, "type"),
+            Self::Vars => write!(f, "vars"),
+            Self::FullName => write!(f, "__name"),
+            Self::Name => write!(f, "name"),
+            Self::Active => write!(f, "active"),
+            Self::HaMode => write!(f, "ha_mode"),
+            Self::OriginalAttributes => write!(f, "original_attributes"),
+            Self::Package => write!(f, "package"),
+            Self::Paused => write!(f, "paused"),
+            Self::SourceLocation => write!(f, "source_location"),
+            Self::Templates => write!(f, "templates"),
+            Self::Version => write!(f, "version"),
+            Self::Zone => write!(f, "zone"),
+            Self::Acknowledgement => write!(f, "acknowledgement"),
+            Self::AcknowledgementExpiry => write!(f, "acknowledgement_expiry"),
+            Self::AcknowledgementLastChange => write!(f, "acknowledgement_last_change"),
+            Self::ActionUrl => write!(f, "action_url"),
+            Self::CheckAttempt => write!(f, "check_attempt"),
+            Self::CheckCommand => write!(f, "check_command"),
+            Self::CheckInterval => write!(f, "check_interval"),
+            Self::CheckPeriod => write!(f, "check_period"),
+            Self::CheckTimeout => write!(f, "check_timeout"),
+            Self::CommandEndpoint => write!(f, "command_endpoint"),
+            Self::DowntimeDepth => write!(f, "downtime_depth"),
+            Self::EnableActiveChecks => write!(f, "enable_active_checks"),
+            Self::EnableEventHandler => write!(f, "enable_event_handler"),
+            Self::EnableFlapping => write!(f, "enable_flapping"),
+            Self::EnableNotifications => write!(f, "enable_notifications"),
+            Self::EnablePassiveChecks => write!(f, "enable_passive_checks"),
+            Self::EnablePerfdata => write!(f, "enable_perfdata"),
+            Self::EventCommand => write!(f, "event_command"),
+            Self::Executions => write!(f, "executions"),
+            Self::Flapping => write!(f, "flapping"),
+            Self::FlappingCurrent => write!(f, "flapping_current"),
+            Self::FlappingIgnoreStates => write!(f, "flapping_ignore_states"),
+            Self::FlappingLastChange => write!(f, "flapping_last_change"),
+            Self::FlappingThreshold => write!(f, "flapping_threshold"),
+            Self::FlappingThresholdLow => write!(f, "flapping_threshold_low"),
+            Self::FlappingThresholdHigh => write!(f, "flapping_threshold_high"),
+            Self::ForceNextCheck => write!(f, "force_next_check"),
+            Self::ForceNextNotification => write!(f, "force_next_notification"),
+            Self::Handled => write!(f, "handled"),
+            Self::IconImage => write!(f, "icon_image"),
+            Self::IconImageAlt => write!(f, "icon_image_alt"),
+            Self::LastCheck => write!(f, "last_check"),
+            Self::LastCheckResult => write!(f, "last_check_result"),
+            Self::LastHardStateChange => write!(f, "last_hard_state_change"),
+            Self::LastReachable => write!(f, "last_reachable"),
+            Self::LastStateChange => write!(f, "last_state_change"),
+            Self::LastStateType => write!(f, "last_state_type"),
+            Self::LastStateUnreachable => write!(f, "last_state_unreachable"),
+            Self::MaxCheckAttempts => write!(f, "max_check_attempts"),
+            Self::NextCheck => write!(f, "next_check"),
+            Self::NextUpdate => write!(f, "next_update"),
+            Self::Notes => write!(f, "notes"),
+            Self::NotesUrl => write!(f, "notes_url"),
+            Self::PreviousStateChange => write!(f, "previous_state_change"),
+            Self::Problem => write!(f, "problem"),
+            Self::RetryInterval => write!(f, "retry_interval"),
+            Self::Severity => write!(f, "severity"),
+            Self::StateType => write!(f, "state_type"),
+            Self::Volatile => write!(f, "volatile"),
+            Self::DisplayName => write!(f, "display_name"),
+            Self::Groups => write!(f, "groups"),
+            Self::HostName => write!(f, "host_name"),
+            Self::LastHardState => write!(f, "last_hard_state"),
+            Self::LastState => write!(f, "last_state"),
+            Self::LastStateCritical => write!(f, "last_state_critical"),
+            Self::LastStateOk => write!(f, "last_state_ok"),
+            Self::LastStateUnknown => write!(f, "last_state_unknown"),
+            Self::LastStateWarning => write!(f, "last_state_warning"),
+            Self::State => write!(f, "state"),
+        }
+    }
+}
@@ -7,7 +7,9 @@
 use serde::{Deserialize, Serialize};
 
 use crate::serde::{deserialize_empty_string_or_string, serialize_none_as_empty_string};
+use crate::types::attributes::IcingaAttribute;
 use crate::types::common::custom_var_object::CustomVarHolder;
+use crate::types::common::custom_var_object::IcingaCustomVarObjectPartial;
 use crate::types::{
     common::custom_var_object::IcingaCustomVarObject, enums::object_type::IcingaObjectType,
     names::IcingaServiceGroupName,
@@ -51,3 +53,109 @@ impl CustomVarHolder for IcingaServiceGroup {
         self.custom_var.custom_var_value(name)
     }
 }
+
+/// the attributes of [IcingaServiceGroup] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IcingaServiceGroupPartial {
+    /// type of object
+    #[serde(rename = "type")]
+    pub object_type: Option<IcingaObjectType>,
+    /// shared config object and custom variable fields
+    #[serde(flatten)]
+    pub custom_var: IcingaCustomVarObjectPartial,
+    /// a short description of the service group
+    pub display_name: Option<String>,
+    /// a list of groups the service group belongs to
+    pub groups: Option<Vec<IcingaServiceGroupName>>,
+    /// URL for actions for the checkable (host or service)
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string",
+        deserialize_with = "deserialize_empty_string_or_string"
+    )]
+    pub action_url: Option<String>,
+    /// notes for the host/service
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string",
+        deserialize_with = "deserialize_empty_string_or_string"
+    )]
+    pub notes: Option<String>,
+    /// URL for notes for the host/service
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_none_as_empty_string",
+        deserialize_with = "deserialize_empty_string_or_string"
+    )]
+    pub notes_url: Option<String>,
+}
+
+/// the attributes of [IcingaServiceGroup] that can be requested with the attrs parameter of a query
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IcingaServiceGroupAttribute {
+    /// type of object
+    ObjectType,
+    /// custom variables specific to this object
+    Vars,
+    /// full object name
+    FullName,
+    /// short object name
+    Name,
+    /// object is active (being checked)
+    Active,
+    /// whether to run a check once or everywhere
+    HaMode,
+    /// original values of object attributes modified at runtime
+    OriginalAttributes,
+    /// configuration package name this object belongs to, _etc for local configuration
+    /// _api for runtime created objects
+    Package,
+    /// object has been paused at runtime
+    Paused,
+    /// location information whether the configuration files are stored
+    SourceLocation,
+    /// templates imported on object compilation
+    Templates,
+    /// timestamp when the object was created or modified. syncred throughout cluster nodes
+    Version,
+    /// the zone this object is a member of
+    Zone,
+    /// a short description of the service group
+    DisplayName,
+    /// a list of groups the service group belongs to
+    Groups,
+    /// URL for actions for the checkable (host or service)
+    ActionUrl,
+    /// notes for the host/service
+    Notes,
+    /// URL for notes for the host/service
+    NotesUrl,
+}
+
+impl IcingaAttribute for IcingaServiceGroupAttribute {}
+
+impl std::fmt::Display for IcingaServiceGroupAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ObjectType => write!(f, "type"),
+            Self::Vars => write!(f, "vars"),
+            Self::FullName => write!(f, "__name"),
+            Self::Name => write!(f, "name"),
+            Self::Active => write!(f, "active"),
+            Self::HaMode => write!(f, "ha_mode"),
+            Self::OriginalAttributes => write!(f, "original_attributes"),
+            Self::Package => write!(f, "package"),
+            Self::Paused => write!(f, "paused"),
+            Self::SourceLocation => write!(f, "source_location"),
+            Self::Templates => write!(f, "templates"),
+            Self::Version => write!(f, "version"),
+            Self::Zone => write!(f, "zone"),
+            Self::DisplayName => write!(f, "display_name"),
+            Self::Groups => write!(f, "groups"),
+            Self::ActionUrl => write!(f, "action_url"),
+            Self::Notes => write!(f, "notes"),
+            Self::NotesUrl => write!(f, "notes_url"),
+        }
+    }
+}
@@ -8,6 +8,8 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
+use crate::types::attributes::IcingaAttribute;
+use crate::types::common::custom_var_object::IcingaCustomVarObjectPartial;
 use crate::types::{
     common::{
         custom_var_object::{CustomVarHolder, IcingaCustomVarObject},
@@ -15,6 +17,7 @@ use crate::types::{
     },
     enums::object_type::IcingaObjectType,
     names::IcingaTimePeriodName,
+    time_ranges::IcingaTimeSegment,
 };
 
 /// an Icinga time period
@@ -28,7 +31,7 @@ pub struct IcingaTimePeriod {
     pub custom_var: IcingaCustomVarObject,
     /// a short description of the time period
     pub display_name: String,
-    /// the time ranges in this time period
+    /// the time ranges in this time period, see [crate::types::time_ranges]
     pub ranges: BTreeMap<String, String>,
     /// function to update this time period's calculated attributes
     pub update: IcingaFunction,
@@ -42,8 +45,9 @@ pub struct IcingaTimePeriod {
     pub valid_begin: Option<serde_json::Value>,
     /// TODO: not sure what the meaning of this attribute is
     pub valid_end: Option<serde_json::Value>,
-    /// TODO: not sure what the meaning of this attribute is
-    pub segments: Option<Vec<serde_json::Value>>,
+    /// the segments during which the time period is active, computed by
+    /// Icinga for the near future
+    pub segments: Option<Vec<IcingaTimeSegment>>,
     /// is the current time inside this time period
     pub is_inside: Option<bool>,
 }
@@ -53,3 +57,121 @@ impl CustomVarHolder for IcingaTimePeriod {
         self.custom_var.custom_var_value(name)
     }
 }
+
+/// the attributes of [IcingaTimePeriod] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IcingaTimePeriodPartial {
+    /// type of icinga object, should always be TimePeriod for this
+    #[serde(rename = "type")]
+    pub object_type: Option<IcingaObjectType>,
+    /// shared config object and custom variable fields
+    #[serde(flatten)]
+    pub custom_var: IcingaCustomVarObjectPartial,
+    /// a short description of the time period
+    pub display_name: Option<String>,
+    /// the time ranges in this time period, see [crate::types::time_ranges]
+    pub ranges: Option<BTreeMap<String, String>>,
+    /// function to update this time period's calculated attributes
+    pub update: Option<IcingaFunction>,
+    /// whether includes or excludes are processed first
+    pub prefer_includes: Option<bool>,
+    /// excludes
+    pub excludes: Option<Vec<IcingaTimePeriodName>>,
+    /// includes
+    pub includes: Option<Vec<IcingaTimePeriodName>>,
+    /// TODO: not sure what the meaning of this attribute is
+    pub valid_begin: Option<serde_json::Value>,
+    /// TODO: not sure what the meaning of this attribute is
+    pub valid_end: Option<serde_json::Value>,
+    /// the segments during which the time period is active, computed by
+    /// Icinga for the near future
+    pub segments: Option<Vec<IcingaTimeSegment>>,
+    /// is the current time inside this time period
+    pub is_inside: Option<bool>,
+}
+
+/// the attributes of [IcingaTimePeriod] that can be requested with the attrs parameter of a query
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IcingaTimePeriodAttribute {
+    /// type of icinga object, should always be TimePeriod for this
+    ObjectType,
+    /// custom variables specific to this object
+    Vars,
+    /// full object name
+    FullName,
+    /// short object name
+    Name,
+    /// object is active (being checked)
+    Active,
+    /// whether to run a check once or everywhere
+    HaMode,
+    /// original values of object attributes modified at runtime
+    OriginalAttributes,
+    /// configuration package name this object belongs to, _etc for local configuration
+    /// _api for runtime created objects
+    Package,
+    /// object has been paused at runtime
+    Paused,
+    /// location information whether the configuration files are stored
+    SourceLocation,
+    /// templates imported on object compilation
+    Templates,
+    /// timestamp when the object was created or modified. syncred throughout cluster nodes
+    Version,
+    /// the zone this object is a member of
+    Zone,
+    /// a short description of the time period
+    DisplayName,
+    /// the time ranges in this time period
+    Ranges,
+    /// function to update this time period's calculated attributes
+    Update,
+    /// whether includes or excludes are processed first
+    PreferIncludes,
+    /// excludes
+    Excludes,
+    /// includes
+    Includes,
+    /// TODO: not sure what the meaning of this attribute is
+    ValidBegin,
+    /// TODO: not sure what the meaning of this attribute is
+    ValidEnd,
+    /// the segments during which the time period is active, computed by
+    /// Icinga for the near future
+    Segments,
+    /// is the current time inside this time period
+    IsInside,
+}
+
+impl IcingaAttribute for IcingaTimePeriodAttribute {}
+
+impl std::fmt::Display for IcingaTimePeriodAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ObjectType => write!(f, "type"),
+            Self::Vars => write!(f, "vars"),
+            Self::FullName => write!(f, "__name"),
+            Self::Name => write!(f, "name"),
+            Self::Active => write!(f, "active"),
+            Self::HaMode => write!(f, "ha_mode"),
+            Self::OriginalAttributes => write!(f, "original_attributes"),
+            Self::Package => write!(f, "package"),
+            Self::Paused => write!(f, "paused"),
+            Self::SourceLocation => write!(f, "source_location"),
+            Self::Templates => write!(f, "templates"),
+            Self::Version => write!(f, "version"),
+            Self::Zone => write!(f, "zone"),
+            Self::DisplayName => write!(f, "display_name"),
+            Self::Ranges => write!(f, "ranges"),
+            Self::Update => write!(f, "update"),
+            Self::PreferIncludes => write!(f, "prefer_includes"),
+            Self::Excludes => write!(f, "excludes"),
+            Self::Includes => write!(f, "includes"),
+            Self::ValidBegin => write!(f, "valid_begin"),
+            Self::ValidEnd => write!(f, "valid_end"),
+            Self::Segments => write!(f, "segments"),
+            Self::IsInside => write!(f, "is_inside"),
+        }
+    }
+}
@@ -7,7 +7,9 @@
 use serde::{Deserialize, Serialize};
 
 use crate::serde::{deserialize_optional_icinga_timestamp, serialize_optional_icinga_timestamp};
+use crate::types::attributes::IcingaAttribute;
 use crate::types::common::custom_var_object::CustomVarHolder;
+use crate::types::common::custom_var_object::IcingaCustomVarObjectPartial;
 use crate::types::{
     common::custom_var_object::IcingaCustomVarObject,
     enums::{host_or_service_state::IcingaHostOrServiceState, object_type::IcingaObjectType},
@@ -52,3 +54,119 @@ impl CustomVarHolder for IcingaUser {
         self.custom_var.custom_var_value(name)
     }
 }
+
+/// the attributes of [IcingaUser] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IcingaUserPartial {
+    /// type of icinga object, should always be User for this
+    #[serde(rename = "type")]
+    pub object_type: Option<IcingaObjectType>,
+    /// shared config object and custom variable fields
+    #[serde(flatten)]
+    pub custom_var: IcingaCustomVarObjectPartial,
+    /// a short description of the user
+    pub display_name: Option<String>,
+    /// the groups in which the user is a member
+    pub groups: Option<Vec<IcingaUserGroupName>>,
+    /// the name of the time period when this user is active
+    pub period: Option<IcingaTimePeriodName>,
+    /// A set of type filters when a notification for this user should be triggered. By default everything is matched.
+    pub types: Option<Vec<IcingaObjectType>>,
+    /// A set of state filters when a notification for this should be triggered. By default everything is matched.
+    pub states: Option<Vec<IcingaHostOrServiceState>>,
+    /// user email address
+    pub email: Option<String>,
+    /// user pager number
+    pub pager: Option<String>,
+    /// enable notifications for this user
+    pub enable_notifications: Option<bool>,
+    /// when did we send the last notification to this user
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_icinga_timestamp",
+        deserialize_with = "deserialize_optional_icinga_timestamp"
+    )]
+    pub last_notification: Option<time::OffsetDateTime>,
+}
+
+/// the attributes of [IcingaUser] that can be requested with the attrs parameter of a query
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IcingaUserAttribute {
+    /// type of icinga object, should always be User for this
+    ObjectType,
+    /// custom variables specific to this object
+    Vars,
+    /// full object name
+    FullName,
+    /// short object name
+    Name,
+    /// object is active (being checked)
+    Active,
+    /// whether to run a check once or everywhere
+    HaMode,
+    /// original values of object attributes modified at runtime
+    OriginalAttributes,
+    /// configuration package name this object belongs to, _etc for local configuration
+    /// _api for runtime created objects
+    Package,
+    /// object has been paused at runtime
+    Paused,
+    /// location information whether the configuration files are stored
+    SourceLocation,
+    /// templates imported on object compilation
+    Templates,
+    /// timestamp when the object was created or modified. syncred throughout cluster nodes
+    Version,
+    /// the zone this object is a member of
+    Zone,
+    /// a short description of the user
+    DisplayName,
+    /// the groups in which the user is a member
+    Groups,
+    /// the name of the time period when this user is active
+    Period,
+    /// A set of type filters when a notification for this user should be triggered. By default everything is matched.
+    Types,
+    /// A set of state filters when a notification for this should be triggered. By default everything is matched.
+    States,
+    /// user email address
+    Email,
+    /// user pager number
+    Pager,
+    /// enable notifications for this user
+    EnableNotifications,
+    /// when did we send the last notification to this user
+    LastNotification,
+}
+
+impl IcingaAttribute for IcingaUserAttribute {}
+
+impl std::fmt::Display for IcingaUserAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ObjectType => write!(f, "type"),
+            Self::Vars => write!(f, "vars"),
+            Self::FullName => write!(f, "__name"),
+            Self::Name => write!(f, "name"),
+            Self::Active => write!(f, "active"),
+            Self::HaMode => write!(f, "ha_mode"),
+            Self::OriginalAttributes => write!(f, "original_attributes"),
+            Self::Package => write!(f, "package"),
+            Self::Paused => write!(f, "paused"),
+            Self::SourceLocation => write!(f, "source_location"),
+            Self::Templates => write!(f, "templates"),
+            Self::Version => write!(f, "version"),
+            Self::Zone => write!(f, "zone"),
+            Self::DisplayName => write!(f, "display_name"),
+            Self::Groups => write!(f, "groups"),
+            Self::Period => write!(f, "period"),
+            Self::Types => write!(f, "types"),
+            Self::States => write!(f, "states"),
+            Self::Email => write!(f, "email"),
+            Self::Pager => write!(f, "pager"),
+            Self::EnableNotifications => write!(f, "enable_notifications"),
+            Self::LastNotification => write!(f, "last_notification"),
+        }
+    }
+}
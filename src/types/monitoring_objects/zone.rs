@@ -6,6 +6,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::types::attributes::IcingaAttribute;
+use crate::types::common::config_object::IcingaConfigObjectPartial;
 use crate::types::{
     common::config_object::IcingaConfigObject,
     enums::object_type::IcingaObjectType,
@@ -30,3 +32,86 @@ pub struct IcingaZone {
     /// all parents
     pub all_parents: Option<serde_json::Value>,
 }
+
+/// the attributes of [IcingaZone] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IcingaZonePartial {
+    /// type of icinga object, should always be Zone for this
+    #[serde(rename = "type")]
+    pub object_type: Option<IcingaObjectType>,
+    /// shared config object fields
+    #[serde(flatten)]
+    pub config_object: IcingaConfigObjectPartial,
+    /// parent zone
+    pub parent: Option<IcingaZoneName>,
+    /// endpoints in the zone
+    pub endpoints: Option<Vec<IcingaEndpointName>>,
+    /// is this a global zone
+    pub global: Option<bool>,
+    /// all parents
+    pub all_parents: Option<serde_json::Value>,
+}
+
+/// the attributes of [IcingaZone] that can be requested with the attrs parameter of a query
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IcingaZoneAttribute {
+    /// type of icinga object, should always be Zone for this
+    ObjectType,
+    /// full object name
+    FullName,
+    /// short object name
+    Name,
+    /// object is active (being checked)
+    Active,
+    /// whether to run a check once or everywhere
+    HaMode,
+    /// original values of object attributes modified at runtime
+    OriginalAttributes,
+    /// configuration package name this object belongs to, _etc for local configuration
+    /// _api for runtime created objects
+    Package,
+    /// object has been paused at runtime
+    Paused,
+    /// location information whether the configuration files are stored
+    SourceLocation,
+    /// templates imported on object compilation
+    Templates,
+    /// timestamp when the object was created or modified. syncred throughout cluster nodes
+    Version,
+    /// the zone this object is a member of
+    Zone,
+    /// parent zone
+    Parent,
+    /// endpoints in the zone
+    Endpoints,
+    /// is this a global zone
+    Global,
+    /// all parents
+    AllParents,
+}
+
+impl IcingaAttribute for IcingaZoneAttribute {}
+
+impl std::fmt::Display for IcingaZoneAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ObjectType => write!(f, "type"),
+            Self::FullName => write!(f, "__name"),
+            Self::Name => write!(f, "name"),
+            Self::Active => write!(f, "active"),
+            Self::HaMode => write!(f, "ha_mode"),
+            Self::OriginalAttributes => write!(f, "original_attributes"),
+            Self::Package => write!(f, "package"),
+            Self::Paused => write!(f, "paused"),
+            Self::SourceLocation => write!(f, "source_location"),
+            Self::Templates => write!(f, "templates"),
+            Self::Version => write!(f, "version"),
+            Self::Zone => write!(f, "zone"),
+            Self::Parent => write!(f, "parent"),
+            Self::Endpoints => write!(f, "endpoints"),
+            Self::Global => write!(f, "global"),
+            Self::AllParents => write!(f, "all_parents"),
+        }
+    }
+}
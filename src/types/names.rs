@@ -1,6 +1,24 @@
 //! type-safe names for various icinga objects
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::types::{
+    enums::object_type::IcingaObjectType,
+    event_stream::{
+        IcingaEvent, acknowledgement_cleared::IcingaEventAcknowledgementCleared,
+        acknowledgement_set::IcingaEventAcknowledgementSet, check_result::IcingaEventCheckResult,
+        comment_added::IcingaEventCommentAdded, comment_removed::IcingaEventCommentRemoved,
+        downtime_added::IcingaEventDowntimeAdded, downtime_removed::IcingaEventDowntimeRemoved,
+        downtime_started::IcingaEventDowntimeStarted,
+        downtime_triggered::IcingaEventDowntimeTriggered, flapping::IcingaEventFlapping,
+        notification::IcingaEventNotification, object_created::IcingaEventObjectCreated,
+        object_deleted::IcingaEventObjectDeleted, object_modified::IcingaEventObjectModified,
+        state_change::IcingaEventStateChange,
+    },
+    monitoring_objects::service::IcingaService,
+    runtime_objects::{comment::IcingaComment, downtime::IcingaDowntime},
+};
 
 /// a check command name
 #[derive(
@@ -162,6 +180,190 @@ pub struct IcingaScheduledDowntimeName(pub String);
 )]
 pub struct IcingaServiceName(pub String);
 
+/// the full name of a service (`host!service`) as used in `__name`, in
+/// filters and in the object events of the event stream
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IcingaServiceFullName {
+    /// the host the service belongs to
+    pub host: IcingaHostName,
+    /// the short name of the service
+    pub service: IcingaServiceName,
+}
+
+/// error parsing a service full name
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid service full name, expected host!service: {0}")]
+pub struct ServiceFullNameParseError(pub String);
+
+/// a service full name was requested for an object or event which does not
+/// refer to a service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("the object does not refer to a service")]
+pub struct NotAServiceError;
+
+impl IcingaServiceFullName {
+    /// the full name of the service on the host
+    #[must_use]
+    pub const fn new(host: IcingaHostName, service: IcingaServiceName) -> Self {
+        Self { host, service }
+    }
+
+    /// the full name from the host and optional service fields most types
+    /// referring to a host or service have, None for hosts
+    ///
+    /// Icinga uses an empty service name for objects referring to a host
+    /// (e.g. host downtimes and comments), so that is treated as None
+    #[must_use]
+    pub fn from_host_and_service(
+        host: &IcingaHostName,
+        service: Option<&IcingaServiceName>,
+    ) -> Option<Self> {
+        service
+            .filter(|service| !service.0.is_empty())
+            .map(|service| Self::new(host.clone(), service.clone()))
+    }
+}
+
+impl std::str::FromStr for IcingaServiceFullName {
+    type Err = ServiceFullNameParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('!') {
+            Some((host, service)) if !host.is_empty() && !service.is_empty() => Ok(Self::new(
+                IcingaHostName(host.to_owned()),
+                IcingaServiceName(service.to_owned()),
+            )),
+            _ => Err(ServiceFullNameParseError(s.to_owned())),
+        }
+    }
+}
+
+impl std::fmt::Display for IcingaServiceFullName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}!{}", self.host, self.service)
+    }
+}
+
+impl Serialize for IcingaServiceFullName {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for IcingaServiceFullName {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<&IcingaService> for IcingaServiceFullName {
+    fn from(value: &IcingaService) -> Self {
+        Self::new(
+            value.host_name.clone(),
+            IcingaServiceName(value.checkable.custom_var.config_object.name.clone()),
+        )
+    }
+}
+
+/// implement the conversion to [IcingaServiceFullName] for types with host
+/// and optional service fields
+macro_rules! service_full_name_from_host_and_service {
+    ($type:ty, $host:ident, $service:ident) => {
+        impl TryFrom<&$type> for IcingaServiceFullName {
+            type Error = NotAServiceError;
+
+            fn try_from(value: &$type) -> Result<Self, Self::Error> {
+                Self::from_host_and_service(&value.$host, value.$service.as_ref())
+                    .ok_or(NotAServiceError)
+            }
+        }
+    };
+}
+
+service_full_name_from_host_and_service!(IcingaDowntime, host_name, service_name);
+service_full_name_from_host_and_service!(IcingaComment, host_name, service_name);
+service_full_name_from_host_and_service!(IcingaEventCheckResult, host, service);
+service_full_name_from_host_and_service!(IcingaEventStateChange, host, service);
+service_full_name_from_host_and_service!(IcingaEventNotification, host, service);
+service_full_name_from_host_and_service!(IcingaEventAcknowledgementSet, host, service);
+service_full_name_from_host_and_service!(IcingaEventAcknowledgementCleared, host, service);
+service_full_name_from_host_and_service!(IcingaEventFlapping, host, service);
+
+/// implement the conversion to [IcingaServiceFullName] for events containing
+/// a comment or downtime
+macro_rules! service_full_name_from_nested {
+    ($type:ty, $field:ident) => {
+        impl TryFrom<&$type> for IcingaServiceFullName {
+            type Error = NotAServiceError;
+
+            fn try_from(value: &$type) -> Result<Self, Self::Error> {
+                Self::try_from(&value.$field)
+            }
+        }
+    };
+}
+
+service_full_name_from_nested!(IcingaEventCommentAdded, comment);
+service_full_name_from_nested!(IcingaEventCommentRemoved, comment);
+service_full_name_from_nested!(IcingaEventDowntimeAdded, downtime);
+service_full_name_from_nested!(IcingaEventDowntimeRemoved, downtime);
+service_full_name_from_nested!(IcingaEventDowntimeStarted, downtime);
+service_full_name_from_nested!(IcingaEventDowntimeTriggered, downtime);
+
+/// implement the conversion to [IcingaServiceFullName] for object events,
+/// which contain the full name of the object
+macro_rules! service_full_name_from_object_event {
+    ($type:ty) => {
+        impl TryFrom<&$type> for IcingaServiceFullName {
+            type Error = NotAServiceError;
+
+            fn try_from(value: &$type) -> Result<Self, Self::Error> {
+                if value.object_type == IcingaObjectType::Service {
+                    value.object_name.parse().map_err(|_err| NotAServiceError)
+                } else {
+                    Err(NotAServiceError)
+                }
+            }
+        }
+    };
+}
+
+service_full_name_from_object_event!(IcingaEventObjectCreated);
+service_full_name_from_object_event!(IcingaEventObjectModified);
+service_full_name_from_object_event!(IcingaEventObjectDeleted);
+
+impl TryFrom<&IcingaEvent> for IcingaServiceFullName {
+    type Error = NotAServiceError;
+
+    fn try_from(value: &IcingaEvent) -> Result<Self, Self::Error> {
+        match value {
+            IcingaEvent::CheckResult(event) => Self::try_from(event),
+            IcingaEvent::StateChange(event) => Self::try_from(event),
+            IcingaEvent::Notification(event) => Self::try_from(event),
+            IcingaEvent::AcknowledgementSet(event) => Self::try_from(event),
+            IcingaEvent::AcknowledgementCleared(event) => Self::try_from(event),
+            IcingaEvent::CommentAdded(event) => Self::try_from(event),
+            IcingaEvent::CommentRemove(event) => Self::try_from(event),
+            IcingaEvent::DowntimeAdded(event) => Self::try_from(event),
+            IcingaEvent::DowntimeRemoved(event) => Self::try_from(event),
+            IcingaEvent::DowntimeStarted(event) => Self::try_from(event),
+            IcingaEvent::DowntimeTriggered(event) => Self::try_from(event),
+            IcingaEvent::ObjectCreated(event) => Self::try_from(event),
+            IcingaEvent::ObjectDeleted(event) => Self::try_from(event),
+            IcingaEvent::ObjectModified(event) => Self::try_from(event),
+            IcingaEvent::Flapping(event) => Self::try_from(event),
+        }
+    }
+}
+
 /// a service group name
 #[derive(
     Debug,
@@ -257,3 +459,85 @@ pub struct IcingaUserGroupName(pub String);
     derive_more::FromStr,
 )]
 pub struct IcingaZoneName(pub String);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+
+    use crate::testing::fixtures;
+
+    #[test]
+    fn test_service_full_name() -> Result<(), Box<dyn Error>> {
+        let full_name: IcingaServiceFullName = "web1!http!8080".parse()?;
+        assert_eq!(
+            full_name,
+            IcingaServiceFullName::new(
+                IcingaHostName("web1".to_owned()),
+                IcingaServiceName("http!8080".to_owned())
+            )
+        );
+        assert_eq!(full_name.to_string(), "web1!http!8080");
+        assert_eq!(
+            serde_json::from_value::<IcingaServiceFullName>(serde_json::to_value(&full_name)?)?,
+            full_name
+        );
+        for invalid in ["web1", "!http", "web1!"] {
+            assert_eq!(
+                invalid.parse::<IcingaServiceFullName>(),
+                Err(ServiceFullNameParseError(invalid.to_owned()))
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_service_full_name_conversions() -> Result<(), Box<dyn Error>> {
+        let expected: IcingaServiceFullName = "web1!http".parse()?;
+        let service: IcingaService = serde_json::from_value(fixtures::service("web1", "http"))?;
+        assert_eq!(IcingaServiceFullName::from(&service), expected);
+        let downtime: IcingaDowntime = serde_json::from_value(fixtures::downtime(
+            "web1!http!maintenance",
+            "maintenance",
+            "web1",
+            Some("http"),
+            serde_json::json!({}),
+        ))?;
+        assert_eq!(IcingaServiceFullName::try_from(&downtime)?, expected);
+        let comment: IcingaComment = serde_json::from_value(fixtures::comment(
+            "web1!note",
+            "note",
+            "web1",
+            None,
+            1,
+            serde_json::json!({}),
+        ))?;
+        assert_eq!(
+            IcingaServiceFullName::try_from(&comment),
+            Err(NotAServiceError)
+        );
+        let host_downtime: IcingaDowntime = serde_json::from_value(fixtures::downtime(
+            "web1!maintenance",
+            "maintenance",
+            "web1",
+            None,
+            serde_json::json!({}),
+        ))?;
+        assert_eq!(
+            host_downtime.service_name,
+            Some(IcingaServiceName(String::new()))
+        );
+        assert_eq!(
+            IcingaServiceFullName::try_from(&host_downtime),
+            Err(NotAServiceError)
+        );
+        let event = IcingaEvent::ObjectModified(IcingaEventObjectModified {
+            timestamp: crate::types::timestamp::IcingaTimestamp::now(),
+            object_type: IcingaObjectType::Service,
+            object_name: "web1!http".to_owned(),
+        });
+        assert_eq!(IcingaServiceFullName::try_from(&event)?, expected);
+        Ok(())
+    }
+}
@@ -57,11 +57,12 @@ pub trait QueryableObject {
 
 /// implement a query REST API Endpoint for the given Icinga type with join support
 macro_rules! query_with_joins {
-    ($name:ident, $builder_name:ident, $object_category:ident, $path_component:ident, $return_type:ident, $join_types:ident, $join_return_type:ident, $object_type:expr, $url_fragment:expr) => {
+    ($name:ident, $builder_name:ident, $object_category:ident, $path_component:ident, $return_type:ident, $attribute_type:ident, $partial_type:ident, $join_types:ident, $join_return_type:ident, $object_type:expr, $url_fragment:expr) => {
         use std::collections::BTreeMap;
 
 #[rustfmt::skip]
         use crate::types::{
+            attributes::add_attrs_to_url,
             enums::object_type::IcingaObjectType,
             filter::IcingaFilter,
             join_types::{
@@ -71,8 +72,8 @@ macro_rules! query_with_joins {
             },
             metadata::{add_meta_to_url, IcingaMetadataType},
             query::{QueryableObject, QueryResultObject, QueryResultObjectWithJoins, ResultsWrapper},
-            rest::{RestApiEndpoint, RestApiResponse},
-            $object_category::$path_component::$return_type,
+            rest::{HttpMethod, RestApiEndpoint, RestApiResponse},
+            $object_category::$path_component::{$attribute_type, $partial_type, $return_type},
         };
 
         /// query for Icinga objects of this type
@@ -92,6 +93,11 @@ macro_rules! query_with_joins {
             /// the metadata to return along with each result
             #[builder(default, setter(strip_option, into))]
             meta: Option<Vec<IcingaMetadataType>>,
+            /// the attributes to return for each result, all attributes if unset
+            ///
+            /// use the partial result type if this is set
+            #[builder(default, setter(strip_option, into))]
+            attrs: Option<Vec<$attribute_type>>,
             /// filter the results
             #[builder(default, setter(strip_option, into))]
             filter: Option<IcingaFilter>,
@@ -141,8 +147,8 @@ macro_rules! query_with_joins {
         impl RestApiEndpoint for $name {
             type RequestBody = IcingaFilter;
 
-            fn method(&self) -> Result<reqwest::Method, crate::error::Error> {
-                Ok(reqwest::Method::GET)
+            fn method(&self) -> Result<HttpMethod, crate::error::Error> {
+                Ok(HttpMethod::Get)
             }
 
             fn url(&self, base_url: &url::Url) -> Result<url::Url, crate::error::Error> {
@@ -153,7 +159,10 @@ macro_rules! query_with_joins {
                     add_joins_to_url(&mut url, &joins)?;
                 }
                 if let Some(meta) = &self.meta {
-                    add_meta_to_url(&mut url, &meta)?;
+                    add_meta_to_url(&mut url, &meta);
+                }
+                if let Some(attrs) = &self.attrs {
+                    add_attrs_to_url(&mut url, &attrs);
                 }
                 Ok(url)
             }
@@ -170,6 +179,8 @@ macro_rules! query_with_joins {
 
         impl RestApiResponse<$name> for ResultsWrapper<QueryResultObject<$return_type>> {}
 
+        impl RestApiResponse<$name> for ResultsWrapper<QueryResultObject<$partial_type>> {}
+
         impl RestApiResponse<$name>
             for ResultsWrapper<QueryResultObject<BTreeMap<String, serde_json::Value>>>
         {
@@ -180,6 +191,11 @@ macro_rules! query_with_joins {
         {
         }
 
+        impl RestApiResponse<$name>
+            for ResultsWrapper<QueryResultObjectWithJoins<$partial_type, $join_return_type>>
+        {
+        }
+
         impl RestApiResponse<$name>
             for ResultsWrapper<
                 QueryResultObjectWithJoins<BTreeMap<String, serde_json::Value>, $join_return_type>,
@@ -209,17 +225,18 @@ pub(crate) use query_with_joins;
 
 /// implement a query REST API Endpoint for the given Icinga type without join support
 macro_rules! query {
-    ($name:ident, $builder_name:ident, $object_category:ident, $path_component:ident, $return_type:ident, $object_type:expr, $url_fragment:expr) => {
+    ($name:ident, $builder_name:ident, $object_category:ident, $path_component:ident, $return_type:ident, $attribute_type:ident, $partial_type:ident, $object_type:expr, $url_fragment:expr) => {
         use std::collections::BTreeMap;
 
 #[rustfmt::skip]
         use crate::types::{
+            attributes::add_attrs_to_url,
             enums::object_type::IcingaObjectType,
             filter::IcingaFilter,
             metadata::{add_meta_to_url, IcingaMetadataType},
             query::{QueryableObject, QueryResultObject, ResultsWrapper},
-            rest::{RestApiEndpoint, RestApiResponse},
-            $object_category::$path_component::$return_type,
+            rest::{HttpMethod, RestApiEndpoint, RestApiResponse},
+            $object_category::$path_component::{$attribute_type, $partial_type, $return_type},
         };
 
         /// query for Icinga objects of this type
@@ -236,6 +253,11 @@ macro_rules! query {
             /// the metadata to return along with each result
             #[builder(default, setter(strip_option, into))]
             meta: Option<Vec<IcingaMetadataType>>,
+            /// the attributes to return for each result, all attributes if unset
+            ///
+            /// use the partial result type if this is set
+            #[builder(default, setter(strip_option, into))]
+            attrs: Option<Vec<$attribute_type>>,
             /// filter the results
             #[builder(default, setter(strip_option, into))]
             filter: Option<IcingaFilter>,
@@ -285,8 +307,8 @@ macro_rules! query {
         impl RestApiEndpoint for $name {
             type RequestBody = IcingaFilter;
 
-            fn method(&self) -> Result<reqwest::Method, crate::error::Error> {
-                Ok(reqwest::Method::GET)
+            fn method(&self) -> Result<HttpMethod, crate::error::Error> {
+                Ok(HttpMethod::Get)
             }
 
             fn url(&self, base_url: &url::Url) -> Result<url::Url, crate::error::Error> {
@@ -294,7 +316,10 @@ macro_rules! query {
                     .join($url_fragment)
                     .map_err(crate::error::Error::CouldNotParseUrlFragment)?;
                 if let Some(meta) = &self.meta {
-                    add_meta_to_url(&mut url, &meta)?;
+                    add_meta_to_url(&mut url, &meta);
+                }
+                if let Some(attrs) = &self.attrs {
+                    add_attrs_to_url(&mut url, &attrs);
                 }
                 Ok(url)
             }
@@ -311,6 +336,8 @@ macro_rules! query {
 
         impl RestApiResponse<$name> for ResultsWrapper<QueryResultObject<$return_type>> {}
 
+        impl RestApiResponse<$name> for ResultsWrapper<QueryResultObject<$partial_type>> {}
+
         impl RestApiResponse<$name>
             for ResultsWrapper<QueryResultObject<BTreeMap<String, serde_json::Value>>>
         {
@@ -318,3 +345,120 @@ macro_rules! query {
     };
 }
 pub(crate) use query;
+
+/// implement a REST API Endpoint returning a single Icinga object of the given type by name
+///
+/// the object name in the URL consists of the given fields joined with `!`,
+/// e.g. `host!service` for services
+macro_rules! get_object {
+    ($name:ident, $builder_name:ident, $object_category:ident, $path_component:ident, $return_type:ident, $attribute_type:ident, $partial_type:ident, $url_fragment:expr, $($field:ident: $field_type:ty),+) => {
+        /// retrieve a single Icinga object of this type by name
+        ///
+        /// returns `None` if the object does not exist
+        #[allow(
+            clippy::missing_errors_doc,
+            reason = "derive_builder generated build() returns Result; #[expect] does not propagate through derive_builder, only #[allow] does"
+        )]
+        #[derive(Debug, Clone, derive_builder::Builder)]
+        #[builder(build_fn(error = "crate::error::Error"), derive(Debug))]
+        pub struct $name {
+            $(
+                /// part of the name of the object to retrieve
+                #[builder(setter(into))]
+                $field: $field_type,
+            )+
+            /// the metadata to return along with the result
+            #[builder(default, setter(strip_option, into))]
+            meta: Option<Vec<crate::types::metadata::IcingaMetadataType>>,
+            /// the attributes to return, all attributes if unset
+            ///
+            /// use the partial result type if this is set
+            #[builder(default, setter(strip_option, into))]
+            attrs: Option<Vec<crate::types::$object_category::$path_component::$attribute_type>>,
+        }
+
+        impl $name {
+            /// create a new builder for this endpoint
+            ///
+            /// this is usually the first step to calling this REST API endpoint
+            #[must_use]
+            pub fn builder() -> $builder_name {
+                $builder_name::default()
+            }
+
+            /// the full name of the object as used in the URL
+            fn object_name(&self) -> String {
+                [$(self.$field.to_string()),+].join("!")
+            }
+        }
+
+        impl crate::types::rest::RestApiEndpoint for $name {
+            type RequestBody = ();
+
+            fn method(&self) -> Result<crate::types::rest::HttpMethod, crate::error::Error> {
+                Ok(crate::types::rest::HttpMethod::Get)
+            }
+
+            fn url(&self, base_url: &url::Url) -> Result<url::Url, crate::error::Error> {
+                let mut url = base_url
+                    .join($url_fragment)
+                    .map_err(crate::error::Error::CouldNotParseUrlFragment)?;
+                url.path_segments_mut()
+                    .map_err(|()| {
+                        crate::error::Error::CouldNotParseUrlFragment(
+                            url::ParseError::RelativeUrlWithCannotBeABaseBase,
+                        )
+                    })?
+                    .push(&self.object_name());
+                if let Some(meta) = &self.meta {
+                    crate::types::metadata::add_meta_to_url(&mut url, &meta);
+                }
+                if let Some(attrs) = &self.attrs {
+                    crate::types::attributes::add_attrs_to_url(&mut url, &attrs);
+                }
+                Ok(url)
+            }
+
+            fn request_body(
+                &self,
+            ) -> Result<Option<std::borrow::Cow<'_, Self::RequestBody>>, crate::error::Error>
+            where
+                Self::RequestBody: Clone + serde::Serialize + std::fmt::Debug,
+            {
+                Ok(None)
+            }
+
+            fn returns_single_object(&self) -> bool {
+                true
+            }
+        }
+
+        impl crate::types::rest::RestApiResponse<$name>
+            for Option<
+                crate::types::query::QueryResultObject<
+                    crate::types::$object_category::$path_component::$return_type,
+                >,
+            >
+        {
+        }
+
+        impl crate::types::rest::RestApiResponse<$name>
+            for Option<
+                crate::types::query::QueryResultObject<
+                    crate::types::$object_category::$path_component::$partial_type,
+                >,
+            >
+        {
+        }
+
+        impl crate::types::rest::RestApiResponse<$name>
+            for Option<
+                crate::types::query::QueryResultObject<
+                    std::collections::BTreeMap<String, serde_json::Value>,
+                >,
+            >
+        {
+        }
+    };
+}
+pub(crate) use get_object;
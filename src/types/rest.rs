@@ -2,6 +2,53 @@
 //!
 //! (as opposed to the streaming event long-polling API parts)
 
+/// the HTTP methods used by the Icinga API
+///
+/// this is used instead of the HTTP library's type so API endpoints can be
+/// described without depending on an HTTP client
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HttpMethod {
+    /// GET, used for queries
+    Get,
+    /// POST, used for actions and object modification
+    Post,
+    /// PUT, used for object creation
+    Put,
+    /// DELETE, used for object deletion
+    Delete,
+}
+
+impl HttpMethod {
+    /// the method as it appears in a request
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Post => "POST",
+            Self::Put => "PUT",
+            Self::Delete => "DELETE",
+        }
+    }
+}
+
+impl std::fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "client")]
+impl From<HttpMethod> for http::Method {
+    fn from(method: HttpMethod) -> Self {
+        match method {
+            HttpMethod::Get => Self::GET,
+            HttpMethod::Post => Self::POST,
+            HttpMethod::Put => Self::PUT,
+            HttpMethod::Delete => Self::DELETE,
+        }
+    }
+}
+
 /// a trait for objects describing a REST API endpoint
 ///
 /// this is implemented by types which contain all the necessary information
@@ -19,7 +66,7 @@ pub trait RestApiEndpoint {
     /// # Errors
     ///
     /// this should return an error if something went wrong in determining the request method
-    fn method(&self) -> Result<reqwest::Method, crate::error::Error>;
+    fn method(&self) -> Result<HttpMethod, crate::error::Error>;
 
     /// returns the URL to use for the request based on the base URL passed in
     /// as a parameter
@@ -41,8 +88,51 @@ pub trait RestApiEndpoint {
     ) -> Result<Option<std::borrow::Cow<'_, Self::RequestBody>>, crate::error::Error>
     where
         Self::RequestBody: Clone + serde::Serialize + std::fmt::Debug;
+
+    /// whether this endpoint returns a single object instead of a list
+    ///
+    /// for these the clients deserialize the response type from the only
+    /// element of the results array and treat a 404 response as `null`,
+    /// so the response type is usually an `Option`
+    fn returns_single_object(&self) -> bool {
+        false
+    }
+}
+
+/// the response to a REST API call without any parsing beyond JSON
+///
+/// returned by the `rest_raw` methods of the clients
+#[cfg(feature = "client")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawResponse {
+    /// the HTTP status of the response
+    pub status: http::StatusCode,
+    /// the response headers, empty for responses replayed from a cassette
+    pub headers: http::HeaderMap,
+    /// the response body
+    pub body: serde_json::Value,
 }
 
 /// this is a marker trait that marks a type as a valid response type for a
 /// given RestApiEndpoint
 pub trait RestApiResponse<ApiEndpoint> {}
+
+#[cfg(test)]
+#[cfg(feature = "client")]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_http_method() {
+        for (method, expected) in [
+            (HttpMethod::Get, http::Method::GET),
+            (HttpMethod::Post, http::Method::POST),
+            (HttpMethod::Put, http::Method::PUT),
+            (HttpMethod::Delete, http::Method::DELETE),
+        ] {
+            assert_eq!(method.to_string(), expected.as_str());
+            assert_eq!(http::Method::from(method), expected);
+        }
+    }
+}
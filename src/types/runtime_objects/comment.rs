@@ -7,6 +7,8 @@
 use serde::{Deserialize, Serialize};
 
 use crate::serde::{deserialize_optional_icinga_timestamp, serialize_optional_icinga_timestamp};
+use crate::types::attributes::IcingaAttribute;
+use crate::types::common::config_object::IcingaConfigObjectPartial;
 use crate::types::enums::comment_type::IcingaCommentType;
 use crate::types::{
     common::config_object::IcingaConfigObject,
@@ -50,3 +52,121 @@ pub struct IcingaComment {
     /// TODO: what does this mean?
     pub legacy_id: u64,
 }
+
+/// the attributes of [IcingaComment] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IcingaCommentPartial {
+    /// type of icinga object, should always be Comment for this
+    #[serde(rename = "type")]
+    pub object_type: Option<IcingaObjectType>,
+    /// shared config object fields
+    #[serde(flatten)]
+    pub config_object: IcingaConfigObjectPartial,
+    /// the host this comment is attached to
+    pub host_name: Option<IcingaHostName>,
+    /// the service this comment is attached to, if not specified it is a host comment
+    pub service_name: Option<IcingaServiceName>,
+    /// the time the comment was entered
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_icinga_timestamp",
+        deserialize_with = "deserialize_optional_icinga_timestamp"
+    )]
+    pub entry_time: Option<time::OffsetDateTime>,
+    /// the type of comment (user or acknowledgement)
+    pub entry_type: Option<IcingaCommentType>,
+    /// the comment author
+    pub author: Option<String>,
+    /// the comment body
+    pub text: Option<String>,
+    /// Only evaluated for entry_type Acknowledgement. true does not remove the comment when the acknowledgement is removed.
+    pub persistent: Option<bool>,
+    /// the comment will expire at this time
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_optional_icinga_timestamp",
+        deserialize_with = "deserialize_optional_icinga_timestamp"
+    )]
+    pub expire_time: Option<time::OffsetDateTime>,
+    /// TODO: what does this mean?
+    pub legacy_id: Option<u64>,
+}
+
+/// the attributes of [IcingaComment] that can be requested with the attrs parameter of a query
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IcingaCommentAttribute {
+    /// type of icinga object, should always be Comment for this
+    ObjectType,
+    /// full object name
+    FullName,
+    /// short object name
+    Name,
+    /// object is active (being checked)
+    Active,
+    /// whether to run a check once or everywhere
+    HaMode,
+    /// original values of object attributes modified at runtime
+    OriginalAttributes,
+    /// configuration package name this object belongs to, _etc for local configuration
+    /// _api for runtime created objects
+    Package,
+    /// object has been paused at runtime
+    Paused,
+    /// location information whether the configuration files are stored
+    SourceLocation,
+    /// templates imported on object compilation
+    Templates,
+    /// timestamp when the object was created or modified. syncred throughout cluster nodes
+    Version,
+    /// the zone this object is a member of
+    Zone,
+    /// the host this comment is attached to
+    HostName,
+    /// the service this comment is attached to, if not specified it is a host comment
+    ServiceName,
+    /// the time the comment was entered
+    EntryTime,
+    /// the type of comment (user or acknowledgement)
+    EntryType,
+    /// the comment author
+    Author,
+    /// the comment body
+    Text,
+    /// Only evaluated for entry_type Acknowledgement. true does not remove the comment when the acknowledgement is removed.
+    Persistent,
+    /// the comment will expire at this time
+    ExpireTime,
+    /// TODO: what does this mean?
+    LegacyId,
+}
+
+impl IcingaAttribute for IcingaCommentAttribute {}
+
+impl std::fmt::Display for IcingaCommentAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ObjectType => write!(f, "type"),
+            Self::FullName => write!(f, "__name"),
+            Self::Name => write!(f, "name"),
+            Self::Active => write!(f, "active"),
+            Self::HaMode => write!(f, "ha_mode"),
+            Self::OriginalAttributes => write!(f, "original_attributes"),
+            Self::Package => write!(f, "package"),
+            Self::Paused => write!(f, "paused"),
+            Self::SourceLocation => write!(f, "source_location"),
+            Self::Templates => write!(f, "templates"),
+            Self::Version => write!(f, "version"),
+            Self::Zone => write!(f, "zone"),
+            Self::HostName => write!(f, "host_name"),
+            Self::ServiceName => write!(f, "service_name"),
+            Self::EntryTime => write!(f, "entry_time"),
+            Self::EntryType => write!(f, "entry_type"),
+            Self::Author => write!(f, "author"),
+            Self::Text => write!(f, "text"),
+            Self::Persistent => write!(f, "persistent"),
+            Self::ExpireTime => write!(f, "expire_time"),
+            Self::LegacyId => write!(f, "legacy_id"),
+        }
+    }
+}
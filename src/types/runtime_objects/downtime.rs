@@ -7,10 +7,12 @@
 use serde::{Deserialize, Serialize};
 
 use crate::serde::{
-    deserialize_optional_icinga_timestamp, deserialize_optional_seconds_as_duration,
-    serialize_optional_duration_as_seconds, serialize_optional_icinga_timestamp,
+    deserialize_optional_seconds_as_duration, serialize_optional_duration_as_seconds,
 };
+use crate::types::attributes::IcingaAttribute;
+use crate::types::common::config_object::IcingaConfigObjectPartial;
 use crate::types::names::{IcingaDowntimeName, IcingaScheduledDowntimeName, IcingaZoneName};
+use crate::types::timestamp::IcingaTimestamp;
 use crate::types::{
     common::config_object::IcingaConfigObject,
     enums::object_type::IcingaObjectType,
@@ -31,33 +33,17 @@ pub struct IcingaDowntime {
     /// the service for which the downtime will be scheduled, if not specified this is a host downtime
     pub service_name: Option<IcingaServiceName>,
     /// the time when the downtime was created (but not necessarily started)
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub entry_time: Option<time::OffsetDateTime>,
+    pub entry_time: IcingaTimestamp,
     /// the author of the downtime
     pub author: String,
     /// the comment displayed for the downtime
     pub comment: String,
     /// the time when the downtime starts
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub start_time: Option<time::OffsetDateTime>,
+    pub start_time: IcingaTimestamp,
     /// the time when the downtime ends
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub end_time: Option<time::OffsetDateTime>,
+    pub end_time: IcingaTimestamp,
     /// the time when this downtime was triggered
-    #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
-    )]
-    pub trigger_time: Option<time::OffsetDateTime>,
+    pub trigger_time: IcingaTimestamp,
     /// is this a fixed downtime
     pub fixed: Option<bool>,
     /// the duration of the downtime
@@ -77,11 +63,69 @@ pub struct IcingaDowntime {
     /// TODO: what does this mean
     pub legacy_id: u64,
     /// when was this downtime removed
+    pub remove_time: IcingaTimestamp,
+    /// was this downtime canceled
+    pub was_canceled: Option<bool>,
+    /// TODO: what does this mean
+    pub config_owner: Option<String>,
+    /// TODO: what does this mean
+    pub config_owner_hash: Option<String>,
+    /// TODO: what does this mean
+    pub authoritative_zone: Option<IcingaZoneName>,
+}
+
+/// the attributes of [IcingaDowntime] requested with the attrs parameter of a query,
+/// all of them are optional since they are only returned if requested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IcingaDowntimePartial {
+    /// type of icinga object, should always be Downtime for this
+    #[serde(rename = "type")]
+    pub object_type: Option<IcingaObjectType>,
+    /// shared config object fields
+    #[serde(flatten)]
+    pub config_object: IcingaConfigObjectPartial,
+    /// the host for which the downtime will be scheduled
+    pub host_name: Option<IcingaHostName>,
+    /// the service for which the downtime will be scheduled, if not specified this is a host downtime
+    pub service_name: Option<IcingaServiceName>,
+    /// the time when the downtime was created (but not necessarily started)
+    #[serde(default)]
+    pub entry_time: IcingaTimestamp,
+    /// the author of the downtime
+    pub author: Option<String>,
+    /// the comment displayed for the downtime
+    pub comment: Option<String>,
+    /// the time when the downtime starts
+    #[serde(default)]
+    pub start_time: IcingaTimestamp,
+    /// the time when the downtime ends
+    #[serde(default)]
+    pub end_time: IcingaTimestamp,
+    /// the time when this downtime was triggered
+    #[serde(default)]
+    pub trigger_time: IcingaTimestamp,
+    /// is this a fixed downtime
+    pub fixed: Option<bool>,
+    /// the duration of the downtime
+    #[serde(default)]
     #[serde(
-        serialize_with = "serialize_optional_icinga_timestamp",
-        deserialize_with = "deserialize_optional_icinga_timestamp"
+        serialize_with = "serialize_optional_duration_as_seconds",
+        deserialize_with = "deserialize_optional_seconds_as_duration"
     )]
-    pub remove_time: Option<time::OffsetDateTime>,
+    pub duration: Option<time::Duration>,
+    /// which other downtime triggered this one
+    pub triggered_by: Option<IcingaDowntimeName>,
+    /// which scheduled downtime scheduled this downtime
+    pub scheduled_by: Option<IcingaScheduledDowntimeName>,
+    /// the parent downtime
+    pub parent: Option<IcingaDowntimeName>,
+    /// downtimes which should be triggered by this one
+    pub triggers: Option<Vec<IcingaDowntimeName>>,
+    /// TODO: what does this mean
+    pub legacy_id: Option<u64>,
+    /// when was this downtime removed
+    #[serde(default)]
+    pub remove_time: IcingaTimestamp,
     /// was this downtime canceled
     pub was_canceled: Option<bool>,
     /// TODO: what does this mean
@@ -91,3 +135,114 @@ pub struct IcingaDowntime {
     /// TODO: what does this mean
     pub authoritative_zone: Option<IcingaZoneName>,
 }
+
+/// the attributes of [IcingaDowntime] that can be requested with the attrs parameter of a query
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IcingaDowntimeAttribute {
+    /// type of icinga object, should always be Downtime for this
+    ObjectType,
+    /// full object name
+    FullName,
+    /// short object name
+    Name,
+    /// object is active (being checked)
+    Active,
+    /// whether to run a check once or everywhere
+    HaMode,
+    /// original values of object attributes modified at runtime
+    OriginalAttributes,
+    /// configuration package name this object belongs to, _etc for local configuration
+    /// _api for runtime created objects
+    Package,
+    /// object has been paused at runtime
+    Paused,
+    /// location information whether the configuration files are stored
+    SourceLocation,
+    /// templates imported on object compilation
+    Templates,
+    /// timestamp when the object was created or modified. syncred throughout cluster nodes
+    Version,
+    /// the zone this object is a member of
+    Zone,
+    /// the host for which the downtime will be scheduled
+    HostName,
+    /// the service for which the downtime will be scheduled, if not specified this is a host downtime
+    ServiceName,
+    /// the time when the downtime was created (but not necessarily started)
+    EntryTime,
+    /// the author of the downtime
+    Author,
+    /// the comment displayed for the downtime
+    Comment,
+    /// the time when the downtime starts
+    StartTime,
+    /// the time when the downtime ends
+    EndTime,
+    /// the time when this downtime was triggered
+    TriggerTime,
+    /// is this a fixed downtime
+    Fixed,
+    /// the duration of the downtime
+    Duration,
+    /// which other downtime triggered this one
+    TriggeredBy,
+    /// which scheduled downtime scheduled this downtime
+    ScheduledBy,
+    /// the parent downtime
+    Parent,
+    /// downtimes which should be triggered by this one
+    Triggers,
+    /// TODO: what does this mean
+    LegacyId,
+    /// when was this downtime removed
+    RemoveTime,
+    /// was this downtime canceled
+    WasCanceled,
+    /// TODO: what does this mean
+    ConfigOwner,
+    /// TODO: what does this mean
+    ConfigOwnerHash,
+    /// TODO: what does this mean
+    AuthoritativeZone,
+}
+
+impl IcingaAttribute for IcingaDowntimeAttribute {}
+
+impl std::fmt::Display for IcingaDowntimeAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ObjectType => write!(f, "type"),
+            Self::FullName => write!(f, "__name"),
+            Self::Name => write!(f, "name"),
+            Self::Active => write!(f, "active"),
+            Self::HaMode => write!(f, "ha_mode"),
+            Self::OriginalAttributes => write!(f, "original_attributes"),
+            Self::Package => write!(f, "package"),
+            Self::Paused => write!(f, "paused"),
+            Self::SourceLocation => write!(f, "source_location"),
+            Self::Templates => write!(f, "templates"),
+            Self::Version => write!(f, "version"),
+            Self::Zone => write!(f, "zone"),
+            Self::HostName => write!(f, "host_name"),
+            Self::ServiceName => write!(f, "service_name"),
+            Self::EntryTime => write!(f, "entry_time"),
+            Self::Author => write!(f, "author"),
+            Self::Comment => write!(f, "comment"),
+            Self::StartTime => write!(f, "start_time"),
+            Self::EndTime => write!(f, "end_time"),
+            Self::TriggerTime => write!(f, "trigger_time"),
+            Self::Fixed => write!(f, "fixed"),
+            Self::Duration => write!(f, "duration"),
+            Self::TriggeredBy => write!(f, "triggered_by"),
+            Self::ScheduledBy => write!(f, "scheduled_by"),
+            Self::Parent => write!(f, "parent"),
+            Self::Triggers => write!(f, "triggers"),
+            Self::LegacyId => write!(f, "legacy_id"),
+            Self::RemoveTime => write!(f, "remove_time"),
+            Self::WasCanceled => write!(f, "was_canceled"),
+            Self::ConfigOwner => write!(f, "config_owner"),
+            Self::ConfigOwnerHash => write!(f, "config_owner_hash"),
+            Self::AuthoritativeZone => write!(f, "authoritative_zone"),
+        }
+    }
+}
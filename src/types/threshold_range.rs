@@ -0,0 +1,269 @@
+//! Nagios threshold ranges as used by check plugins
+//!
+//! [Nagios Plugin Development Guidelines](https://nagios-plugins.org/doc/guidelines.html#THRESHOLDFORMAT)
+//!
+//! | range    | alerts if the value is   |
+//! |----------|--------------------------|
+//! | `10`     | < 0 or > 10              |
+//! | `10:`    | < 10                     |
+//! | `~:10`   | > 10                     |
+//! | `10:20`  | < 10 or > 20             |
+//! | `@10:20` | >= 10 and <= 20          |
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// a threshold range in the Nagios plugin range format
+///
+/// this is used for the warn and crit values of performance data and can be
+/// deserialized from custom variables holding thresholds (e.g. `ping_wrta`
+/// or `load_wload1`) with
+/// [CustomVarHolder::custom_var_deserialized](crate::types::common::custom_var_object::CustomVarHolder::custom_var_deserialized),
+/// both numbers and strings are accepted
+///
+/// not Eq because it contains floating point values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IcingaThresholdRange {
+    /// the start of the range, None means negative infinity (`~`)
+    pub start: Option<f64>,
+    /// the end of the range, None means positive infinity
+    pub end: Option<f64>,
+    /// alert if the value is inside the range (`@`) instead of outside of it
+    pub inside: bool,
+}
+
+/// error parsing a threshold range
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ThresholdRangeParseError {
+    /// the range is empty
+    #[error("empty threshold range")]
+    Empty,
+    /// the start or end of the range is not a number
+    #[error("invalid number in threshold range: {0}")]
+    InvalidNumber(String),
+    /// the start of the range is greater than the end
+    #[error("start of threshold range is greater than its end: {0}")]
+    StartGreaterThanEnd(String),
+}
+
+impl IcingaThresholdRange {
+    /// the range from 0 to `end`, the meaning of a plain number as a threshold
+    #[must_use]
+    pub const fn from_zero_to(end: f64) -> Self {
+        Self {
+            start: Some(0f64),
+            end: Some(end),
+            inside: false,
+        }
+    }
+
+    /// whether the value is within the start and end of the range (inclusive)
+    #[must_use]
+    pub fn contains(&self, value: f64) -> bool {
+        self.start.is_none_or(|start| value >= start) && self.end.is_none_or(|end| value <= end)
+    }
+
+    /// whether the value triggers an alert for this threshold
+    #[must_use]
+    pub fn alerts(&self, value: f64) -> bool {
+        self.contains(value) == self.inside
+    }
+
+    /// the range if it is a plain number (`0:end` without `@`)
+    #[must_use]
+    pub fn as_plain_number(&self) -> Option<f64> {
+        match (self.start, self.end, self.inside) {
+            (Some(0f64), Some(end), false) => Some(end),
+            _ => None,
+        }
+    }
+}
+
+impl From<f64> for IcingaThresholdRange {
+    fn from(value: f64) -> Self {
+        Self::from_zero_to(value)
+    }
+}
+
+impl std::str::FromStr for IcingaThresholdRange {
+    type Err = ThresholdRangeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let range = s.trim();
+        let (inside, range) = range
+            .strip_prefix('@')
+            .map_or((false, range), |range| (true, range));
+        if range.is_empty() {
+            return Err(ThresholdRangeParseError::Empty);
+        }
+        // non-finite bounds (inf, NaN) make the range comparisons meaningless
+        let parse_number = |number: &str| {
+            number
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .ok_or_else(|| ThresholdRangeParseError::InvalidNumber(s.to_owned()))
+        };
+        let (start, end) = match range.split_once(':') {
+            None => (Some(0f64), Some(parse_number(range)?)),
+            Some((start, end)) => {
+                let start = match start.trim() {
+                    "~" => None,
+                    "" => Some(0f64),
+                    start => Some(parse_number(start)?),
+                };
+                let end = match end.trim() {
+                    "" => None,
+                    end => Some(parse_number(end)?),
+                };
+                (start, end)
+            }
+        };
+        if let (Some(start), Some(end)) = (start, end)
+            && start > end
+        {
+            return Err(ThresholdRangeParseError::StartGreaterThanEnd(s.to_owned()));
+        }
+        Ok(Self { start, end, inside })
+    }
+}
+
+impl std::fmt::Display for IcingaThresholdRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.inside {
+            write!(f, "@")?;
+        }
+        match self.start {
+            Some(start) if start == 0f64 && self.end.is_some() => {}
+            Some(start) => write!(f, "{start}:")?,
+            None => write!(f, "~:")?,
+        }
+        if let Some(end) = self.end {
+            write!(f, "{end}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for IcingaThresholdRange {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if let Some(end) = self.as_plain_number() {
+            serializer.serialize_f64(end)
+        } else {
+            serializer.collect_str(self)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for IcingaThresholdRange {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        /// the JSON representations of a threshold range
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum NumberOrString {
+            /// a plain number
+            Number(f64),
+            /// a range in the Nagios range format
+            String(String),
+        }
+        match NumberOrString::deserialize(deserializer)? {
+            NumberOrString::Number(end) => Ok(Self::from_zero_to(end)),
+            NumberOrString::String(range) => range.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+
+    #[test]
+    fn test_parse_and_evaluate_ranges() -> Result<(), Box<dyn Error>> {
+        let cases: [(&str, &[f64], &[f64]); 6] = [
+            ("10", &[-1.0, 10.5], &[0.0, 5.0, 10.0]),
+            ("10:", &[9.9, -5.0], &[10.0, 1e9]),
+            ("~:5", &[5.1], &[-1e9, 5.0]),
+            ("10:20", &[9.0, 21.0], &[10.0, 20.0]),
+            ("@10:20", &[10.0, 15.0, 20.0], &[9.0, 21.0]),
+            ("@~:-3.5", &[-3.5, -100.0], &[0.0]),
+        ];
+        for (range, alerting, ok) in cases {
+            let parsed: IcingaThresholdRange = range.parse()?;
+            assert_eq!(parsed.to_string(), range);
+            for value in alerting {
+                assert!(parsed.alerts(*value), "{range} should alert for {value}");
+            }
+            for value in ok {
+                assert!(
+                    !parsed.alerts(*value),
+                    "{range} should not alert for {value}"
+                );
+            }
+        }
+        assert_eq!(
+            ":10".parse::<IcingaThresholdRange>()?,
+            IcingaThresholdRange::from_zero_to(10.0)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_range_errors() {
+        assert_eq!(
+            "@".parse::<IcingaThresholdRange>(),
+            Err(ThresholdRangeParseError::Empty)
+        );
+        assert_eq!(
+            "10:x".parse::<IcingaThresholdRange>(),
+            Err(ThresholdRangeParseError::InvalidNumber("10:x".to_owned()))
+        );
+        for range in [
+            "inf",
+            "NaN",
+            "10:infinity",
+            "~:-inf",
+            "NaN:10",
+            "@nan",
+            "1e999",
+        ] {
+            assert_eq!(
+                range.parse::<IcingaThresholdRange>(),
+                Err(ThresholdRangeParseError::InvalidNumber(range.to_owned()))
+            );
+        }
+        assert_eq!(
+            "20:10".parse::<IcingaThresholdRange>(),
+            Err(ThresholdRangeParseError::StartGreaterThanEnd(
+                "20:10".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_serde_ranges() -> Result<(), Box<dyn Error>> {
+        let ranges: Vec<IcingaThresholdRange> = serde_json::from_str(r#"[100, "@1:2", "20"]"#)?;
+        assert_eq!(
+            ranges,
+            vec![
+                IcingaThresholdRange::from_zero_to(100.0),
+                IcingaThresholdRange {
+                    start: Some(1.0),
+                    end: Some(2.0),
+                    inside: true,
+                },
+                IcingaThresholdRange::from_zero_to(20.0),
+            ]
+        );
+        assert_eq!(serde_json::to_string(&ranges)?, r#"[100.0,"@1:2",20.0]"#);
+        Ok(())
+    }
+}
@@ -0,0 +1,1215 @@
+//! The range syntax of time periods and scheduled downtimes
+//!
+//! [Official Documentation](https://icinga.com/docs/icinga-2/latest/doc/08-advanced-topics/#timeperiods)
+//!
+//! The keys of the `ranges` attribute select days, the values are comma
+//! separated times of day (`HH:MM-HH:MM`, seconds are optional, `24:00` is
+//! the end of the day and a range ending before it starts ends on the next
+//! day) on those days.
+//!
+//! | key                       | days                                       |
+//! |---------------------------|--------------------------------------------|
+//! | `2024-12-24`              | a calendar date                            |
+//! | `day 1`, `day -1`         | the first and last day of every month      |
+//! | `monday`                  | every monday                               |
+//! | `monday 1`, `monday -1`   | the first and last monday of every month   |
+//! | `monday -1 november`      | the last monday of november                |
+//! | `january 1`               | january 1 of every year                    |
+//! | `monday - friday`         | a range of any two days of the same kind   |
+//! | `day 1 - 15`              | the first 15 days of every month           |
+//! | `2024-01-01 / 7`          | every 7th day starting at the date         |
+//! | `day 1 - 15 / 5`          | every 5th day of a range                   |
+//!
+//! Icinga evaluates ranges in the local time zone of the Icinga instance,
+//! here they are evaluated in an [IcingaTimeZone] which maps local times to
+//! their UTC offset, either a fixed offset or a conversion using a time zone
+//! database so segments around daylight saving time changes are correct.
+//!
+//! Only the ranges of the default `LegacyTimePeriod` update function are
+//! supported, other update functions compute their segments in Icinga.
+
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::num::NonZeroU32;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, UtcOffset, Weekday};
+
+use crate::types::{
+    monitoring_objects::time_period::IcingaTimePeriod, names::IcingaTimePeriodName,
+    timestamp::IcingaTimestamp,
+};
+
+/// the weekdays with their names in the range syntax
+const WEEKDAYS: [(Weekday, &str); 7] = [
+    (Weekday::Monday, "monday"),
+    (Weekday::Tuesday, "tuesday"),
+    (Weekday::Wednesday, "wednesday"),
+    (Weekday::Thursday, "thursday"),
+    (Weekday::Friday, "friday"),
+    (Weekday::Saturday, "saturday"),
+    (Weekday::Sunday, "sunday"),
+];
+
+/// the months with their names in the range syntax
+const MONTHS: [(Month, &str); 12] = [
+    (Month::January, "january"),
+    (Month::February, "february"),
+    (Month::March, "march"),
+    (Month::April, "april"),
+    (Month::May, "may"),
+    (Month::June, "june"),
+    (Month::July, "july"),
+    (Month::August, "august"),
+    (Month::September, "september"),
+    (Month::October, "october"),
+    (Month::November, "november"),
+    (Month::December, "december"),
+];
+
+/// error parsing a range of a time period or scheduled downtime
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TimeRangeParseError {
+    /// the key of the range does not select days
+    #[error("invalid days in time range: {0}")]
+    InvalidDays(String),
+    /// the stride of the range is not a positive number
+    #[error("invalid stride in time range: {0}")]
+    InvalidStride(String),
+    /// the value of the range is not a list of times of day
+    #[error("invalid times of day in time range: {0}")]
+    InvalidTimes(String),
+}
+
+/// error evaluating time periods
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TimePeriodError {
+    /// a range of the time period can not be parsed
+    #[error("invalid range in time period {0}: {1}")]
+    InvalidRange(IcingaTimePeriodName, TimeRangeParseError),
+    /// a time period or one it includes or excludes is not known
+    #[error("unknown time period: {0}")]
+    UnknownTimePeriod(IcingaTimePeriodName),
+    /// a time period includes or excludes itself, directly or indirectly
+    #[error("time period {0} includes or excludes itself")]
+    CircularReference(IcingaTimePeriodName),
+}
+
+/// the time zone ranges are evaluated in, usually the one of the Icinga
+/// instance
+///
+/// it maps local times to their UTC offset. For local times that occur twice
+/// or not at all when the clocks change the conversion decides which offset
+/// applies.
+#[derive(Clone)]
+pub struct IcingaTimeZone {
+    /// the UTC offset at a local time
+    offset_at: Arc<dyn Fn(PrimitiveDateTime) -> UtcOffset + Send + Sync>,
+}
+
+impl IcingaTimeZone {
+    /// a time zone using the given conversion from local times to their UTC
+    /// offset, e.g. one looking up the rules of the time zone in a time zone
+    /// database
+    #[must_use]
+    pub fn new(offset_at: impl Fn(PrimitiveDateTime) -> UtcOffset + Send + Sync + 'static) -> Self {
+        Self {
+            offset_at: Arc::new(offset_at),
+        }
+    }
+
+    /// a time zone without daylight saving time
+    #[must_use]
+    pub fn fixed(offset: UtcOffset) -> Self {
+        Self::new(move |_local| offset)
+    }
+
+    /// the UTC offset at a local time
+    #[must_use]
+    pub fn offset_at(&self, local: PrimitiveDateTime) -> UtcOffset {
+        (self.offset_at)(local)
+    }
+
+    /// the instant of a local time
+    fn assume(&self, local: PrimitiveDateTime) -> OffsetDateTime {
+        local.assume_offset(self.offset_at(local))
+    }
+}
+
+impl std::fmt::Debug for IcingaTimeZone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IcingaTimeZone").finish_non_exhaustive()
+    }
+}
+
+impl From<UtcOffset> for IcingaTimeZone {
+    fn from(value: UtcOffset) -> Self {
+        Self::fixed(value)
+    }
+}
+
+impl From<&Self> for IcingaTimeZone {
+    fn from(value: &Self) -> Self {
+        value.clone()
+    }
+}
+
+/// a single day or a day that repeats every week, month or year
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IcingaDay {
+    /// a calendar date (`2024-12-24`)
+    Date(Date),
+    /// a day of every month, negative days count from the end of the month
+    /// (`day 1`, `day -1`)
+    DayOfMonth(i8),
+    /// a day of every week (`monday`)
+    Weekday(Weekday),
+    /// the nth weekday of a month, negative values count from the end of the
+    /// month (`monday 1`, `monday -1 november`)
+    NthWeekday {
+        /// the weekday
+        weekday: Weekday,
+        /// which of the weekdays in the month
+        nth: i8,
+        /// the month, every month if None
+        month: Option<Month>,
+    },
+    /// a day of a month every year, negative days count from the end of the
+    /// month (`january 1`, `february -1`)
+    MonthDay {
+        /// the month
+        month: Month,
+        /// the day of the month
+        day: i8,
+    },
+}
+
+/// how often an [IcingaDay] repeats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Repetition {
+    /// never
+    Never,
+    /// every week
+    Weekly,
+    /// every month
+    Monthly,
+    /// every year
+    Yearly,
+}
+
+impl IcingaDay {
+    /// how often the day repeats
+    const fn repetition(self) -> Repetition {
+        match self {
+            Self::Date(_) => Repetition::Never,
+            Self::Weekday(_) => Repetition::Weekly,
+            Self::DayOfMonth(_) | Self::NthWeekday { month: None, .. } => Repetition::Monthly,
+            Self::NthWeekday { month: Some(_), .. } | Self::MonthDay { .. } => Repetition::Yearly,
+        }
+    }
+
+    /// the occurrence of the day in the given month (or year for days
+    /// repeating every year), None for weekdays and days that do not exist
+    /// in that month (e.g. `day 31` in april)
+    fn resolve(self, year: i32, month: Month) -> Option<Date> {
+        match self {
+            Self::Date(date) => Some(date),
+            Self::Weekday(_) => None,
+            Self::DayOfMonth(day) => day_of_month(year, month, day),
+            Self::NthWeekday {
+                weekday,
+                nth,
+                month: nth_month,
+            } => nth_weekday(year, nth_month.unwrap_or(month), weekday, nth),
+            Self::MonthDay { month, day } => day_of_month(year, month, day),
+        }
+    }
+
+    /// whether the date is an occurrence of this day
+    fn matches(self, date: Date) -> bool {
+        match self {
+            Self::Weekday(weekday) => date.weekday() == weekday,
+            day => day.resolve(date.year(), date.month()) == Some(date),
+        }
+    }
+
+    /// parse the whitespace separated tokens of a single day, a plain number
+    /// is a day of the same kind as `start` in the end of a range
+    fn parse(tokens: &[&str], start: Option<Self>) -> Option<Self> {
+        match *tokens {
+            [date] if date.contains('-') && !date.starts_with('-') => {
+                parse_date(date).map(Self::Date)
+            }
+            ["day", day] => parse_number(day, 31).map(Self::DayOfMonth),
+            [name] => {
+                if let Some(weekday) = parse_weekday(name) {
+                    Some(Self::Weekday(weekday))
+                } else {
+                    let day = parse_number(name, 31)?;
+                    match start? {
+                        Self::DayOfMonth(_) => Some(Self::DayOfMonth(day)),
+                        Self::MonthDay { month, .. } => Some(Self::MonthDay { month, day }),
+                        _ => None,
+                    }
+                }
+            }
+            [name, number] => {
+                if let Some(weekday) = parse_weekday(name) {
+                    Some(Self::NthWeekday {
+                        weekday,
+                        nth: parse_number(number, 5)?,
+                        month: None,
+                    })
+                } else {
+                    Some(Self::MonthDay {
+                        month: parse_month(name)?,
+                        day: parse_number(number, 31)?,
+                    })
+                }
+            }
+            [weekday, nth, month] => Some(Self::NthWeekday {
+                weekday: parse_weekday(weekday)?,
+                nth: parse_number(nth, 5)?,
+                month: Some(parse_month(month)?),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for IcingaDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Date(date) => write!(f, "{date}"),
+            Self::DayOfMonth(day) => write!(f, "day {day}"),
+            Self::Weekday(weekday) => write!(f, "{}", weekday_name(*weekday)),
+            Self::NthWeekday {
+                weekday,
+                nth,
+                month,
+            } => {
+                write!(f, "{} {nth}", weekday_name(*weekday))?;
+                if let Some(month) = month {
+                    write!(f, " {}", month_name(*month))?;
+                }
+                Ok(())
+            }
+            Self::MonthDay { month, day } => write!(f, "{} {day}", month_name(*month)),
+        }
+    }
+}
+
+/// the days selected by the key of a range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IcingaDayRange {
+    /// the first day
+    pub start: IcingaDay,
+    /// the last day (inclusive), only `start` itself if None
+    pub end: Option<IcingaDay>,
+    /// only every nth day starting at `start`
+    pub stride: Option<NonZeroU32>,
+}
+
+impl IcingaDayRange {
+    /// whether the range selects the date
+    #[must_use]
+    pub fn contains(&self, date: Date) -> bool {
+        let Some(end) = self.end else {
+            return match (self.start, self.stride) {
+                (IcingaDay::Date(start), Some(_)) => {
+                    start <= date && self.is_on_stride(start, date)
+                }
+                (start, _) => start.matches(date),
+            };
+        };
+        match (self.start, end) {
+            (IcingaDay::Weekday(start), IcingaDay::Weekday(end)) => {
+                let weekday_index = |weekday: Weekday| i16::from(weekday.number_days_from_monday());
+                let length = weekday_index(end)
+                    .saturating_sub(weekday_index(start))
+                    .rem_euclid(7);
+                let since_start = weekday_index(date.weekday())
+                    .saturating_sub(weekday_index(start))
+                    .rem_euclid(7);
+                since_start <= length
+                    && self.stride.is_none_or(|stride| {
+                        u32::from(since_start.unsigned_abs())
+                            .checked_rem(stride.get())
+                            .is_some_and(|rest| rest == 0)
+                    })
+            }
+            (start, end) => {
+                let (year, month) = (date.year(), date.month());
+                let (this, previous, next) = match start.repetition() {
+                    Repetition::Yearly => (
+                        (year, Month::January),
+                        (year.saturating_sub(1), Month::January),
+                        (year.saturating_add(1), Month::January),
+                    ),
+                    _ => (
+                        (year, month),
+                        previous_month(year, month),
+                        next_month(year, month),
+                    ),
+                };
+                self.contains_in(date, end, previous, this)
+                    || self.contains_in(date, end, this, next)
+            }
+        }
+    }
+
+    /// whether the date is within the occurrence of the range starting in the
+    /// `this` month or year, ranges ending before they start end in `next`
+    fn contains_in(
+        &self,
+        date: Date,
+        end: IcingaDay,
+        (year, month): (i32, Month),
+        (next_year, next_month): (i32, Month),
+    ) -> bool {
+        let Some(start) = self.start.resolve(year, month) else {
+            return false;
+        };
+        let end = match end.resolve(year, month) {
+            Some(end) if end >= start => Some(end),
+            _ if end.repetition() == Repetition::Never => None,
+            _ => end.resolve(next_year, next_month),
+        };
+        end.is_some_and(|end| start <= date && date <= end && self.is_on_stride(start, date))
+    }
+
+    /// whether the date is a multiple of the stride after the start
+    fn is_on_stride(&self, start: Date, date: Date) -> bool {
+        self.stride.is_none_or(|stride| {
+            u32::try_from(date.to_julian_day().saturating_sub(start.to_julian_day()))
+                .ok()
+                .and_then(|days| days.checked_rem(stride.get()))
+                .is_some_and(|rest| rest == 0)
+        })
+    }
+}
+
+impl std::str::FromStr for IcingaDayRange {
+    type Err = TimeRangeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TimeRangeParseError::InvalidDays(s.to_owned());
+        let lowercase = s.to_lowercase();
+        let (days, stride) = match lowercase.split_once('/') {
+            Some((days, stride)) => (
+                days,
+                Some(
+                    stride
+                        .trim()
+                        .parse::<NonZeroU32>()
+                        .map_err(|_err| TimeRangeParseError::InvalidStride(s.to_owned()))?,
+                ),
+            ),
+            None => (lowercase.as_str(), None),
+        };
+        let tokens = days.split_whitespace().collect::<Vec<_>>();
+        let (start, end) = match tokens.iter().position(|token| *token == "-") {
+            Some(separator) => {
+                let (start, end) = tokens.split_at(separator);
+                (start, end.get(1..))
+            }
+            None => (tokens.as_slice(), None),
+        };
+        let start = IcingaDay::parse(start, None).ok_or_else(invalid)?;
+        let end = end
+            .map(|end| IcingaDay::parse(end, Some(start)).ok_or_else(invalid))
+            .transpose()?;
+        if let Some(end) = end
+            && end.repetition() != start.repetition()
+        {
+            return Err(invalid());
+        }
+        Ok(Self { start, end, stride })
+    }
+}
+
+impl std::fmt::Display for IcingaDayRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.start)?;
+        if let Some(end) = self.end {
+            write!(f, " - {end}")?;
+        }
+        if let Some(stride) = self.stride {
+            write!(f, " / {stride}")?;
+        }
+        Ok(())
+    }
+}
+
+/// a range of times of day, offsets from midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IcingaTimeOfDayRange {
+    /// the start of the range
+    pub start: time::Duration,
+    /// the end of the range (exclusive), a range ending before it starts
+    /// ends on the next day
+    pub end: time::Duration,
+}
+
+impl IcingaTimeOfDayRange {
+    /// parse a comma separated list of ranges
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if one of the ranges can not be parsed
+    pub fn parse_all(s: &str) -> Result<Vec<Self>, TimeRangeParseError> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|range| !range.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    /// the segment of this range on a date, the start and end are converted
+    /// to instants separately so they can have different UTC offsets
+    fn on(&self, date: Date, time_zone: &IcingaTimeZone) -> Option<IcingaTimeSegment> {
+        let midnight = PrimitiveDateTime::new(date, time::Time::MIDNIGHT);
+        let end = if self.end < self.start {
+            self.end.checked_add(time::Duration::DAY)?
+        } else {
+            self.end
+        };
+        Some(IcingaTimeSegment {
+            begin: time_zone.assume(midnight.checked_add(self.start)?).into(),
+            end: time_zone.assume(midnight.checked_add(end)?).into(),
+        })
+    }
+}
+
+impl std::str::FromStr for IcingaTimeOfDayRange {
+    type Err = TimeRangeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TimeRangeParseError::InvalidTimes(s.to_owned());
+        let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+        Ok(Self {
+            start: parse_time_of_day(start).ok_or_else(invalid)?,
+            end: parse_time_of_day(end).ok_or_else(invalid)?,
+        })
+    }
+}
+
+impl std::fmt::Display for IcingaTimeOfDayRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let write_time = |f: &mut std::fmt::Formatter<'_>, offset: time::Duration| {
+            let seconds = offset.whole_seconds();
+            write!(
+                f,
+                "{:02}:{:02}",
+                seconds.checked_div(3600).unwrap_or_default(),
+                seconds
+                    .checked_rem(3600)
+                    .and_then(|rest| rest.checked_div(60))
+                    .unwrap_or_default()
+            )?;
+            match seconds.checked_rem(60) {
+                Some(0) | None => Ok(()),
+                Some(rest) => write!(f, ":{rest:02}"),
+            }
+        };
+        write_time(f, self.start)?;
+        write!(f, "-")?;
+        write_time(f, self.end)
+    }
+}
+
+/// a single entry of the ranges of a time period or scheduled downtime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcingaTimeRange {
+    /// the days of the range (the key)
+    pub days: IcingaDayRange,
+    /// the times of day on those days (the value)
+    pub times: Vec<IcingaTimeOfDayRange>,
+}
+
+impl IcingaTimeRange {
+    /// parse the key and value of a range
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if either the key or the value can not be parsed
+    pub fn parse(days: &str, times: &str) -> Result<Self, TimeRangeParseError> {
+        Ok(Self {
+            days: days.parse()?,
+            times: IcingaTimeOfDayRange::parse_all(times)?,
+        })
+    }
+
+    /// parse all entries of a ranges attribute
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if any of the ranges can not be parsed
+    pub fn parse_all(ranges: &BTreeMap<String, String>) -> Result<Vec<Self>, TimeRangeParseError> {
+        ranges
+            .iter()
+            .map(|(days, times)| Self::parse(days, times))
+            .collect()
+    }
+
+    /// the segments of this range overlapping `from` to `to`, cut off at both
+    /// ends, in chronological order and evaluated in the given time zone
+    ///
+    /// segments of a single range can overlap, see [merge_segments]
+    #[must_use]
+    pub fn segments(
+        &self,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+        time_zone: impl Into<IcingaTimeZone>,
+    ) -> Vec<IcingaTimeSegment> {
+        let time_zone = time_zone.into();
+        let mut segments = Vec::new();
+        // the local dates are at most a day before or after the UTC ones
+        let from_date = from.to_offset(UtcOffset::UTC).date();
+        let first = from_date.previous_day().unwrap_or(from_date);
+        let to_date = to.to_offset(UtcOffset::UTC).date();
+        let last = to_date.next_day().unwrap_or(to_date);
+        // ranges ending before they start reach into the next day
+        let mut date = first.previous_day();
+        while let Some(current) = date
+            && current <= last
+        {
+            if self.days.contains(current) {
+                segments.extend(
+                    self.times
+                        .iter()
+                        .filter_map(|times| times.on(current, &time_zone))
+                        .filter_map(|segment| segment.clamp(from, to)),
+                );
+            }
+            date = current.next_day();
+        }
+        segments
+    }
+}
+
+/// a time span during which a time period is active
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct IcingaTimeSegment {
+    /// the start of the segment
+    pub begin: IcingaTimestamp,
+    /// the end of the segment (exclusive)
+    pub end: IcingaTimestamp,
+}
+
+impl IcingaTimeSegment {
+    /// whether the instant is within the segment
+    #[must_use]
+    pub fn contains(&self, instant: OffsetDateTime) -> bool {
+        let instant = IcingaTimestamp::from(instant);
+        self.begin <= instant && instant < self.end
+    }
+
+    /// the part of the segment between `from` and `to`, None if there is none
+    fn clamp(self, from: OffsetDateTime, to: OffsetDateTime) -> Option<Self> {
+        let begin = self.begin.max(from.into());
+        let end = self.end.min(to.into());
+        (begin < end).then_some(Self { begin, end })
+    }
+}
+
+/// sort segments and merge overlapping and adjacent ones
+#[must_use]
+pub fn merge_segments(mut segments: Vec<IcingaTimeSegment>) -> Vec<IcingaTimeSegment> {
+    segments.sort();
+    let mut merged: Vec<IcingaTimeSegment> = Vec::with_capacity(segments.len());
+    for segment in segments {
+        if let Some(last) = merged.last_mut()
+            && segment.begin <= last.end
+        {
+            last.end = last.end.max(segment.end);
+        } else {
+            merged.push(segment);
+        }
+    }
+    merged
+}
+
+/// remove the time covered by `removed` (sorted and merged) from `segments`
+fn subtract_segments(
+    segments: Vec<IcingaTimeSegment>,
+    removed: &[IcingaTimeSegment],
+) -> Vec<IcingaTimeSegment> {
+    let mut remaining = Vec::with_capacity(segments.len());
+    for segment in segments {
+        let mut begin = segment.begin;
+        for removed in removed {
+            if removed.end <= begin || removed.begin >= segment.end {
+                continue;
+            }
+            if removed.begin > begin {
+                remaining.push(IcingaTimeSegment {
+                    begin,
+                    end: removed.begin,
+                });
+            }
+            begin = begin.max(removed.end);
+        }
+        if begin < segment.end {
+            remaining.push(IcingaTimeSegment {
+                begin,
+                end: segment.end,
+            });
+        }
+    }
+    remaining
+}
+
+/// the parsed ranges and references to other time periods of a time period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcingaTimePeriodDefinition {
+    /// the ranges of the time period
+    pub ranges: Vec<IcingaTimeRange>,
+    /// time periods whose segments are added
+    pub includes: Vec<IcingaTimePeriodName>,
+    /// time periods whose segments are removed
+    pub excludes: Vec<IcingaTimePeriodName>,
+    /// whether includes are added after excludes are removed (so included
+    /// segments are never excluded) instead of before
+    pub prefer_includes: bool,
+}
+
+impl IcingaTimePeriodDefinition {
+    /// parse the ranges of a time period
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if any of the ranges can not be parsed
+    pub fn from_time_period(time_period: &IcingaTimePeriod) -> Result<Self, TimeRangeParseError> {
+        Ok(Self {
+            ranges: IcingaTimeRange::parse_all(&time_period.ranges)?,
+            includes: time_period.includes.clone(),
+            excludes: time_period.excludes.clone(),
+            prefer_includes: time_period.prefer_includes.unwrap_or(true),
+        })
+    }
+}
+
+/// a set of time periods which can include and exclude each other,
+/// evaluated in the time zone of the Icinga instance
+#[derive(Debug, Clone)]
+pub struct IcingaTimePeriods {
+    /// the time periods by name
+    periods: BTreeMap<IcingaTimePeriodName, IcingaTimePeriodDefinition>,
+    /// the time zone of the Icinga instance
+    time_zone: IcingaTimeZone,
+}
+
+impl IcingaTimePeriods {
+    /// an empty set of time periods evaluated in the given time zone
+    #[must_use]
+    pub const fn new(time_zone: IcingaTimeZone) -> Self {
+        Self {
+            periods: BTreeMap::new(),
+            time_zone,
+        }
+    }
+
+    /// the time periods returned by the API, evaluated in the given time
+    /// zone
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if a range of any of the time periods can not be
+    /// parsed
+    pub fn from_time_periods(
+        time_periods: impl IntoIterator<Item = impl Borrow<IcingaTimePeriod>>,
+        time_zone: IcingaTimeZone,
+    ) -> Result<Self, TimePeriodError> {
+        let mut periods = Self::new(time_zone);
+        for time_period in time_periods {
+            let time_period = time_period.borrow();
+            let name = IcingaTimePeriodName(time_period.custom_var.config_object.name.clone());
+            let definition = IcingaTimePeriodDefinition::from_time_period(time_period)
+                .map_err(|e| TimePeriodError::InvalidRange(name.clone(), e))?;
+            periods.insert(name, definition);
+        }
+        Ok(periods)
+    }
+
+    /// add or replace a time period
+    pub fn insert(&mut self, name: IcingaTimePeriodName, definition: IcingaTimePeriodDefinition) {
+        self.periods.insert(name, definition);
+    }
+
+    /// the segments of a time period between `from` and `to`, sorted and
+    /// merged, including and excluding other time periods
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the time period or one of the time periods
+    /// it references is unknown or a time period references itself
+    pub fn segments(
+        &self,
+        name: &IcingaTimePeriodName,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+    ) -> Result<Vec<IcingaTimeSegment>, TimePeriodError> {
+        self.resolve(name, from, to, &mut Vec::new())
+    }
+
+    /// whether the instant is inside the time period
+    ///
+    /// # Errors
+    ///
+    /// this returns an error if the time period or one of the time periods
+    /// it references is unknown or a time period references itself
+    pub fn is_inside(
+        &self,
+        name: &IcingaTimePeriodName,
+        instant: OffsetDateTime,
+    ) -> Result<bool, TimePeriodError> {
+        let to = instant.saturating_add(time::Duration::SECOND);
+        Ok(self
+            .segments(name, instant, to)?
+            .iter()
+            .any(|segment| segment.contains(instant)))
+    }
+
+    /// the segments of a time period, `visiting` contains the time periods
+    /// currently being resolved to detect cycles
+    fn resolve(
+        &self,
+        name: &IcingaTimePeriodName,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+        visiting: &mut Vec<IcingaTimePeriodName>,
+    ) -> Result<Vec<IcingaTimeSegment>, TimePeriodError> {
+        if visiting.contains(name) {
+            return Err(TimePeriodError::CircularReference(name.clone()));
+        }
+        let definition = self
+            .periods
+            .get(name)
+            .ok_or_else(|| TimePeriodError::UnknownTimePeriod(name.clone()))?;
+        visiting.push(name.clone());
+        let own = definition
+            .ranges
+            .iter()
+            .flat_map(|range| range.segments(from, to, &self.time_zone))
+            .collect::<Vec<_>>();
+        let mut included = Vec::new();
+        for include in &definition.includes {
+            included.extend(self.resolve(include, from, to, visiting)?);
+        }
+        let mut excluded = Vec::new();
+        for exclude in &definition.excludes {
+            excluded.extend(self.resolve(exclude, from, to, visiting)?);
+        }
+        visiting.pop();
+        let excluded = merge_segments(excluded);
+        Ok(if definition.prefer_includes {
+            let mut segments = subtract_segments(merge_segments(own), &excluded);
+            segments.extend(included);
+            merge_segments(segments)
+        } else {
+            let mut segments = own;
+            segments.extend(included);
+            subtract_segments(merge_segments(segments), &excluded)
+        })
+    }
+}
+
+/// the name of a weekday in the range syntax
+fn weekday_name(weekday: Weekday) -> &'static str {
+    WEEKDAYS
+        .iter()
+        .find(|(candidate, _)| *candidate == weekday)
+        .map_or("", |(_, name)| name)
+}
+
+/// parse the name of a weekday
+fn parse_weekday(name: &str) -> Option<Weekday> {
+    WEEKDAYS
+        .iter()
+        .find(|(_, candidate)| *candidate == name)
+        .map(|(weekday, _)| *weekday)
+}
+
+/// the name of a month in the range syntax
+fn month_name(month: Month) -> &'static str {
+    MONTHS
+        .iter()
+        .find(|(candidate, _)| *candidate == month)
+        .map_or("", |(_, name)| name)
+}
+
+/// parse the name of a month
+fn parse_month(name: &str) -> Option<Month> {
+    MONTHS
+        .iter()
+        .find(|(_, candidate)| *candidate == name)
+        .map(|(month, _)| *month)
+}
+
+/// parse a non-zero number between `-max` and `max`
+fn parse_number(number: &str, max: i8) -> Option<i8> {
+    number
+        .parse::<i8>()
+        .ok()
+        .filter(|number| *number != 0 && number.unsigned_abs() <= max.unsigned_abs())
+}
+
+/// parse a date in the `YYYY-MM-DD` format
+fn parse_date(date: &str) -> Option<Date> {
+    let mut parts = date.split('-');
+    let (Some(year), Some(month), Some(day), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    Date::from_calendar_date(
+        year.parse().ok()?,
+        Month::try_from(month.parse::<u8>().ok()?).ok()?,
+        day.parse().ok()?,
+    )
+    .ok()
+}
+
+/// parse a time of day in the `HH:MM` or `HH:MM:SS` format into an offset
+/// from midnight, `24:00` is the end of the day
+fn parse_time_of_day(time: &str) -> Option<time::Duration> {
+    let mut parts = time.trim().split(':');
+    let (Some(hours), Some(minutes), seconds, None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    let hours = hours.parse::<u8>().ok()?;
+    let minutes = minutes.parse::<u8>().ok()?;
+    let seconds = seconds.map_or(Some(0), |seconds| seconds.parse::<u8>().ok())?;
+    if minutes >= 60 || seconds >= 60 || hours > 24 || (hours == 24 && (minutes, seconds) != (0, 0))
+    {
+        return None;
+    }
+    time::Duration::hours(i64::from(hours))
+        .checked_add(time::Duration::minutes(i64::from(minutes)))?
+        .checked_add(time::Duration::seconds(i64::from(seconds)))
+}
+
+/// the month before the given one
+fn previous_month(year: i32, month: Month) -> (i32, Month) {
+    if month == Month::January {
+        (year.saturating_sub(1), Month::December)
+    } else {
+        (year, month.previous())
+    }
+}
+
+/// the month after the given one
+fn next_month(year: i32, month: Month) -> (i32, Month) {
+    if month == Month::December {
+        (year.saturating_add(1), Month::January)
+    } else {
+        (year, month.next())
+    }
+}
+
+/// a day of a month, negative days count from the end of the month
+fn day_of_month(year: i32, month: Month, day: i8) -> Option<Date> {
+    let day = if day < 0 {
+        i16::from(month.length(year))
+            .checked_add(1)?
+            .checked_add(i16::from(day))?
+    } else {
+        i16::from(day)
+    };
+    Date::from_calendar_date(year, month, u8::try_from(day).ok()?).ok()
+}
+
+/// the nth weekday of a month, negative values count from the end of the month
+fn nth_weekday(year: i32, month: Month, weekday: Weekday, nth: i8) -> Option<Date> {
+    let length = month.length(year);
+    let weekday_index = |date: Date| i16::from(date.weekday().number_days_from_monday());
+    let target = i16::from(weekday.number_days_from_monday());
+    let day = if nth > 0 {
+        let first = Date::from_calendar_date(year, month, 1).ok()?;
+        target
+            .checked_sub(weekday_index(first))?
+            .checked_rem_euclid(7)?
+            .checked_add(1)?
+            .checked_add(i16::from(nth).checked_sub(1)?.checked_mul(7)?)?
+    } else {
+        let last = Date::from_calendar_date(year, month, length).ok()?;
+        i16::from(length)
+            .checked_sub(
+                weekday_index(last)
+                    .checked_sub(target)?
+                    .checked_rem_euclid(7)?,
+            )?
+            .checked_sub(
+                i16::from(nth)
+                    .checked_neg()?
+                    .checked_sub(1)?
+                    .checked_mul(7)?,
+            )?
+    };
+    Date::from_calendar_date(year, month, u8::try_from(day).ok()?).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+    use time::macros::{date, datetime};
+
+    #[test]
+    fn test_parse_day_ranges() -> Result<(), Box<dyn Error>> {
+        for range in [
+            "2024-12-24",
+            "2024-12-24 - 2025-01-06",
+            "2024-01-01 / 7",
+            "day 1",
+            "day -1",
+            "monday",
+            "monday - friday",
+            "monday 3",
+            "friday -1",
+            "monday -1 november",
+            "january 1",
+            "february -1",
+            "day 1 - day 15 / 5",
+            "july 10 - august 15 / 2",
+            "friday 2 - day 15 / 3",
+            "day 1 - day -1",
+        ] {
+            assert_eq!(range.parse::<IcingaDayRange>()?.to_string(), range);
+        }
+        assert_eq!(
+            "Day 1 - 15".parse::<IcingaDayRange>()?,
+            "day 1 - day 15".parse::<IcingaDayRange>()?
+        );
+        assert_eq!(
+            "day 1 - -1".parse::<IcingaDayRange>()?,
+            "day 1 - day -1".parse::<IcingaDayRange>()?
+        );
+        for range in [
+            "",
+            "day 0",
+            "monday 6",
+            "someday",
+            "monday - day 3",
+            "x - y",
+        ] {
+            assert_eq!(
+                range.parse::<IcingaDayRange>(),
+                Err(TimeRangeParseError::InvalidDays(range.to_owned()))
+            );
+        }
+        assert_eq!(
+            "day 1 / 0".parse::<IcingaDayRange>(),
+            Err(TimeRangeParseError::InvalidStride("day 1 / 0".to_owned()))
+        );
+        assert_eq!(
+            IcingaTimeOfDayRange::parse_all("08:00-12:00, 13:00-17:30:15,22:00-24:00")?
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["08:00-12:00", "13:00-17:30:15", "22:00-24:00"]
+        );
+        assert_eq!(
+            IcingaTimeOfDayRange::parse_all("08:00-24:01"),
+            Err(TimeRangeParseError::InvalidTimes("08:00-24:01".to_owned()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_day_range_contains() -> Result<(), Box<dyn Error>> {
+        let cases: [(&str, &[Date], &[Date]); 9] = [
+            (
+                "monday -1 november",
+                &[date!(2024 - 11 - 25), date!(2025 - 11 - 24)],
+                &[date!(2024 - 11 - 18), date!(2024 - 12 - 30)],
+            ),
+            (
+                "day -1",
+                &[date!(2024 - 02 - 29), date!(2024 - 04 - 30)],
+                &[date!(2023 - 02 - 27), date!(2024 - 05 - 30)],
+            ),
+            (
+                "day 1 - 15 / 5",
+                &[
+                    date!(2024 - 03 - 01),
+                    date!(2024 - 03 - 06),
+                    date!(2024 - 03 - 11),
+                ],
+                &[date!(2024 - 03 - 02), date!(2024 - 03 - 16)],
+            ),
+            (
+                "day 25 - 5",
+                &[date!(2024 - 12 - 31), date!(2025 - 01 - 05)],
+                &[date!(2025 - 01 - 06), date!(2025 - 01 - 24)],
+            ),
+            (
+                "november 15 - february 15",
+                &[date!(2024 - 11 - 15), date!(2025 - 01 - 10)],
+                &[date!(2025 - 02 - 16), date!(2025 - 06 - 01)],
+            ),
+            (
+                "friday - monday",
+                &[
+                    date!(2024 - 06 - 07),
+                    date!(2024 - 06 - 09),
+                    date!(2024 - 06 - 10),
+                ],
+                &[date!(2024 - 06 - 11), date!(2024 - 06 - 13)],
+            ),
+            (
+                "2024-01-01 / 7",
+                &[date!(2024 - 01 - 01), date!(2024 - 01 - 15)],
+                &[date!(2023 - 12 - 25), date!(2024 - 01 - 16)],
+            ),
+            (
+                "2024-12-24 - 2025-01-06",
+                &[date!(2024 - 12 - 24), date!(2025 - 01 - 06)],
+                &[date!(2024 - 12 - 23), date!(2025 - 01 - 07)],
+            ),
+            (
+                "monday 1 - thursday 4",
+                &[date!(2024 - 10 - 07), date!(2024 - 10 - 24)],
+                &[date!(2024 - 10 - 06), date!(2024 - 10 - 25)],
+            ),
+        ];
+        for (range, inside, outside) in cases {
+            let parsed: IcingaDayRange = range.parse()?;
+            for date in inside {
+                assert!(parsed.contains(*date), "{range} should contain {date}");
+            }
+            for date in outside {
+                assert!(!parsed.contains(*date), "{range} should not contain {date}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_time_periods() -> Result<(), Box<dyn Error>> {
+        let name = |name: &str| IcingaTimePeriodName(name.to_owned());
+        let definition = |ranges: &[(&str, &str)],
+                          includes: &[&str],
+                          excludes: &[&str]|
+         -> Result<IcingaTimePeriodDefinition, TimeRangeParseError> {
+            Ok(IcingaTimePeriodDefinition {
+                ranges: ranges
+                    .iter()
+                    .map(|(days, times)| IcingaTimeRange::parse(days, times))
+                    .collect::<Result<_, _>>()?,
+                includes: includes.iter().map(|n| name(n)).collect(),
+                excludes: excludes.iter().map(|n| name(n)).collect(),
+                prefer_includes: true,
+            })
+        };
+        let mut periods = IcingaTimePeriods::new(UtcOffset::from_hms(1, 0, 0)?.into());
+        periods.insert(
+            name("workhours"),
+            definition(&[("monday - friday", "09:00-17:00")], &[], &["holidays"])?,
+        );
+        periods.insert(
+            name("holidays"),
+            definition(&[("2024-12-24 - 2024-12-26", "00:00-24:00")], &[], &[])?,
+        );
+        periods.insert(
+            name("oncall"),
+            definition(&[("day 1 - 31", "22:00-06:00")], &["workhours"], &[])?,
+        );
+        assert!(periods.is_inside(&name("workhours"), datetime!(2024-12-23 08:30 UTC))?);
+        assert!(!periods.is_inside(&name("workhours"), datetime!(2024-12-23 16:00 UTC))?);
+        assert!(!periods.is_inside(&name("workhours"), datetime!(2024-12-24 10:00 UTC))?);
+        assert!(periods.is_inside(&name("oncall"), datetime!(2024-12-01 04:00 UTC))?);
+        assert_eq!(
+            periods.segments(
+                &name("oncall"),
+                datetime!(2024-12-23 00:00 +1),
+                datetime!(2024-12-25 00:00 +1)
+            )?,
+            vec![
+                IcingaTimeSegment {
+                    begin: datetime!(2024-12-23 00:00 +1).into(),
+                    end: datetime!(2024-12-23 06:00 +1).into(),
+                },
+                IcingaTimeSegment {
+                    begin: datetime!(2024-12-23 09:00 +1).into(),
+                    end: datetime!(2024-12-23 17:00 +1).into(),
+                },
+                IcingaTimeSegment {
+                    begin: datetime!(2024-12-23 22:00 +1).into(),
+                    end: datetime!(2024-12-24 06:00 +1).into(),
+                },
+                IcingaTimeSegment {
+                    begin: datetime!(2024-12-24 22:00 +1).into(),
+                    end: datetime!(2024-12-25 00:00 +1).into(),
+                },
+            ]
+        );
+        periods.insert(name("loop"), definition(&[], &["loop"], &[])?);
+        assert_eq!(
+            periods.is_inside(&name("loop"), datetime!(2024-12-23 08:30 UTC)),
+            Err(TimePeriodError::CircularReference(name("loop")))
+        );
+        assert_eq!(
+            periods.is_inside(&name("missing"), datetime!(2024-12-23 08:30 UTC)),
+            Err(TimePeriodError::UnknownTimePeriod(name("missing")))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_time_periods_across_daylight_saving_time() -> Result<(), Box<dyn Error>> {
+        let name = IcingaTimePeriodName("workhours".to_owned());
+        // central european time in 2024
+        let (winter, summer) = (UtcOffset::from_hms(1, 0, 0)?, UtcOffset::from_hms(2, 0, 0)?);
+        let mut periods = IcingaTimePeriods::new(IcingaTimeZone::new(move |local| {
+            if (datetime!(2024-03-31 03:00)..datetime!(2024-10-27 03:00)).contains(&local) {
+                summer
+            } else {
+                winter
+            }
+        }));
+        periods.insert(
+            name.clone(),
+            IcingaTimePeriodDefinition {
+                ranges: vec![
+                    IcingaTimeRange::parse("monday - friday", "09:00-17:00")?,
+                    IcingaTimeRange::parse("2024-03-31", "01:00-04:00")?,
+                ],
+                includes: Vec::new(),
+                excludes: Vec::new(),
+                prefer_includes: true,
+            },
+        );
+        assert_eq!(
+            periods.segments(
+                &name,
+                datetime!(2024-03-29 00:00 UTC),
+                datetime!(2024-04-02 00:00 UTC)
+            )?,
+            vec![
+                IcingaTimeSegment {
+                    begin: datetime!(2024-03-29 08:00 UTC).into(),
+                    end: datetime!(2024-03-29 16:00 UTC).into(),
+                },
+                IcingaTimeSegment {
+                    begin: datetime!(2024-03-31 00:00 UTC).into(),
+                    end: datetime!(2024-03-31 02:00 UTC).into(),
+                },
+                IcingaTimeSegment {
+                    begin: datetime!(2024-04-01 07:00 UTC).into(),
+                    end: datetime!(2024-04-01 15:00 UTC).into(),
+                },
+            ]
+        );
+        assert!(periods.is_inside(&name, datetime!(2024-04-01 07:30 UTC))?);
+        assert!(!periods.is_inside(&name, datetime!(2024-04-01 15:30 UTC))?);
+        Ok(())
+    }
+}
@@ -0,0 +1,331 @@
+//! Lossless Icinga timestamps
+//!
+//! Icinga represents timestamps as unix timestamps in floating point seconds
+//! (usually with 6 digits after the decimal point) and uses `0` for timestamps
+//! that are not set. [IcingaTimestamp] keeps the original value along with
+//! whether it was an integer, a floating point number or `null` so it
+//! serializes back to the same JSON value it was deserialized from and
+//! converts to [time::OffsetDateTime] on demand.
+
+use serde::{Deserialize, Serialize};
+
+/// the number of microseconds in a second, the precision of Icinga
+/// timestamps
+const MICROSECONDS_PER_SECOND: i64 = 1_000_000;
+
+/// the number of microseconds in a second as a float
+const MICROSECONDS_PER_SECOND_F64: f64 = 1_000_000f64;
+
+/// a unix timestamp as used by the Icinga API
+///
+/// `0`, `null` and (with `#[serde(default)]`) absent values are all the
+/// unset timestamp [IcingaTimestamp::UNSET]
+///
+/// equality, ordering and hashing use the total order of the floating point
+/// value, not the form it was deserialized from
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IcingaTimestamp {
+    /// the unix timestamp seconds
+    seconds: f64,
+    /// the JSON form the timestamp is serialized in
+    form: IcingaTimestampForm,
+}
+
+/// the JSON form of a timestamp
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum IcingaTimestampForm {
+    /// a floating point number, used for all timestamps not deserialized
+    /// from JSON
+    #[default]
+    Float,
+    /// an integer
+    Integer,
+    /// `null`
+    Null,
+}
+
+impl IcingaTimestamp {
+    /// the timestamp Icinga uses for timestamps that are not set
+    pub const UNSET: Self = Self::from_unix_timestamp(0f64);
+
+    /// create a timestamp from unix timestamp seconds
+    #[must_use]
+    pub const fn from_unix_timestamp(seconds: f64) -> Self {
+        Self {
+            seconds,
+            form: IcingaTimestampForm::Float,
+        }
+    }
+
+    /// create a timestamp from unix timestamp microseconds
+    ///
+    /// the whole seconds and the fraction are converted separately, the
+    /// inverse of [Self::to_unix_timestamp_micros]
+    #[must_use]
+    #[expect(
+        clippy::cast_precision_loss,
+        clippy::as_conversions,
+        reason = "unix timestamps in seconds and microseconds in a second are far below the precision limit of f64"
+    )]
+    pub fn from_unix_timestamp_micros(micros: i64) -> Self {
+        let seconds = micros.div_euclid(MICROSECONDS_PER_SECOND) as f64;
+        let fraction = micros.rem_euclid(MICROSECONDS_PER_SECOND) as f64;
+        Self::from_unix_timestamp(seconds + fraction / MICROSECONDS_PER_SECOND_F64)
+    }
+
+    /// the current time
+    #[must_use]
+    pub fn now() -> Self {
+        Self::from(time::OffsetDateTime::now_utc())
+    }
+
+    /// the unix timestamp seconds exactly as received from Icinga
+    #[must_use]
+    pub const fn as_unix_timestamp(self) -> f64 {
+        self.seconds
+    }
+
+    /// whether the timestamp is set (not `0`)
+    #[must_use]
+    pub fn is_set(self) -> bool {
+        self.seconds != 0f64
+    }
+
+    /// the timestamp in unix timestamp microseconds, the precision Icinga
+    /// uses
+    ///
+    /// the whole seconds and the fraction are converted separately so the
+    /// microseconds are not distorted by the limited precision of the float
+    ///
+    /// returns None if the timestamp is not set or does not fit
+    #[must_use]
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::as_conversions,
+        reason = "the values are integral after trunc and round, out of range seconds saturate and fail the checked multiplication"
+    )]
+    pub fn to_unix_timestamp_micros(self) -> Option<i64> {
+        if !self.is_set() || !self.seconds.is_finite() {
+            return None;
+        }
+        let whole = self.seconds.trunc();
+        let fraction = ((self.seconds - whole) * MICROSECONDS_PER_SECOND_F64).round();
+        (whole as i64)
+            .checked_mul(MICROSECONDS_PER_SECOND)?
+            .checked_add(fraction as i64)
+    }
+
+    /// the timestamp as a [time::OffsetDateTime]
+    ///
+    /// returns None if the timestamp is not set or outside the range
+    /// supported by [time::OffsetDateTime]
+    #[must_use]
+    pub fn to_offset_date_time(self) -> Option<time::OffsetDateTime> {
+        let micros = self.to_unix_timestamp_micros()?;
+        time::OffsetDateTime::from_unix_timestamp_nanos(i128::from(micros).checked_mul(1_000)?).ok()
+    }
+}
+
+impl PartialEq for IcingaTimestamp {
+    fn eq(&self, other: &Self) -> bool {
+        self.seconds.total_cmp(&other.seconds).is_eq()
+    }
+}
+
+impl Eq for IcingaTimestamp {}
+
+impl PartialOrd for IcingaTimestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for IcingaTimestamp {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.seconds.total_cmp(&other.seconds)
+    }
+}
+
+impl std::hash::Hash for IcingaTimestamp {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.seconds.to_bits().hash(state);
+    }
+}
+
+impl std::fmt::Display for IcingaTimestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.seconds)
+    }
+}
+
+impl From<time::OffsetDateTime> for IcingaTimestamp {
+    fn from(value: time::OffsetDateTime) -> Self {
+        Self::from_unix_timestamp_micros(
+            value
+                .unix_timestamp()
+                .saturating_mul(MICROSECONDS_PER_SECOND)
+                .saturating_add(i64::from(value.microsecond())),
+        )
+    }
+}
+
+impl From<Option<time::OffsetDateTime>> for IcingaTimestamp {
+    fn from(value: Option<time::OffsetDateTime>) -> Self {
+        value.map_or(Self::UNSET, Self::from)
+    }
+}
+
+impl From<IcingaTimestamp> for Option<time::OffsetDateTime> {
+    fn from(value: IcingaTimestamp) -> Self {
+        value.to_offset_date_time()
+    }
+}
+
+impl Serialize for IcingaTimestamp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self.form {
+            IcingaTimestampForm::Float => serializer.serialize_f64(self.seconds),
+            #[expect(
+                clippy::cast_possible_truncation,
+                clippy::as_conversions,
+                reason = "the seconds were deserialized from an integer so they are integral"
+            )]
+            IcingaTimestampForm::Integer => serializer.serialize_i64(self.seconds as i64),
+            IcingaTimestampForm::Null => serializer.serialize_none(),
+        }
+    }
+}
+
+/// deserializes a timestamp remembering its JSON form
+struct IcingaTimestampVisitor;
+
+impl<'de> serde::de::Visitor<'de> for IcingaTimestampVisitor {
+    type Value = IcingaTimestamp;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("a unix timestamp or null")
+    }
+
+    #[expect(
+        clippy::cast_precision_loss,
+        clippy::as_conversions,
+        reason = "unix timestamps in seconds are far below the precision limit of f64"
+    )]
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(IcingaTimestamp {
+            seconds: v as f64,
+            form: IcingaTimestampForm::Integer,
+        })
+    }
+
+    #[expect(
+        clippy::cast_precision_loss,
+        clippy::as_conversions,
+        reason = "unix timestamps in seconds are far below the precision limit of f64"
+    )]
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(IcingaTimestamp {
+            seconds: v as f64,
+            form: IcingaTimestampForm::Integer,
+        })
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(IcingaTimestamp::from_unix_timestamp(v))
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(IcingaTimestamp {
+            seconds: 0f64,
+            form: IcingaTimestampForm::Null,
+        })
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        self.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for IcingaTimestamp {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(IcingaTimestampVisitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::error::Error;
+
+    #[test]
+    fn test_icinga_timestamp_round_trip() -> Result<(), Box<dyn Error>> {
+        for json in [
+            "1700000000.123456",
+            "1699999999.999999",
+            "0.0",
+            "0",
+            "1700000000",
+            "null",
+        ] {
+            let timestamp: IcingaTimestamp = serde_json::from_str(json)?;
+            assert_eq!(serde_json::to_string(&timestamp)?, json);
+        }
+        for (json, micros) in [
+            ("1700000000.123456", 1_700_000_000_123_456),
+            ("1700000000.000001", 1_700_000_000_000_001),
+            ("1699999999.999999", 1_699_999_999_999_999),
+            ("1700000000.987654", 1_700_000_000_987_654),
+            ("-1.5", -1_500_000),
+        ] {
+            let timestamp: IcingaTimestamp = serde_json::from_str(json)?;
+            assert_eq!(timestamp.to_unix_timestamp_micros(), Some(micros), "{json}");
+            let date_time = time::OffsetDateTime::from_unix_timestamp_nanos(
+                i128::from(micros).saturating_mul(1_000),
+            )?;
+            assert_eq!(timestamp.to_offset_date_time(), Some(date_time), "{json}");
+            assert_eq!(IcingaTimestamp::from(date_time), timestamp, "{json}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_icinga_timestamp_unset() -> Result<(), Box<dyn Error>> {
+        for json in ["0", "0.0", "null"] {
+            let timestamp: IcingaTimestamp = serde_json::from_str(json)?;
+            assert_eq!(timestamp, IcingaTimestamp::UNSET);
+            assert!(!timestamp.is_set(), "{json} should be unset");
+            assert_eq!(timestamp.to_offset_date_time(), None);
+        }
+        assert_eq!(IcingaTimestamp::from(None), IcingaTimestamp::UNSET);
+        Ok(())
+    }
+}